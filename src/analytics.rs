@@ -0,0 +1,632 @@
+//! Breakdowns of typing by the kind of character typed.
+
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    date,
+    stats::{self, Session, Totals},
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CharClass {
+    Lowercase,
+    Capital,
+    Digit,
+    Punctuation,
+    Space,
+}
+
+impl CharClass {
+    pub const ALL: [Self; 5] = [
+        Self::Lowercase,
+        Self::Capital,
+        Self::Digit,
+        Self::Punctuation,
+        Self::Space,
+    ];
+
+    pub fn of(c: char) -> Self {
+        match c {
+            c if c.is_whitespace() => Self::Space,
+            c if c.is_numeric() => Self::Digit,
+            c if c.is_uppercase() => Self::Capital,
+            c if c.is_alphabetic() => Self::Lowercase,
+            _ => Self::Punctuation,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Lowercase => "lowercase",
+            Self::Capital => "capitals",
+            Self::Digit => "digits",
+            Self::Punctuation => "punctuation",
+            Self::Space => "spaces",
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Tally {
+    /// Characters typed, right or wrong.
+    pub typed: usize,
+    pub mistakes: usize,
+    /// Characters with a previous keystroke to time them from.
+    pub timed: usize,
+    /// Time spent on the timed characters.
+    pub elapsed_ms: u64,
+}
+
+impl Tally {
+    pub fn add(&mut self, other: &Tally) {
+        self.typed += other.typed;
+        self.mistakes += other.mistakes;
+        self.timed += other.timed;
+        self.elapsed_ms += other.elapsed_ms;
+    }
+
+    pub fn wpm(&self) -> f64 {
+        stats::wpm(self.timed, Duration::from_millis(self.elapsed_ms))
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        match self.typed {
+            0 => 1.0,
+            n => (n - self.mistakes) as f64 / n as f64,
+        }
+    }
+}
+
+/// Per-class tallies for a session, or any number of them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassStats(BTreeMap<CharClass, Tally>);
+
+impl ClassStats {
+    /// Counts a keystroke meant to type `expected`, taking `elapsed` since
+    /// the previous one if there was one.
+    pub fn record(&mut self, expected: char, mistake: bool, elapsed: Option<Duration>) {
+        let tally = self.0.entry(CharClass::of(expected)).or_default();
+        tally.typed += 1;
+        tally.mistakes += mistake as usize;
+        if let Some(elapsed) = elapsed {
+            tally.timed += 1;
+            tally.elapsed_ms += elapsed.as_millis() as u64;
+        }
+    }
+
+    pub fn merge(&mut self, other: &ClassStats) {
+        for (&class, tally) in &other.0 {
+            self.0.entry(class).or_default().add(tally);
+        }
+    }
+
+    pub fn get(&self, class: CharClass) -> Tally {
+        self.0.get(&class).copied().unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
+
+/// How keystrokes made with Shift held compare to those without, going by
+/// the modifiers the terminal reports.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ShiftStats {
+    pub shifted: Tally,
+    pub unshifted: Tally,
+    /// Mistakes made typing each capital letter.
+    pub fumbled: BTreeMap<char, usize>,
+}
+
+impl ShiftStats {
+    pub fn record(
+        &mut self,
+        expected: char,
+        shifted: bool,
+        mistake: bool,
+        elapsed: Option<Duration>,
+    ) {
+        let tally = match shifted {
+            true => &mut self.shifted,
+            false => &mut self.unshifted,
+        };
+        tally.typed += 1;
+        tally.mistakes += mistake as usize;
+        if let Some(elapsed) = elapsed {
+            tally.timed += 1;
+            tally.elapsed_ms += elapsed.as_millis() as u64;
+        }
+        if mistake && expected.is_uppercase() {
+            *self.fumbled.entry(expected).or_default() += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &ShiftStats) {
+        self.shifted.add(&other.shifted);
+        self.unshifted.add(&other.unshifted);
+        for (&c, &n) in &other.fumbled {
+            *self.fumbled.entry(c).or_default() += n;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shifted.typed == 0 && self.unshifted.typed == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Average extra time a shifted keystroke takes over an unshifted one.
+    pub fn penalty(&self) -> Option<Duration> {
+        let average = |t: &Tally| (t.timed > 0).then(|| t.elapsed_ms as f64 / t.timed as f64);
+        let penalty = average(&self.shifted)? - average(&self.unshifted)?;
+        Some(Duration::from_secs_f64(penalty.max(0.0) / 1000.0))
+    }
+
+    /// Capitals with the most mistakes, most first.
+    pub fn most_fumbled(&self, n: usize) -> Vec<(char, usize)> {
+        let mut fumbled: Vec<_> = self.fumbled.iter().map(|(&c, &n)| (c, n)).collect();
+        fumbled.sort_by_key(|&(c, n)| (std::cmp::Reverse(n), c));
+        fumbled.truncate(n);
+        fumbled
+    }
+}
+
+pub fn shift_report(stats: &ShiftStats) -> String {
+    let mut out = match stats.penalty() {
+        Some(penalty) => format!(
+            "shifted keys take {}ms longer on average ({:.1}% vs {:.1}% accuracy)\n",
+            penalty.as_millis(),
+            stats.shifted.accuracy() * 100.0,
+            stats.unshifted.accuracy() * 100.0,
+        ),
+        None => "not enough shifted keystrokes to compare\n".to_owned(),
+    };
+    let fumbled = stats.most_fumbled(5);
+    if !fumbled.is_empty() {
+        let list: Vec<_> = fumbled.iter().map(|(c, n)| format!("{c} ({n})")).collect();
+        out += &format!("most fumbled capitals: {}\n", list.join(", "));
+    }
+    out
+}
+
+/// Mistakes made on each pair of letters, keyed by the pair in lowercase
+/// and counted against the second letter.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Bigrams(BTreeMap<String, usize>);
+
+impl Bigrams {
+    pub fn record(&mut self, previous: Option<char>, expected: char, mistake: bool) {
+        let Some(previous) = previous else {
+            return;
+        };
+        if mistake && previous.is_alphabetic() && expected.is_alphabetic() {
+            let pair = previous
+                .to_lowercase()
+                .chain(expected.to_lowercase())
+                .collect();
+            *self.0.entry(pair).or_default() += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Bigrams) {
+        for (pair, &n) in &other.0 {
+            *self.0.entry(pair.clone()).or_default() += n;
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Pairs with the most mistakes, most first.
+    pub fn weakest(&self, n: usize) -> Vec<&str> {
+        let mut pairs: Vec<_> = self.0.iter().collect();
+        pairs.sort_by_key(|&(pair, &n)| (std::cmp::Reverse(n), pair));
+        pairs
+            .into_iter()
+            .take(n)
+            .map(|(pair, _)| pair.as_str())
+            .collect()
+    }
+}
+
+/// Upper bounds of the buckets in an [`Intervals`] histogram, in
+/// milliseconds; the last bucket takes everything longer. No one types most
+/// keys within the first.
+pub const INTERVAL_BUCKETS: [u64; 10] = [20, 40, 60, 80, 100, 150, 200, 300, 500, 1000];
+/// Gaps longer than this are pauses rather than typing, and left out.
+const MAX_INTERVAL: Duration = Duration::from_secs(2);
+/// Keystrokes needed before a session is judged at all.
+const MIN_INTERVALS: usize = 30;
+/// Human rhythm is never this even: the standard deviation of the gaps
+/// as a fraction of their mean.
+const MIN_VARIATION: f64 = 0.1;
+
+/// Distribution of the time between keystrokes, for telling sessions that
+/// were typed from ones that were pasted or scripted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Intervals {
+    /// Count in each of [`INTERVAL_BUCKETS`], plus one for longer gaps.
+    pub histogram: Vec<usize>,
+    pub sum_ms: f64,
+    pub sum_sq_ms: f64,
+}
+
+impl Intervals {
+    pub fn record(&mut self, interval: Duration) {
+        if interval > MAX_INTERVAL {
+            return;
+        }
+        let ms = interval.as_secs_f64() * 1000.0;
+        let bucket = INTERVAL_BUCKETS
+            .iter()
+            .position(|&bound| ms < bound as f64)
+            .unwrap_or(INTERVAL_BUCKETS.len());
+        self.histogram.resize(INTERVAL_BUCKETS.len() + 1, 0);
+        self.histogram[bucket] += 1;
+        self.sum_ms += ms;
+        self.sum_sq_ms += ms * ms;
+    }
+
+    pub fn count(&self) -> usize {
+        self.histogram.iter().sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Why the keystrokes couldn't have been typed by hand, if they look
+    /// that way.
+    pub fn suspicion(&self) -> Option<&'static str> {
+        let count = self.count();
+        if count < MIN_INTERVALS {
+            return None;
+        }
+        let under_floor = self.histogram.first().copied().unwrap_or(0);
+        if under_floor * 2 > count {
+            return Some("most keystrokes came under 20ms apart");
+        }
+        let mean = self.sum_ms / count as f64;
+        let variance = (self.sum_sq_ms / count as f64 - mean * mean).max(0.0);
+        if mean > 0.0 && variance.sqrt() / mean < MIN_VARIATION {
+            return Some("keystrokes came at suspiciously even intervals");
+        }
+        None
+    }
+}
+
+/// Table of speed and accuracy by class, with each class's speed relative
+/// to lowercase letters.
+pub fn class_report(stats: &ClassStats) -> String {
+    let baseline = stats.get(CharClass::Lowercase).wpm();
+    let mut out = format!(
+        "{:<12} {:>8} {:>5} {:>6} {:>9}\n",
+        "", "chars", "wpm", "acc", "vs lower"
+    );
+    for class in CharClass::ALL {
+        let tally = stats.get(class);
+        if tally.typed == 0 {
+            continue;
+        }
+        let relative = match baseline {
+            b if b > 0.0 && tally.timed > 0 => format!("{:+.0}%", (tally.wpm() / b - 1.0) * 100.0),
+            _ => "-".to_owned(),
+        };
+        out += &format!(
+            "{:<12} {:>8} {:>5.0} {:>5.1}% {:>9}\n",
+            class.name(),
+            tally.typed,
+            tally.wpm(),
+            tally.accuracy() * 100.0,
+            relative
+        );
+    }
+    out
+}
+
+/// Shades from no typing to the busiest day.
+const SHADES: [char; 5] = ['·', '░', '▒', '▓', '█'];
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Words typed on each of the 53 weeks of days up to and including
+/// `today`, in days since 1970-01-01, as a calendar with a column per week
+/// and a row per weekday, Monday first.
+pub fn heat_calendar(sessions: &[Session], today: i64) -> String {
+    let first = today - i64::from(date::weekday(today)) - 52 * 7;
+    let mut words = vec![0.0; (today - first + 1) as usize];
+    for session in sessions {
+        let day = date::day_of(session.timestamp);
+        if (first..=today).contains(&day) {
+            words[(day - first) as usize] += session.chars as f64 / 5.0;
+        }
+    }
+    let busiest = words.iter().copied().fold(0.0, f64::max);
+    let shade = |w: f64| match w {
+        w if w <= 0.0 => SHADES[0],
+        w => SHADES[1 + ((w / busiest * 4.0).ceil() as usize - 1).min(3)],
+    };
+
+    let weeks = words.len().div_ceil(7);
+    let mut months = vec![' '; weeks + 3];
+    let mut last_month = None;
+    for week in 0..weeks {
+        let (_, month, _) = date::civil_from_days(first + week as i64 * 7);
+        if last_month.is_some_and(|m| m != month) {
+            for (i, c) in MONTHS[month as usize - 1].chars().enumerate() {
+                months[week + i] = c;
+            }
+        }
+        last_month = Some(month);
+    }
+    let mut out = format!("    {}\n", months.iter().collect::<String>().trim_end());
+    for (weekday, name) in ["Mon", "", "Wed", "", "Fri", "", "Sun"].iter().enumerate() {
+        out += &format!("{name:<4}");
+        for week in 0..weeks {
+            out.extend(words.get(week * 7 + weekday).map(|&w| shade(w)));
+        }
+        out += "\n";
+    }
+    let total: f64 = words.iter().sum();
+    let days = words.iter().filter(|&&w| w > 0.0).count();
+    out +=
+        &format!("{total:.0} words on {days} days in the last year, most in a day {busiest:.0}\n");
+    out
+}
+
+/// Speed and accuracy for each keyboard sessions were typed on, busiest
+/// first.
+pub fn keyboard_report(sessions: &[Session]) -> String {
+    let mut keyboards: BTreeMap<Option<&str>, Totals> = BTreeMap::new();
+    for session in sessions {
+        keyboards
+            .entry(session.keyboard.as_deref())
+            .or_default()
+            .add(session);
+    }
+    let mut keyboards: Vec<_> = keyboards.into_iter().collect();
+    keyboards.sort_by_key(|(_, totals)| std::cmp::Reverse(totals.chars));
+
+    let mut out = format!(
+        "{:<20} {:>8} {:>8} {:>5} {:>6}\n",
+        "keyboard", "sessions", "words", "wpm", "acc"
+    );
+    for (keyboard, totals) in keyboards {
+        out += &format!(
+            "{:<20} {:>8} {:>8.0} {:>5.0} {:>5.1}%\n",
+            keyboard.unwrap_or("unspecified"),
+            totals.sessions,
+            totals.chars as f64 / 5.0,
+            totals.wpm(),
+            totals.accuracy() * 100.0
+        );
+    }
+    out
+}
+
+/// Weeks shown in [`trend_report`].
+const TREND_WEEKS: i64 = 8;
+
+/// Speed and accuracy for each of the last few weeks up to `today`, in days
+/// since 1970-01-01, with how speed changed from the week before. Weeks
+/// without sessions are left out.
+pub fn trend_report(sessions: &[Session], today: i64) -> String {
+    let this_week = today - i64::from(date::weekday(today));
+    let first = this_week - (TREND_WEEKS - 1) * 7;
+    let mut weeks: BTreeMap<i64, Totals> = BTreeMap::new();
+    for session in sessions {
+        let day = date::day_of(session.timestamp);
+        if (first..=today).contains(&day) {
+            let week = day - i64::from(date::weekday(day));
+            weeks.entry(week).or_default().add(session);
+        }
+    }
+
+    let mut out = format!(
+        "{:<10} {:>8} {:>8} {:>5} {:>6} {:>6}\n",
+        "week of", "sessions", "words", "wpm", "acc", "change"
+    );
+    let mut previous: Option<f64> = None;
+    for (week, totals) in weeks {
+        let change = match previous {
+            Some(wpm) => format!("{:+.0}", totals.wpm() - wpm),
+            None => String::new(),
+        };
+        out += &format!(
+            "{:<10} {:>8} {:>8.0} {:>5.0} {:>5.1}% {:>6}\n",
+            date::format_date(week as u64 * 24 * 60 * 60),
+            totals.sessions,
+            totals.chars as f64 / 5.0,
+            totals.wpm(),
+            totals.accuracy() * 100.0,
+            change
+        );
+        previous = Some(totals.wpm());
+    }
+    out
+}
+
+/// The book key among `keys` that `query` names, matching case-insensitively
+/// on any part of it.
+pub fn find_book<'a>(
+    keys: impl IntoIterator<Item = &'a str>,
+    query: &str,
+) -> anyhow::Result<&'a str> {
+    let needle = query.to_lowercase();
+    let mut matches: Vec<_> = keys
+        .into_iter()
+        .filter(|key| key.to_lowercase().contains(&needle))
+        .collect();
+    matches.sort_unstable();
+    matches.dedup();
+    match matches[..] {
+        [key] => Ok(key),
+        [] => anyhow::bail!("no sessions for a book matching {query:?}"),
+        _ => anyhow::bail!(
+            "{query:?} matches several books:\n  {}",
+            matches.join("\n  ")
+        ),
+    }
+}
+
+/// Side by side totals for two books, with how the second differs from the
+/// first.
+pub fn compare_report(a: (&str, &Totals), b: (&str, &Totals)) -> String {
+    let difficulty = |t: &Totals| match t.chars {
+        0 => 1.0,
+        n => t.adjusted_chars / n as f64,
+    };
+    let rows = [
+        ("sessions", a.1.sessions as f64, b.1.sessions as f64, 0),
+        ("words", a.1.chars as f64 / 5.0, b.1.chars as f64 / 5.0, 0),
+        ("wpm", a.1.wpm(), b.1.wpm(), 1),
+        ("adjusted wpm", a.1.adjusted_wpm(), b.1.adjusted_wpm(), 1),
+        (
+            "accuracy %",
+            a.1.accuracy() * 100.0,
+            b.1.accuracy() * 100.0,
+            1,
+        ),
+        ("difficulty", difficulty(a.1), difficulty(b.1), 2),
+    ];
+    let mut out = format!("A: {}\nB: {}\n\n", a.0, b.0);
+    out += &format!("{:<13} {:>9} {:>9} {:>9}\n", "", "A", "B", "B - A");
+    for (name, x, y, precision) in rows {
+        out += &format!(
+            "{name:<13} {x:>9.precision$} {y:>9.precision$} {:>+9.precision$}\n",
+            y - x
+        );
+    }
+
+    let (wpm, adjusted) = (
+        b.1.wpm() - a.1.wpm(),
+        b.1.adjusted_wpm() - a.1.adjusted_wpm(),
+    );
+    let verdict = |d: f64| match d {
+        d if d.abs() < 1.0 => "about as fast".to_owned(),
+        d if d < 0.0 => format!("{:.0} wpm slower", -d),
+        d => format!("{d:.0} wpm faster"),
+    };
+    out += &format!(
+        "\nOn B you are {}, and {} once difficulty is taken into account.\n",
+        verdict(wpm),
+        verdict(adjusted)
+    );
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_tallies_by_class() {
+        let mut stats = ClassStats::default();
+        stats.record('a', false, None);
+        stats.record('B', true, Some(Duration::from_millis(300)));
+        stats.record('b', false, Some(Duration::from_millis(100)));
+        stats.record('7', false, Some(Duration::from_millis(200)));
+        assert_eq!(stats.get(CharClass::Lowercase).typed, 2);
+        assert_eq!(stats.get(CharClass::Lowercase).timed, 1);
+        assert_eq!(stats.get(CharClass::Capital).mistakes, 1);
+        assert_eq!(stats.get(CharClass::Digit).elapsed_ms, 200);
+        assert_eq!(stats.get(CharClass::Punctuation), Tally::default());
+    }
+
+    #[test]
+    fn the_calendar_ends_today() {
+        // A Wednesday, with typing on it and the Monday before.
+        let today = date::days_from_civil(2024, 3, 6);
+        let session = |day: i64, chars| Session {
+            timestamp: day as u64 * 86400,
+            chars,
+            ..Session::default()
+        };
+        let calendar = heat_calendar(&[session(today, 500), session(today - 2, 100)], today);
+        let rows: Vec<_> = calendar.lines().collect();
+        assert!(rows[1].ends_with('░'));
+        assert!(rows[3].ends_with('█'));
+        assert!(rows[5].ends_with('·'));
+        assert_eq!(rows[6].chars().count(), 4 + 52);
+    }
+
+    #[test]
+    fn trends_go_by_week() {
+        let today = date::days_from_civil(2024, 3, 6);
+        let session = |day: i64, chars| Session {
+            timestamp: day as u64 * 86400,
+            chars,
+            elapsed_ms: 60_000,
+            ..Session::default()
+        };
+        let sessions = [
+            session(today, 300),
+            session(today - 2, 300),
+            session(today - 7, 250),
+            session(today - 70, 100),
+        ];
+        let report = trend_report(&sessions, today);
+        let rows: Vec<_> = report.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows[1].starts_with("2024-02-26"));
+        assert!(rows[2].starts_with("2024-03-04"));
+        assert!(rows[2].ends_with("+10"), "{}", rows[2]);
+    }
+
+    #[test]
+    fn books_are_found_by_any_part_of_their_key() {
+        let keys = [
+            "Charles Dickens / Bleak House",
+            "Charles Dickens / Hard Times",
+        ];
+        assert_eq!(find_book(keys, "bleak").unwrap(), keys[0]);
+        assert!(find_book(keys, "dickens").is_err());
+        assert!(find_book(keys, "asimov").is_err());
+    }
+
+    #[test]
+    fn scripted_keystrokes_are_suspicious() {
+        let mut typed = Intervals::default();
+        let mut scripted = Intervals::default();
+        let mut pasted = Intervals::default();
+        for i in 0..60 {
+            typed.record(Duration::from_millis(120 + i % 7 * 30));
+            scripted.record(Duration::from_millis(100));
+            pasted.record(Duration::from_millis(i % 3));
+        }
+        assert_eq!(typed.suspicion(), None);
+        assert!(scripted.suspicion().is_some());
+        assert!(pasted.suspicion().is_some());
+    }
+
+    #[test]
+    fn shifted_keys_are_compared_with_unshifted() {
+        let mut stats = ShiftStats::default();
+        stats.record('a', false, false, Some(Duration::from_millis(100)));
+        stats.record('T', true, true, Some(Duration::from_millis(250)));
+        stats.record('T', true, true, Some(Duration::from_millis(150)));
+        stats.record('W', true, true, None);
+        assert_eq!(stats.penalty(), Some(Duration::from_millis(100)));
+        assert_eq!(stats.most_fumbled(1), [('T', 2)]);
+    }
+}
@@ -0,0 +1,211 @@
+//! Assignments: a book, a range of chapters and targets to hit, handed out
+//! as a file. Running one produces a results file to hand back.
+
+use std::{
+    fs,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::stats::{Session, Totals};
+
+#[derive(Debug, Deserialize)]
+pub struct Assignment {
+    /// Path to the book, relative to the assignment file.
+    pub book: PathBuf,
+    /// First chapter to type, counting table of contents entries from 1.
+    pub first: usize,
+    /// Last chapter to type, inclusive. Defaults to `first`.
+    pub last: Option<usize>,
+    #[serde(default)]
+    pub target: Target,
+    /// Mixed into the results checksum, so results can't be edited without
+    /// knowing it. Not a real signature: anyone with the assignment has it.
+    #[serde(default)]
+    pub key: String,
+}
+
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+pub struct Target {
+    pub wpm: Option<f64>,
+    /// Fraction of keystrokes that must be correct, from 0 to 1.
+    pub accuracy: Option<f64>,
+}
+
+impl Target {
+    /// Whether `session` typed its chapter to the end at these targets.
+    pub fn is_met_by(&self, session: &Session) -> bool {
+        session.finished
+            && self.wpm.is_none_or(|wpm| session.wpm() >= wpm)
+            && self.accuracy.is_none_or(|acc| session.accuracy() >= acc)
+    }
+}
+
+impl Assignment {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut assignment: Self = toml::from_str(&fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            assignment.book = dir.join(&assignment.book);
+        }
+        anyhow::ensure!(assignment.first > 0, "chapters are numbered from 1");
+        anyhow::ensure!(
+            assignment.last.unwrap_or(assignment.first) >= assignment.first,
+            "last chapter comes before first"
+        );
+        Ok(assignment)
+    }
+
+    /// TOC indices of the assigned chapters.
+    pub fn chapters(&self) -> RangeInclusive<usize> {
+        self.first - 1..=self.last.unwrap_or(self.first) - 1
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Results {
+    pub profile: String,
+    pub book: String,
+    /// Seconds since the Unix epoch when the results were written.
+    pub submitted: u64,
+    pub target: Target,
+    /// Whether every chapter was finished and the targets were met overall,
+    /// with no session flagged.
+    pub passed: bool,
+    /// Sessions that don't look typed by hand, see [`Session::is_plausible`].
+    #[serde(default)]
+    pub flagged: usize,
+    pub sessions: Vec<Session>,
+    pub checksum: String,
+}
+
+impl Results {
+    pub fn new(
+        assignment: &Assignment,
+        profile: &str,
+        book: String,
+        submitted: u64,
+        sessions: Vec<Session>,
+    ) -> anyhow::Result<Self> {
+        let totals: Totals = sessions.iter().collect();
+        let finished = assignment
+            .chapters()
+            .all(|c| sessions.iter().any(|s| s.chapter == c && s.finished));
+        let target = assignment.target;
+        let flagged = sessions.iter().filter(|s| !s.is_plausible()).count();
+        let passed = finished
+            && flagged == 0
+            && target.wpm.is_none_or(|wpm| totals.wpm() >= wpm)
+            && target.accuracy.is_none_or(|acc| totals.accuracy() >= acc);
+
+        let mut results = Self {
+            profile: profile.to_owned(),
+            book,
+            submitted,
+            target,
+            passed,
+            flagged,
+            sessions,
+            checksum: String::new(),
+        };
+        results.checksum = results.digest(&assignment.key)?;
+        Ok(results)
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        Ok(toml::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        fs::write(path, toml::to_string(self)?)?;
+        Ok(())
+    }
+
+    /// Whether the results are unchanged since they were written.
+    pub fn verify(&self, key: &str) -> anyhow::Result<bool> {
+        Ok(self.checksum == self.digest(key)?)
+    }
+
+    /// FNV-1a over the key and everything but the checksum itself.
+    fn digest(&self, key: &str) -> anyhow::Result<String> {
+        let body = Self {
+            checksum: String::new(),
+            profile: self.profile.clone(),
+            book: self.book.clone(),
+            sessions: self.sessions.clone(),
+            ..*self
+        };
+        let body = toml::to_string(&body)?;
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in key.bytes().chain(body.bytes()) {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        Ok(format!("{hash:016x}"))
+    }
+}
+
+/// Where the results for `assignment` are written for `profile`.
+pub fn results_path(assignment: &Path, profile: &str) -> PathBuf {
+    let stem = assignment
+        .file_stem()
+        .map(|s| s.to_string_lossy())
+        .unwrap_or_default();
+    assignment.with_file_name(format!("{stem}-{profile}-results.toml"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn results() -> (Assignment, Results) {
+        let assignment = Assignment {
+            book: PathBuf::from("frankenstein.epub"),
+            first: 1,
+            last: Some(2),
+            target: Target {
+                wpm: Some(40.0),
+                accuracy: Some(0.95),
+            },
+            key: "hunter2".to_owned(),
+        };
+        let session = |chapter| Session {
+            chapter,
+            chars: 1200,
+            elapsed_ms: 90_000,
+            finished: true,
+            ..Session::default()
+        };
+        let sessions = vec![session(0), session(1)];
+        let results = Results::new(
+            &assignment,
+            "alice",
+            "Frankenstein".to_owned(),
+            1_700_000_000,
+            sessions,
+        )
+        .unwrap();
+        (assignment, results)
+    }
+
+    #[test]
+    fn saved_results_still_verify() {
+        let (assignment, results) = results();
+        let path = std::env::temp_dir().join(format!("ept-results-{}.toml", std::process::id()));
+        results.save(&path).unwrap();
+        let loaded = Results::load(&path);
+        fs::remove_file(&path).unwrap();
+        let loaded = loaded.unwrap();
+        assert!(loaded.verify(&assignment.key).unwrap());
+        assert!(!loaded.verify("not the key").unwrap());
+    }
+
+    #[test]
+    fn edited_results_fail_to_verify() {
+        let (assignment, mut results) = results();
+        assert!(results.verify(&assignment.key).unwrap());
+        results.sessions[1].elapsed_ms /= 2;
+        assert!(!results.verify(&assignment.key).unwrap());
+    }
+}
@@ -1,12 +1,17 @@
+use std::time::{Duration, Instant};
+
 use lepu::{Content, Epub};
 
-use crate::style::{Style, Styling};
+use serde::{Deserialize, Serialize};
 
-#[rustfmt::skip]
-const REPLACEMENTS: &[(char, &str)] = &[
-    ('—', "--"),
-    ('…', "..."),
-];
+use crate::{
+    analytics::{Bigrams, ClassStats, Intervals, ShiftStats},
+    config::{Config, Limit},
+    stats::Stats,
+    style::{Style, Styling, StylingIter},
+    transform::{self, Draft},
+    translate::Action,
+};
 
 #[rustfmt::skip]
 const ALTERNATIVES: &[(char, &[char])] = &[
@@ -15,14 +20,76 @@ const ALTERNATIVES: &[(char, &[char])] = &[
     (' ',  &[' '])
 ];
 
+/// How far back the typist is allowed to correct themselves.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackspaceMode {
+    /// Backspace does nothing; every error stands.
+    #[serde(alias = "off", alias = "none")]
+    Disabled,
+    /// Backspace can't cross the start of the word currently being typed.
+    /// Typing the space after a word commits it, space and all; reaching
+    /// back over the space would make the word before current again, and
+    /// so on back to the start.
+    Word,
+    #[default]
+    Unlimited,
+}
+
+impl std::str::FromStr for BackspaceMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "off" | "none" | "disabled" => Ok(Self::Disabled),
+            "word" => Ok(Self::Word),
+            "unlimited" | "on" => Ok(Self::Unlimited),
+            _ => Err(anyhow::anyhow!("unknown backspace mode `{s}`")),
+        }
+    }
+}
+
 pub struct Backend {
     text: String,
+    blocks: Vec<block::Block>,
     typed: String,
+    start: Len,
     cursor: Len,
     cursor_prev: Len,
     errors: Vec<Len>,
     deleted_errors: Vec<Len>,
     styling: Styling<Len>,
+    backspace: BackspaceMode,
+    keystrokes: usize,
+    mistakes: usize,
+    /// Character index of every mistake, including ones since corrected.
+    mistake_positions: Vec<usize>,
+    classes: ClassStats,
+    shift: ShiftStats,
+    bigrams: Bigrams,
+    intervals: Intervals,
+    /// When each input happened, along with how far past `start` the cursor
+    /// was afterwards.
+    history: Vec<(Instant, usize)>,
+    /// When each character was pushed, and whether it was a mistake.
+    keystroke_times: Vec<(Instant, bool)>,
+    /// Time taken as now for inputs, instead of the real time. Set when
+    /// grading replays, which run faster than they were typed.
+    clock: Option<Instant>,
+    /// How soon after a mistake typing the character it should have been
+    /// overtypes it, if at all.
+    grace: Option<Duration>,
+    /// Mistakes overtyped within `grace`.
+    forgiven: usize,
+    /// Start the word over on a mistake.
+    repeat_words: bool,
+    /// Keep the cursor where it is on a mistake, until the right character
+    /// is typed.
+    stop_on_error: bool,
+    /// How far past `start` typing may go.
+    limit: Option<Limit>,
+    /// Where `limit` ends typing, if short of the end of the text.
+    stop: Option<Len>,
 }
 
 // struct Node {
@@ -40,15 +107,19 @@ pub struct Backend {
 //     Title,
 // }
 
-mod block {
+pub mod block {
     use lepu::Align;
 
     use crate::backend::Len;
 
+    /// A paragraph, header or quote as it was in the book, kept so the text
+    /// can be navigated by its structure without going back to the book.
+    #[derive(Debug, Clone)]
     pub struct Block {
-        range: std::ops::Range<Len>,
-        kind: Kind,
-        align: Option<Align>,
+        /// Where it is in the chapter's text, after any transforms.
+        pub range: std::ops::Range<Len>,
+        pub kind: Kind,
+        pub align: Option<Align>,
         // It can be a header OR a paragraph OR a blockquote of arbitrary nestedness
         // any of these can be force aligned left, right, center
     }
@@ -59,6 +130,7 @@ mod block {
         }
     }
 
+    #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     pub enum Kind {
         Header,
         Paragraph,
@@ -66,13 +138,22 @@ mod block {
     }
 }
 
-impl Backend {
-    pub fn new(book: &mut Epub, chapter: usize) -> Self {
+/// Text of a chapter along with its styling, as extracted from the book.
+pub struct ChapterText {
+    pub text: String,
+    pub styling: Styling<Len>,
+    pub blocks: Vec<block::Block>,
+}
+
+impl ChapterText {
+    pub fn extract(book: &mut Epub, chapter: usize, config: &Config) -> anyhow::Result<Self> {
         let mut buf = String::new();
         let mut char_count = 0;
-        let mut styling = Styling::builder();
+        let mut styles = Vec::new();
         let mut blocks: Vec<block::Block> = Vec::new();
-        book.traverse_chapter_with_replacements(chapter, REPLACEMENTS, |_, content, align| {
+        // Line breaks inside paragraphs.
+        let mut breaks = Vec::new();
+        book.traverse_chapter_with_replacements(chapter, &[], |_, content, align| {
             let text = match content {
                 Content::Textual(text) => text,
                 Content::Image(..) => return,
@@ -95,24 +176,91 @@ impl Backend {
                 let sty = Style::from_bits(sty.bits()).unwrap();
                 let chunk_len = Len::new(s.len(), s.chars().count());
                 buf.push_str(&s);
-                styling.add(sty, cur..cur + chunk_len);
+                styles.push((sty, cur..cur + chunk_len));
                 char_count += chunk_len.chars;
                 cur += chunk_len;
             }
             let end = Len::new(buf.len(), char_count);
-            let block = block::Block::new(start..end, kind, None);
+            breaks.extend(
+                buf[start.bytes..end.bytes]
+                    .char_indices()
+                    .enumerate()
+                    .filter(|&(_, (_, c))| c == '\n')
+                    .map(|(chars, (bytes, _))| start + Len::new(bytes, chars)),
+            );
+            let block = block::Block::new(start..end, kind, align);
             blocks.push(block);
+        })?;
+
+        let mut draft = Draft {
+            text: buf,
+            styles,
+            breaks,
+            blocks,
+        };
+        for step in transform::pipeline(config) {
+            step.apply(&mut draft);
+        }
+
+        let mut styling = Styling::builder();
+        for (sty, range) in draft.styles {
+            if range.start < range.end {
+                styling.add(sty, range);
+            }
+        }
+
+        let mut blocks = draft.blocks;
+        blocks.retain(|block| block.range.start < block.range.end);
+
+        Ok(Self {
+            text: draft.text,
+            styling: styling.build(),
+            blocks,
         })
-        .unwrap();
+    }
+}
 
+impl Backend {
+    pub fn new(book: &mut Epub, chapter: usize, config: &Config) -> Self {
+        let text = ChapterText::extract(book, chapter, config).unwrap();
+        Self::from_text(text, config.backspace)
+    }
+
+    pub fn from_text(
+        ChapterText {
+            text,
+            styling,
+            blocks,
+        }: ChapterText,
+        backspace: BackspaceMode,
+    ) -> Self {
         Self {
-            text: buf,
+            text,
+            blocks,
             typed: String::new(),
+            start: Len::new(0, 0),
             cursor: Len::new(0, 0),
             cursor_prev: Len::new(0, 0),
             errors: Vec::new(),
             deleted_errors: Vec::new(),
-            styling: styling.build(),
+            styling,
+            backspace,
+            keystrokes: 0,
+            mistakes: 0,
+            mistake_positions: Vec::new(),
+            classes: ClassStats::default(),
+            shift: ShiftStats::default(),
+            bigrams: Bigrams::default(),
+            intervals: Intervals::default(),
+            history: Vec::new(),
+            keystroke_times: Vec::new(),
+            clock: None,
+            grace: None,
+            forgiven: 0,
+            repeat_words: false,
+            stop_on_error: false,
+            limit: None,
+            stop: None,
         }
     }
 
@@ -120,10 +268,138 @@ impl Backend {
         &self.text
     }
 
+    /// The chapter's paragraphs, headers and quotes, in order.
+    pub fn blocks(&self) -> &[block::Block] {
+        &self.blocks
+    }
+
     pub fn cursor(&self) -> Len {
         self.cursor
     }
 
+    /// Position typing began from.
+    pub fn start(&self) -> Len {
+        self.start
+    }
+
+    /// Restarts typing from `pos`, discarding anything typed so far.
+    pub fn start_at(&mut self, pos: Len) {
+        self.typed.clear();
+        self.start = pos;
+        self.cursor = pos;
+        self.cursor_prev = pos;
+        self.errors.clear();
+        self.deleted_errors.clear();
+        self.keystrokes = 0;
+        self.mistakes = 0;
+        self.forgiven = 0;
+        self.mistake_positions.clear();
+        self.classes.clear();
+        self.shift.clear();
+        self.bigrams.clear();
+        self.intervals.clear();
+        self.history.clear();
+        self.keystroke_times.clear();
+        self.update_stop();
+    }
+
+    /// Position of the `chars`th character, clamped to the end of the text.
+    pub fn position(&self, chars: usize) -> Len {
+        match self.text.char_indices().nth(chars) {
+            Some((bytes, _)) => Len::new(bytes, chars),
+            None => self.end(),
+        }
+    }
+
+    /// Start of the sentence containing `pos`. Sentences are assumed to end
+    /// at a `.`, `!` or `?` (optionally followed by closing punctuation) that
+    /// is followed by whitespace, and never span paragraphs.
+    pub fn sentence_start(&self, pos: Len) -> Len {
+        let para = self.text[..pos.bytes].rfind('\n').map_or(0, |i| i + 1);
+        let mut cur = Len::new(para, self.text[..para].chars().count());
+        let mut start = cur;
+        let mut ended = false;
+        for c in self.text[para..pos.bytes].chars() {
+            cur += Len::new(c.len_utf8(), 1);
+            match c {
+                '.' | '!' | '?' => ended = true,
+                c if c.is_whitespace() => {
+                    if ended {
+                        start = cur;
+                    }
+                }
+                '"' | '\'' | '”' | '’' | ')' | ']' => {}
+                _ => ended = false,
+            }
+        }
+        start
+    }
+
+    pub fn end(&self) -> Len {
+        Len::new(self.text.len(), self.text.chars().count())
+    }
+
+    pub fn next_char(&self, pos: Len) -> Len {
+        match self.text[pos.bytes..].chars().next() {
+            Some(c) => pos + Len::new(c.len_utf8(), 1),
+            None => pos,
+        }
+    }
+
+    pub fn previous_char(&self, pos: Len) -> Len {
+        match self.text[..pos.bytes].chars().next_back() {
+            Some(c) => pos - Len::new(c.len_utf8(), 1),
+            None => pos,
+        }
+    }
+
+    /// Start of the word following the one at `pos`.
+    pub fn next_word(&self, mut pos: Len) -> Len {
+        let mut found_whitespace = false;
+        for c in self.text[pos.bytes..].chars() {
+            let is_ws = c.is_whitespace();
+            if found_whitespace && !is_ws {
+                break;
+            }
+            found_whitespace |= is_ws;
+            pos += Len::new(c.len_utf8(), 1);
+        }
+        pos
+    }
+
+    /// Start of the word at `pos`, or of the previous word if already there.
+    pub fn previous_word(&self, mut pos: Len) -> Len {
+        let mut found_nonwhitespace = false;
+        for c in self.text[..pos.bytes].chars().rev() {
+            let is_ws = c.is_whitespace();
+            if found_nonwhitespace && is_ws {
+                break;
+            }
+            found_nonwhitespace |= !is_ws;
+            pos -= Len::new(c.len_utf8(), 1);
+        }
+        pos
+    }
+
+    pub fn next_paragraph(&self, pos: Len) -> Len {
+        let rest = &self.text[pos.bytes..];
+        match rest.find('\n') {
+            Some(i) => pos + Len::new(i + 1, rest[..i].chars().count() + 1),
+            None => self.end(),
+        }
+    }
+
+    /// Start of the paragraph at `pos`, or of the previous paragraph if
+    /// already there.
+    pub fn previous_paragraph(&self, pos: Len) -> Len {
+        let before = &self.text[..pos.bytes];
+        let before = before.strip_suffix('\n').unwrap_or(before);
+        match before.rfind('\n') {
+            Some(i) => Len::new(i + 1, before[..=i].chars().count()),
+            None => Len::default(),
+        }
+    }
+
     pub fn last_cursor_position(&self) -> Len {
         self.cursor_prev
     }
@@ -132,28 +408,281 @@ impl Backend {
         &self.errors
     }
 
+    /// Character indices typed so far that are currently correct.
+    pub fn typed_correctly(&self) -> impl Iterator<Item = usize> + '_ {
+        (self.start.chars..self.cursor.chars)
+            .filter(|&i| self.errors.binary_search_by_key(&i, |e| e.chars).is_err())
+    }
+
+    /// Keystrokes broken down by the class of character they were for.
+    pub fn classes(&self) -> &ClassStats {
+        &self.classes
+    }
+
+    pub fn shift(&self) -> &ShiftStats {
+        &self.shift
+    }
+
+    pub fn bigrams(&self) -> &Bigrams {
+        &self.bigrams
+    }
+
+    pub fn intervals(&self) -> &Intervals {
+        &self.intervals
+    }
+
+    /// Where every mistake this session was made, in the order they were.
+    pub fn mistake_positions(&self) -> &[usize] {
+        &self.mistake_positions
+    }
+
     pub fn backspaced_errors(&self) -> &[Len] {
         &self.deleted_errors
     }
 
+    /// Whether the whole text has been typed.
+    pub fn is_finished(&self) -> bool {
+        self.cursor.bytes >= self.stop().bytes
+    }
+
+    /// Whether typing went all the way to the end of the text, rather than
+    /// stopping at a limit.
+    pub fn reached_end(&self) -> bool {
+        self.cursor.bytes == self.text.len()
+    }
+
+    /// Where typing ends.
+    pub fn stop(&self) -> Len {
+        self.stop.unwrap_or_else(|| self.end())
+    }
+
+    /// Ends typing `limit` past where it starts, at the end of a word for a
+    /// limit in words.
+    pub fn set_limit(&mut self, limit: Option<Limit>) {
+        self.limit = limit;
+        self.update_stop();
+    }
+
+    fn update_stop(&mut self) {
+        let Some(limit) = self.limit else {
+            self.stop = None;
+            return;
+        };
+        let rest = &self.text[self.start.bytes..];
+        let len = match limit {
+            Limit::Chars(n) => rest.chars().take(n).map(char::len_utf8).sum(),
+            Limit::Words(n) => {
+                let mut words = 0;
+                let mut in_word = false;
+                let mut len = rest.len();
+                for (i, c) in rest.char_indices() {
+                    if c.is_whitespace() && in_word && words == n {
+                        len = i;
+                        break;
+                    }
+                    if !c.is_whitespace() && !in_word {
+                        words += 1;
+                    }
+                    in_word = !c.is_whitespace();
+                }
+                len
+            }
+        };
+        let stop = self.start.bytes + len;
+        self.stop = Some(Len::new(stop, self.text[..stop].chars().count()));
+    }
+
+    pub fn history(&self) -> &[(Instant, usize)] {
+        &self.history
+    }
+
+    pub fn stats(&self) -> Stats {
+        let elapsed = match (self.history.first(), self.history.last()) {
+            (Some(first), Some(last)) => last.0 - first.0,
+            _ => Duration::ZERO,
+        };
+        Stats {
+            mode: self.backspace,
+            chars: self.cursor.chars - self.start.chars,
+            elapsed,
+            keystrokes: self.keystrokes,
+            mistakes: self.mistakes,
+            uncorrected: self.errors.len(),
+            forgiven: self.forgiven,
+        }
+    }
+
+    /// Speed and accuracy over just the inputs in the `window` before `now`,
+    /// to show how typing is going at the moment rather than on average.
+    pub fn recent_stats(&self, window: Duration, now: Instant) -> Stats {
+        let since = now.checked_sub(window);
+        let recent = |at: Instant| since.is_none_or(|since| at >= since);
+        let i = self.history.partition_point(|&(at, _)| !recent(at));
+        let (first, base) = match i {
+            0 => (self.history.first().map(|&(at, _)| at), 0),
+            // Progress as of the window opening, timed from then.
+            _ => (since, self.history[i - 1].1),
+        };
+        let progress = self.history.last().map_or(0, |&(_, p)| p);
+        let keystrokes = self.keystroke_times.iter().filter(|&&(at, _)| recent(at));
+        Stats {
+            mode: self.backspace,
+            chars: progress.saturating_sub(base),
+            elapsed: first.map_or(Duration::ZERO, |first| now.saturating_duration_since(first)),
+            keystrokes: keystrokes.clone().count(),
+            mistakes: keystrokes.filter(|&&(_, mistake)| mistake).count(),
+            uncorrected: 0,
+            forgiven: 0,
+        }
+    }
+
     pub fn clear_per_update_data(&mut self) {
         self.deleted_errors.truncate(0);
     }
 
-    pub fn push(&mut self, c: char) {
+    /// Lets a mistake be fixed by typing the right character within
+    /// `grace_ms` of it, without backspacing. 0 turns this off.
+    pub fn set_grace(&mut self, grace_ms: u64) {
+        self.grace = (grace_ms > 0).then(|| Duration::from_millis(grace_ms));
+    }
+
+    /// Sends the cursor back to the start of the word on each mistake.
+    pub fn set_repeat_words(&mut self, repeat_words: bool) {
+        self.repeat_words = repeat_words;
+    }
+
+    /// Holds the cursor in place on each mistake, counting it but not
+    /// moving on until the right character is typed.
+    pub fn set_stop_on_error(&mut self, stop_on_error: bool) {
+        self.stop_on_error = stop_on_error;
+    }
+
+    pub fn stop_on_error(&self) -> bool {
+        self.stop_on_error
+    }
+
+    /// If `c` overtypes a mistake made just before, fixes the mistake in
+    /// place and returns true. Only applies when `c` isn't also the next
+    /// character to type, which would leave it unclear which was meant.
+    fn forgive(&mut self, c: char, now: Instant) -> bool {
+        let Some(grace) = self.grace else {
+            return false;
+        };
+        let slip = self.previous_char(self.cursor);
+        if self.errors.last() != Some(&slip) || slip < self.start {
+            return false;
+        }
+        let Some(&(at, _)) = self.history.last() else {
+            return false;
+        };
+        let wanted = self.text[slip.bytes..].chars().next();
+        let next = self.text[self.cursor.bytes..].chars().next();
+        let overtypes = wanted
+            .is_some_and(|w| chars_are_equal_including_unicode_alternatives(w, c))
+            && next.is_none_or(|n| !chars_are_equal_including_unicode_alternatives(n, c));
+        if !overtypes || now.saturating_duration_since(at) > grace {
+            return false;
+        }
+        self.typed.pop();
+        self.typed.push(c);
+        self.keystrokes += 1;
+        self.errors.pop();
+        self.forgiven += 1;
+        self.keystroke_times.push((now, false));
+        self.record();
+        true
+    }
+
+    /// Types `c`, `shifted` if Shift was held for it.
+    pub fn push(&mut self, c: char, shifted: bool) {
         let Some(goal) = self.text[self.cursor.bytes..].chars().next() else {
             return;
         };
-        self.typed.push(c);
-        if !chars_are_equal_including_unicode_alternatives(goal, c) {
+        if self.forgive(c, self.now()) {
+            return;
+        }
+        self.keystrokes += 1;
+        let mistake = !chars_are_equal_including_unicode_alternatives(goal, c);
+        let stopped = mistake && self.stop_on_error;
+        if !stopped {
+            self.typed.push(c);
+        }
+        let now = self.now();
+        let since_last = self
+            .history
+            .last()
+            .map(|&(at, _)| now.saturating_duration_since(at));
+        self.classes.record(goal, mistake, since_last);
+        self.shift.record(goal, shifted, mistake, since_last);
+        let previous = self.text[..self.cursor.bytes].chars().next_back();
+        self.bigrams.record(previous, goal, mistake);
+        if let Some(interval) = since_last {
+            self.intervals.record(interval);
+        }
+        self.keystroke_times.push((now, mistake));
+        if mistake {
+            self.mistakes += 1;
+            self.mistake_positions.push(self.cursor.chars);
+        }
+        if stopped {
+            return;
+        }
+        if mistake {
             self.errors.push(self.cursor);
         }
         self.cursor_prev = self.cursor;
         self.cursor.bytes += goal.len_utf8();
         self.cursor.chars += 1;
+        self.record();
+        if mistake && self.repeat_words {
+            self.rewind_to(self.word_start(self.cursor_prev));
+        }
+    }
+
+    fn record(&mut self) {
+        let progress = self.cursor.chars - self.start.chars;
+        self.history.push((self.now(), progress));
+    }
+
+    /// Moves every input so far `by` later, so time spent away isn't
+    /// counted.
+    pub fn postpone(&mut self, by: Duration) {
+        for (at, _) in &mut self.history {
+            *at += by;
+        }
+        for (at, _) in &mut self.keystroke_times {
+            *at += by;
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.clock.unwrap_or_else(Instant::now)
+    }
+
+    /// Takes `now` as the time of the inputs that follow.
+    pub fn set_clock(&mut self, now: Instant) {
+        self.clock = Some(now);
+    }
+
+    pub fn apply(&mut self, action: Action) {
+        match action {
+            Action::Type { c, shifted } => self.push(c, shifted),
+            Action::Backspace => self.pop(),
+            Action::DeleteWord => self.delete_word_backwards(),
+            Action::RestartParagraph => self.rewind_to(self.paragraph_start(self.cursor)),
+            Action::RetryError => {
+                if let Some(&error) = self.errors.last() {
+                    self.rewind_to(error);
+                }
+            }
+            Action::ToggleStopOnError => self.stop_on_error = !self.stop_on_error,
+        }
     }
 
     pub fn pop(&mut self) {
+        if self.cursor <= self.backspace_floor() {
+            return;
+        }
         let Some(typed) = self.typed.chars().last() else {
             return;
         };
@@ -162,6 +691,7 @@ impl Backend {
     }
 
     pub fn delete_word_backwards(&mut self) {
+        let limit = self.cursor.chars - self.backspace_floor().chars;
         let mut found_nonwhitespace = false;
         let [typed, text] = self
             .typed
@@ -173,11 +703,71 @@ impl Backend {
                 !(found_nonwhitespace && is_ws)
             })
             .zip(self.text[..self.cursor.bytes].chars().rev())
+            .take(limit)
             .map(|(a, b)| [Len::new(a.len_utf8(), 1), Len::new(b.len_utf8(), 1)])
             .fold([Len::default(); 2], |acc, x| [0, 1].map(|i| acc[i] + x[i]));
         self.delete_backwards_impl(text, typed);
     }
 
+    /// Moves the cursor back to `pos`, as though everything typed since had
+    /// been deleted, however far backspace is allowed to reach. Keystrokes
+    /// and mistakes made along the way still count.
+    pub fn rewind_to(&mut self, pos: Len) {
+        let pos = pos.max(self.start);
+        if pos >= self.cursor {
+            return;
+        }
+        let chars = self.cursor.chars - pos.chars;
+        let typed = self
+            .typed
+            .chars()
+            .rev()
+            .take(chars)
+            .map(char::len_utf8)
+            .sum();
+        self.delete_backwards_impl(self.cursor - pos, Len::new(typed, chars));
+    }
+
+    /// Start of the word `pos` is in, or of the one just before it if `pos`
+    /// is on whitespace after a word.
+    pub fn word_start(&self, pos: Len) -> Len {
+        let mut start = pos;
+        for c in self.text[..pos.bytes].chars().rev() {
+            if c.is_whitespace() {
+                break;
+            }
+            start -= Len::new(c.len_utf8(), 1);
+        }
+        start
+    }
+
+    /// Start of the paragraph `pos` is in.
+    pub fn paragraph_start(&self, pos: Len) -> Len {
+        let before = &self.text[..pos.bytes];
+        match before.rfind('\n') {
+            Some(i) => Len::new(i + 1, before[..=i].chars().count()),
+            None => Len::default(),
+        }
+    }
+
+    /// Earliest position backspace is allowed to reach.
+    fn backspace_floor(&self) -> Len {
+        match self.backspace {
+            BackspaceMode::Unlimited => self.start,
+            BackspaceMode::Disabled => self.cursor,
+            BackspaceMode::Word => {
+                let mut floor = self.cursor;
+                for c in self.text[..self.cursor.bytes].chars().rev() {
+                    if c.is_whitespace() || floor <= self.start {
+                        break;
+                    }
+                    floor -= Len::new(c.len_utf8(), 1);
+                }
+                floor
+            }
+        }
+    }
+
     fn delete_backwards_impl(&mut self, len: Len, typed: Len) {
         self.typed.truncate(self.typed.len() - typed.bytes);
         self.cursor_prev = self.cursor;
@@ -185,9 +775,11 @@ impl Backend {
 
         let first = self.errors.partition_point(|&i| i < self.cursor);
         self.deleted_errors.extend(self.errors.drain(first..));
+        self.record();
     }
 
-    pub fn style_iter(&self, start: Len, end: Len) -> impl Iterator<Item = (Style, Len)> + '_ {
+    /// Styled runs of the text from `start` to `end`; see [`Styling::iter`].
+    pub fn style_iter(&self, start: Len, end: Len) -> StylingIter<'_, Len> {
         self.styling.iter(start, end)
     }
 }
@@ -252,7 +844,7 @@ impl std::ops::SubAssign<Self> for Len {
     }
 }
 
-fn chars_are_equal_including_unicode_alternatives(expected: char, got: char) -> bool {
+pub(crate) fn chars_are_equal_including_unicode_alternatives(expected: char, got: char) -> bool {
     if expected == got {
         true
     } else if let Some(alts) = ALTERNATIVES.iter().find(|x| x.0 == got) {
@@ -261,3 +853,181 @@ fn chars_are_equal_including_unicode_alternatives(expected: char, got: char) ->
         false
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rewinding_deletes_what_was_typed_since() {
+        let text = ChapterText {
+            text: "one\ntwo three".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Disabled);
+        for c in "one\ntwi thr".chars() {
+            backend.push(c, false);
+        }
+        backend.apply(Action::RetryError);
+        assert_eq!(backend.cursor(), Len::new(6, 6));
+        assert!(backend.errors().is_empty());
+        backend.apply(Action::RestartParagraph);
+        assert_eq!(backend.cursor(), Len::new(4, 4));
+        assert_eq!(backend.stats().mistakes, 1);
+    }
+
+    #[test]
+    fn backspace_reaches_as_far_as_the_mode_allows() {
+        let typed = |mode, s: &str| {
+            let text = ChapterText {
+                text: "one two three".to_owned(),
+                styling: Styling::builder().build(),
+                blocks: Vec::new(),
+            };
+            let mut backend = Backend::from_text(text, mode);
+            for c in s.chars() {
+                backend.push(c, false);
+            }
+            backend
+        };
+
+        let mut backend = typed(BackspaceMode::Disabled, "one tx");
+        backend.pop();
+        backend.delete_word_backwards();
+        assert_eq!(backend.cursor(), Len::new(6, 6));
+
+        let mut backend = typed(BackspaceMode::Word, "one twx");
+        backend.pop();
+        assert_eq!(backend.cursor(), Len::new(6, 6));
+        backend.delete_word_backwards();
+        backend.pop();
+        assert_eq!(backend.cursor(), Len::new(4, 4));
+
+        // A mistake on the space is past fixing once it's typed.
+        let mut backend = typed(BackspaceMode::Word, "one twox");
+        backend.pop();
+        assert_eq!(backend.cursor(), Len::new(8, 8));
+        assert_eq!(backend.errors(), [Len::new(7, 7)]);
+    }
+
+    #[test]
+    fn slips_overtyped_in_the_grace_window_are_forgiven() {
+        let text = ChapterText {
+            text: "cat sees".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Disabled);
+        backend.set_grace(150);
+        let base = Instant::now();
+        let type_at = |backend: &mut Backend, ms: u64, c: char| {
+            backend.set_clock(base + Duration::from_millis(ms));
+            backend.push(c, false);
+        };
+        type_at(&mut backend, 0, 'c');
+        type_at(&mut backend, 100, 'x');
+        type_at(&mut backend, 200, 'a');
+        assert_eq!(backend.cursor(), Len::new(2, 2));
+        assert!(backend.errors().is_empty());
+        // Too slow to count as a slip, so it's a mistake of its own.
+        type_at(&mut backend, 300, 'x');
+        type_at(&mut backend, 600, 't');
+        assert_eq!(backend.errors(), [Len::new(2, 2), Len::new(3, 3)]);
+        // The character after the slip is the same, so it's taken as typing
+        // on rather than overtyping.
+        for (ms, c) in [(700, 's'), (800, 'x'), (850, 'e')] {
+            type_at(&mut backend, ms, c);
+        }
+        assert_eq!(backend.cursor(), Len::new(7, 7));
+        let stats = backend.stats();
+        assert_eq!(
+            (stats.mistakes, stats.uncorrected, stats.forgiven),
+            (4, 3, 1)
+        );
+    }
+
+    #[test]
+    fn recent_stats_only_count_the_window() {
+        let text = ChapterText {
+            text: "one two".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Disabled);
+        let base = Instant::now();
+        for (ms, c) in [(0, 'o'), (1000, 'x'), (2000, 'e'), (4000, ' '), (5000, 't')] {
+            backend.set_clock(base + Duration::from_millis(ms));
+            backend.push(c, false);
+        }
+        let now = base + Duration::from_millis(6000);
+        let stats = backend.recent_stats(Duration::from_secs(3), now);
+        assert_eq!((stats.chars, stats.keystrokes, stats.mistakes), (2, 2, 0));
+        assert_eq!(stats.elapsed, Duration::from_secs(3));
+        let stats = backend.recent_stats(Duration::from_secs(60), now);
+        assert_eq!((stats.chars, stats.keystrokes, stats.mistakes), (5, 5, 1));
+        assert_eq!(stats.elapsed, Duration::from_secs(6));
+    }
+
+    #[test]
+    fn mistakes_hold_the_cursor_when_stopping_on_errors() {
+        let text = ChapterText {
+            text: "ab".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Unlimited);
+        backend.apply(Action::ToggleStopOnError);
+        for c in "axxb".chars() {
+            backend.push(c, false);
+        }
+        assert!(backend.is_finished());
+        assert!(backend.errors().is_empty());
+        let stats = backend.stats();
+        assert_eq!((stats.keystrokes, stats.mistakes), (4, 2));
+    }
+
+    #[test]
+    fn mistakes_send_the_cursor_back_to_the_start_of_the_word() {
+        let text = ChapterText {
+            text: "one two".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Disabled);
+        backend.set_repeat_words(true);
+        for c in "one tw".chars() {
+            backend.push(c, false);
+        }
+        backend.push('x', false);
+        assert_eq!(backend.cursor(), Len::new(4, 4));
+        assert!(backend.errors().is_empty());
+        backend.push('x', false);
+        assert_eq!(backend.cursor(), Len::new(4, 4));
+        for c in "two".chars() {
+            backend.push(c, false);
+        }
+        assert!(backend.is_finished());
+        assert_eq!(backend.stats().mistakes, 2);
+    }
+
+    #[test]
+    fn limits_count_from_where_typing_starts() {
+        let text = ChapterText {
+            text: "one two\nthree  four".to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let mut backend = Backend::from_text(text, BackspaceMode::Disabled);
+        backend.set_limit(Some(Limit::Words(2)));
+        assert_eq!(backend.stop(), Len::new(7, 7));
+        backend.start_at(Len::new(4, 4));
+        assert_eq!(backend.stop(), Len::new(13, 13));
+        backend.set_limit(Some(Limit::Chars(100)));
+        assert_eq!(backend.stop(), backend.end());
+        for c in "two\nthree  four".chars() {
+            backend.push(c, false);
+        }
+        assert!(backend.is_finished() && backend.reached_end());
+    }
+}
@@ -0,0 +1,157 @@
+//! Everything ept keeps, bundled into one `.tar.zst` archive to carry to
+//! another machine: the config file and every profile's sessions, progress,
+//! coverage and replays.
+
+use std::{
+    ffi::OsStr,
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::paths;
+
+/// zstd level. Logs and replays are small and compress well, so there's
+/// little to gain from going higher.
+const LEVEL: i32 = 19;
+
+type Archive = tar::Archive<zstd::Decoder<'static, io::BufReader<fs::File>>>;
+
+/// Name of the config file in an archive.
+const CONFIG: &str = "config.toml";
+/// Directory the data directory's contents go under in an archive.
+const DATA: &str = "data";
+
+/// Where the config file and data directory are here.
+fn locations() -> anyhow::Result<(PathBuf, PathBuf)> {
+    match (paths::config_file(), paths::data_dir()) {
+        (Some(config), Some(data)) => Ok((config, data)),
+        _ => anyhow::bail!("no home directory to keep ept's files in"),
+    }
+}
+
+/// Writes everything kept to `archive`, returning how many files went in.
+pub fn backup(archive: &Path) -> anyhow::Result<usize> {
+    let (config, data) = locations()?;
+    let encoder = zstd::Encoder::new(fs::File::create(archive)?, LEVEL)?;
+    let mut tar = tar::Builder::new(encoder);
+    let mut files = 0;
+    if config.exists() {
+        tar.append_path_with_name(&config, CONFIG)?;
+        files += 1;
+    }
+    // The log is only of use on the machine it was written on, and on some
+    // systems the config file lives in the data directory too.
+    let skip = [paths::log_file(), Some(config)];
+    for path in walk(&data)? {
+        if skip.contains(&Some(path.clone())) {
+            continue;
+        }
+        tar.append_path_with_name(&path, Path::new(DATA).join(path.strip_prefix(&data)?))?;
+        files += 1;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(files)
+}
+
+/// Every file under `dir`, in order, or none if it doesn't exist.
+fn walk(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        match path.is_dir() {
+            true => files.extend(walk(&path)?),
+            false => files.push(path),
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Unpacks an archive written by [`backup`], returning how many files came
+/// out. Unless `overwrite` is set, nothing is written if any file in it is
+/// already here, so a restore can't quietly replace newer history.
+pub fn restore(archive: &Path, overwrite: bool) -> anyhow::Result<usize> {
+    let (config, data) = locations()?;
+    let mut existing = Vec::new();
+    for entry in open(archive)?.entries()? {
+        let entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let target = target(&config, &data, &entry.path()?)?;
+        if target.exists() {
+            existing.push(target);
+        }
+    }
+    if let Some(first) = existing.first().filter(|_| !overwrite) {
+        anyhow::bail!(
+            "{} files would be replaced, {} among them; pass --force to replace them",
+            existing.len(),
+            first.display()
+        );
+    }
+
+    let mut files = 0;
+    for entry in open(archive)?.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let target = target(&config, &data, &entry.path()?)?;
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        entry.unpack(&target)?;
+        files += 1;
+    }
+    Ok(files)
+}
+
+fn open(archive: &Path) -> anyhow::Result<Archive> {
+    Ok(tar::Archive::new(zstd::Decoder::new(fs::File::open(
+        archive,
+    )?)?))
+}
+
+/// Where the file at `path` in an archive goes, given where the config file
+/// and data directory are. Anything else is refused rather than written
+/// wherever it points.
+fn target(config: &Path, data: &Path, path: &Path) -> anyhow::Result<PathBuf> {
+    if path == Path::new(CONFIG) {
+        return Ok(config.to_owned());
+    }
+    let mut components = path.components();
+    let in_data = components.next() == Some(Component::Normal(OsStr::new(DATA)));
+    let rest = components.as_path();
+    let inside = rest.components().next().is_some()
+        && rest.components().all(|c| matches!(c, Component::Normal(_)));
+    match in_data && inside {
+        true => Ok(data.join(rest)),
+        false => anyhow::bail!("{} isn't part of an ept backup", path.display()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn archived_paths_stay_inside_the_backed_up_directories() {
+        let config = Path::new("/home/me/.config/ept/config.toml");
+        let data = Path::new("/home/me/.local/share/ept");
+        let place = |path: &str| target(config, data, Path::new(path));
+        assert_eq!(place("config.toml").unwrap(), config);
+        assert_eq!(
+            place("data/profiles/default/sessions.jsonl").unwrap(),
+            data.join("profiles/default/sessions.jsonl")
+        );
+        for path in ["data/../../.bashrc", "/etc/passwd", "other/file", "data"] {
+            assert!(place(path).is_err(), "{path}");
+        }
+    }
+}
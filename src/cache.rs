@@ -0,0 +1,110 @@
+use std::{
+    sync::{
+        mpsc::{self, Receiver, TryRecvError},
+        Arc,
+    },
+    thread,
+};
+
+use lepu::Epub;
+
+use crate::{backend::ChapterText, config::Config, difficulty::Difficulty};
+
+/// Text of every chapter in the book, extracted on a background thread so
+/// whole-book features don't stall the UI.
+pub struct ChapterCache {
+    chapters: Vec<Option<ChapterText>>,
+    difficulties: Vec<Option<Difficulty>>,
+    rx: Option<Receiver<(usize, ChapterText, Difficulty)>>,
+}
+
+impl ChapterCache {
+    /// Starts parsing `bytes`, which must be the same EPUB the display was
+    /// opened with. Chapters are indexed by their position in the TOC.
+    pub fn spawn(bytes: Vec<u8>, chapter_count: usize, config: Arc<Config>) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            let Ok(mut book) = Epub::new(bytes) else {
+                return;
+            };
+            for i in 0..chapter_count {
+                let Some(chapter) = book.chapter_by_toc_index(i) else {
+                    continue;
+                };
+                let spine_idx = chapter.index_in_spine();
+                let Ok(text) = ChapterText::extract(&mut book, spine_idx, &config) else {
+                    continue;
+                };
+                let difficulty = Difficulty::of(&text.text);
+                if tx.send((i, text, difficulty)).is_err() {
+                    return;
+                }
+            }
+        });
+
+        Self {
+            chapters: (0..chapter_count).map(|_| None).collect(),
+            difficulties: vec![None; chapter_count],
+            rx: Some(rx),
+        }
+    }
+
+    /// Collects chapters parsed since the last call.
+    fn poll(&mut self) {
+        let Some(rx) = &self.rx else {
+            return;
+        };
+        loop {
+            match rx.try_recv() {
+                Ok((i, text, difficulty)) => {
+                    self.chapters[i] = Some(text);
+                    self.difficulties[i] = Some(difficulty);
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.rx = None;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Whether the background thread has finished.
+    pub fn is_complete(&mut self) -> bool {
+        self.poll();
+        self.rx.is_none()
+    }
+
+    /// Number of chapters parsed so far.
+    pub fn parsed(&mut self) -> usize {
+        self.poll();
+        self.chapters.iter().flatten().count()
+    }
+
+    pub fn get(&mut self, chapter: usize) -> Option<&ChapterText> {
+        self.poll();
+        self.chapters.get(chapter)?.as_ref()
+    }
+
+    pub fn difficulty(&mut self, chapter: usize) -> Option<Difficulty> {
+        self.poll();
+        *self.difficulties.get(chapter)?
+    }
+
+    /// Chapters parsed so far, along with their TOC index.
+    pub fn iter(&mut self) -> impl Iterator<Item = (usize, &ChapterText)> {
+        self.poll();
+        self.chapters
+            .iter()
+            .enumerate()
+            .filter_map(|(i, c)| Some((i, c.as_ref()?)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.chapters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chapters.is_empty()
+    }
+}
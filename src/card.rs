@@ -0,0 +1,88 @@
+//! Result cards summarising a finished session, for showing on screen or
+//! saving to share.
+
+#[cfg(feature = "png")]
+mod raster;
+
+use crate::{
+    difficulty,
+    stats::{self, Metric, Rerun, SentenceSpeed, Stats},
+};
+
+/// Slices a session is split into for the speed graph.
+pub const SPEED_BUCKETS: usize = 36;
+
+pub struct Card {
+    /// Book the session was in, with its author if known.
+    pub title: String,
+    pub chapter: String,
+    pub stats: Stats,
+    /// Speed given in the headline.
+    pub metric: Metric,
+    /// WPM over equal slices of the session.
+    pub speeds: Vec<f64>,
+    /// Speed of each sentence typed in full.
+    pub sentences: Vec<SentenceSpeed>,
+    /// Difficulty score of the chapter, if known.
+    pub difficulty: Option<f64>,
+    /// Comparison with the last time the chapter was typed to the end, if
+    /// it has been.
+    pub rerun: Option<Rerun>,
+}
+
+impl Card {
+    pub fn headline(&self) -> String {
+        format!(
+            "{} · {:.1}% accuracy",
+            self.stats.format_speed(self.metric),
+            self.stats.accuracy() * 100.0
+        )
+    }
+
+    /// Gross and net speed, whichever the headline gives.
+    pub fn speeds(&self) -> String {
+        format!(
+            "{:.0} wpm gross · {:.0} wpm net",
+            self.stats.wpm(),
+            self.stats.net_wpm()
+        )
+    }
+
+    pub fn details(&self) -> String {
+        let mut details = format!(
+            "{} · {} chars · {} errors",
+            stats::format_duration(self.stats.elapsed),
+            self.stats.chars,
+            self.stats.mistakes
+        );
+        if self.stats.forgiven > 0 {
+            details += &format!(" ({} overtyped)", self.stats.forgiven);
+        }
+        details
+    }
+
+    /// How hard the chapter was and the speed adjusted for it.
+    pub fn difficulty(&self) -> Option<String> {
+        let difficulty = self.difficulty?;
+        Some(format!(
+            "difficulty {difficulty:.2} · {:.0} adjusted wpm",
+            difficulty::adjusted_wpm(self.stats.wpm(), difficulty)
+        ))
+    }
+
+    /// Speed and mistakes against the last run through the chapter.
+    pub fn rerun(&self) -> Option<String> {
+        let rerun = self.rerun.as_ref()?;
+        Some(format!(
+            "vs last run: {:+.1} wpm · {} mistakes fixed · {} new",
+            rerun.mean_wpm(),
+            rerun.fixed,
+            rerun.regressed
+        ))
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png(&self, path: &std::path::Path) -> anyhow::Result<()> {
+        raster::write(self, path)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use super::Card;
+
+const WIDTH: usize = 640;
+const HEIGHT: usize = 320;
+const MARGIN: usize = 24;
+
+type Rgb = [u8; 3];
+const BACKGROUND: Rgb = [30, 30, 46];
+const FOREGROUND: Rgb = [205, 214, 244];
+const DIM: Rgb = [127, 132, 156];
+const ACCENT: Rgb = [137, 180, 250];
+
+/// 5x7 glyphs, one byte per row with the low five bits set for lit pixels.
+#[rustfmt::skip]
+const FONT: &[(char, [u8; 7])] = &[
+    ('0', [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E]),
+    ('1', [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('2', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F]),
+    ('3', [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E]),
+    ('4', [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02]),
+    ('5', [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E]),
+    ('6', [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E]),
+    ('7', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08]),
+    ('8', [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E]),
+    ('9', [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C]),
+    ('A', [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11]),
+    ('B', [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E]),
+    ('C', [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E]),
+    ('D', [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C]),
+    ('E', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F]),
+    ('F', [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10]),
+    ('G', [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F]),
+    ('H', [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11]),
+    ('I', [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E]),
+    ('J', [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C]),
+    ('K', [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11]),
+    ('L', [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F]),
+    ('M', [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11]),
+    ('N', [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11]),
+    ('O', [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('P', [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10]),
+    ('Q', [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D]),
+    ('R', [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11]),
+    ('S', [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E]),
+    ('T', [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04]),
+    ('U', [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E]),
+    ('V', [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04]),
+    ('W', [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A]),
+    ('X', [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11]),
+    ('Y', [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04]),
+    ('Z', [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F]),
+    ('.', [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C]),
+    (',', [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08]),
+    (':', [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00]),
+    ('%', [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03]),
+    ('-', [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00]),
+    ('\'', [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00]),
+    ('/', [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00]),
+    ('!', [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04]),
+    ('?', [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04]),
+    ('(', [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02]),
+    (')', [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08]),
+    ('·', [0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00]),
+];
+
+struct Canvas(Vec<u8>);
+
+impl Canvas {
+    fn new() -> Self {
+        Self(BACKGROUND.repeat(WIDTH * HEIGHT))
+    }
+
+    fn fill(&mut self, x: usize, y: usize, w: usize, h: usize, color: Rgb) {
+        for row in y..(y + h).min(HEIGHT) {
+            for col in x..(x + w).min(WIDTH) {
+                let i = 3 * (row * WIDTH + col);
+                self.0[i..i + 3].copy_from_slice(&color);
+            }
+        }
+    }
+
+    /// Draws `text` in capitals, dropping characters the font lacks and
+    /// anything past the right margin.
+    fn text(&mut self, x: usize, y: usize, scale: usize, color: Rgb, text: &str) {
+        let advance = 6 * scale;
+        let mut x = x;
+        for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+            if x + 5 * scale > WIDTH - MARGIN {
+                break;
+            }
+            if let Some((_, rows)) = FONT.iter().find(|(g, _)| *g == c) {
+                for (dy, row) in rows.iter().enumerate() {
+                    for dx in (0..5).filter(|dx| row & (0x10 >> dx) != 0) {
+                        self.fill(x + dx * scale, y + dy * scale, scale, scale, color);
+                    }
+                }
+            }
+            x += advance;
+        }
+    }
+}
+
+pub fn write(card: &Card, path: &Path) -> anyhow::Result<()> {
+    let mut canvas = Canvas::new();
+    canvas.text(MARGIN, MARGIN, 3, FOREGROUND, &card.title);
+    canvas.text(MARGIN, 60, 2, DIM, &card.chapter);
+    canvas.text(MARGIN, 96, 4, ACCENT, &card.headline());
+    canvas.text(MARGIN, 140, 2, FOREGROUND, &card.details());
+
+    let graph_top = 176;
+    let graph_height = HEIGHT - MARGIN - graph_top;
+    let graph_width = WIDTH - 2 * MARGIN;
+    let max = card.speeds.iter().copied().fold(0.0, f64::max);
+    if max > 0.0 {
+        let bar = graph_width / card.speeds.len();
+        for (i, speed) in card.speeds.iter().enumerate() {
+            let h = (speed / max * graph_height as f64).round() as usize;
+            let x = MARGIN + i * bar;
+            canvas.fill(x, graph_top + graph_height - h, bar.max(2) - 1, h, ACCENT);
+        }
+    }
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        WIDTH as u32,
+        HEIGHT as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(&canvas.0)?;
+    Ok(())
+}
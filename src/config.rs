@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assignment::Target,
+    backend::BackspaceMode,
+    filter::{Filters, KeySet, Replacements},
+    keymap::Keymap,
+    paths,
+    stats::Metric,
+    store,
+    translate::InputMethod,
+};
+
+/// Settings that stay fixed for the lifetime of a [`Display`](crate::term::Display).
+/// Put together from several [`Origin`]s by [`Layers::resolve`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Width of the text view, in characters.
+    pub width: u16,
+    /// Most of the screen's width the text may take, from 0 to 1.
+    pub max_width: f64,
+    /// Columns kept clear to the left of the text.
+    pub margin_left: u16,
+    /// Columns kept clear to the right of the text.
+    pub margin_right: u16,
+    /// Where the text sits between the margins.
+    pub column: Column,
+    /// What to show beside the text while typing.
+    pub gutter: Gutter,
+    /// Type in the left half of the screen, with the paragraphs either side
+    /// of the current one in the right half.
+    pub context_pane: bool,
+    /// How far down the screen the typing line sits, from 0 at the top to 1
+    /// at the bottom.
+    pub anchor: f64,
+    pub backspace: BackspaceMode,
+    /// Milliseconds after a mistake in which typing the right character
+    /// fixes it in place, as a slip caught straight away, rather than
+    /// moving on. 0 turns this off.
+    pub grace_ms: u64,
+    /// Send the cursor back to the start of the word on a mistake, so every
+    /// word is typed through correctly in one go.
+    pub repeat_words: bool,
+    /// Keep the cursor on a mistake until the right character is typed,
+    /// rather than moving on past it.
+    pub stop_on_error: bool,
+    /// Speed shown on result cards and in the status corner.
+    pub metric: Metric,
+    /// Show the time taken and speed so far in the top right corner while
+    /// typing.
+    pub status: bool,
+    /// Seconds of typing to also show a live speed and accuracy for in the
+    /// status corner, updated on every keystroke. 0 leaves them out.
+    pub status_window: u64,
+    /// Show the word being typed in large letters above the text.
+    pub large_word: bool,
+    /// Join lines broken mid-paragraph where they look left over from the
+    /// plain text a book was converted from.
+    pub join_lines: bool,
+    /// Words that keep their hyphen when joining lines split at it.
+    pub hyphenated: Vec<String>,
+    /// Characters swapped for ones easier to type, on top of the built-in
+    /// dashes and ellipses. Setting one to itself keeps it as it is.
+    pub normalize: BTreeMap<String, String>,
+    /// Text changed in chapters before typing, on top of any rules in the
+    /// book's sidecar.
+    pub replace: Replacements,
+    /// Text removed from chapters before typing.
+    pub exclude: Filters,
+    /// Leave out Project Gutenberg headers, footers and license chapters.
+    pub strip_gutenberg: bool,
+    /// Only keep paragraphs that can be typed with these keys.
+    pub keys: Option<KeySet>,
+    /// Put dialogue in double quotes in books that use single quotes for it,
+    /// turning any double quotes inside into single ones.
+    pub double_quotes: bool,
+    /// Directory of books to pick from with `ept library`.
+    pub library: Option<PathBuf>,
+    /// Name under which sessions are recorded.
+    pub profile: String,
+    /// Keyboard being typed on, stamped onto each session.
+    pub keyboard: Option<String>,
+    /// Encrypt the profile's stored sessions with a passphrase, taken from
+    /// `EPT_PASSPHRASE` or asked for at startup.
+    pub encrypt: bool,
+    /// Show a pace caret to type against.
+    pub pace: Option<PaceConfig>,
+    /// End each session this far past where it started, for quick tests of
+    /// a fixed length.
+    pub limit: Option<Limit>,
+    /// Type a short drill before the first chapter of a run.
+    pub warmup: bool,
+    /// Treat books as courses, where each chapter unlocks once the one
+    /// before has been typed to these targets.
+    pub course: Option<Target>,
+    /// Accuracy, from 0 to 1, below which a paragraph is saved to type
+    /// again on later days.
+    pub review_below: Option<f64>,
+    /// How key events are turned into typing.
+    pub input: InputMethod,
+    /// Keys for moving around and for commands while typing.
+    pub bindings: Keymap,
+    /// Most times a second animations are redrawn.
+    pub max_fps: u32,
+    /// What to do on a mistake, besides colouring it.
+    pub alert: Alert,
+    /// Where to sync sessions with `ept sync`.
+    pub sync: Option<SyncConfig>,
+    /// Settings for particular profiles, overriding the ones above.
+    pub profiles: BTreeMap<String, toml::Table>,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Alert {
+    #[default]
+    None,
+    /// Ring the terminal bell.
+    Bell,
+    /// Flash the edges of the screen.
+    Flash,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gutter {
+    #[default]
+    None,
+    /// Numbers to the left of each paragraph.
+    Paragraphs,
+    /// A bar down the right edge of the screen showing how far through the
+    /// chapter the cursor is.
+    Progress,
+    /// A scrollbar down the right edge of the screen, coloured by how many
+    /// mistakes were made in each part of the chapter.
+    Errors,
+}
+
+/// How long a session may run, written `{ words = 50 }` or
+/// `{ chars = 300 }`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Limit {
+    Words(usize),
+    Chars(usize),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PaceConfig {
+    /// Speed of the caret, or the starting point for an adaptive one with no
+    /// history to go on.
+    pub wpm: f64,
+    /// Follow your average over the last week instead of a fixed speed.
+    pub adaptive: bool,
+    /// Added to the average when adaptive, to keep pushing you.
+    pub delta: f64,
+}
+
+impl Default for PaceConfig {
+    fn default() -> Self {
+        Self {
+            wpm: 40.0,
+            adaptive: false,
+            delta: 5.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncConfig {
+    /// Base URL; each profile's log lives at `<url>/<profile>/sessions.jsonl`.
+    pub url: String,
+    /// Sent as a bearer token, if set. Never shown by `ept config show`.
+    #[serde(skip_serializing)]
+    pub token: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            width: 60,
+            max_width: 1.0,
+            margin_left: 0,
+            margin_right: 0,
+            column: Column::default(),
+            gutter: Gutter::default(),
+            context_pane: false,
+            anchor: 0.5,
+            backspace: BackspaceMode::default(),
+            grace_ms: 0,
+            repeat_words: false,
+            stop_on_error: false,
+            metric: Metric::default(),
+            status: false,
+            status_window: 0,
+            large_word: false,
+            join_lines: true,
+            hyphenated: Vec::new(),
+            normalize: BTreeMap::new(),
+            replace: Replacements::default(),
+            exclude: Filters::default(),
+            strip_gutenberg: true,
+            keys: None,
+            double_quotes: false,
+            library: None,
+            profile: store::DEFAULT_PROFILE.to_owned(),
+            keyboard: None,
+            encrypt: false,
+            pace: None,
+            limit: None,
+            warmup: false,
+            course: None,
+            review_below: None,
+            input: InputMethod::default(),
+            bindings: Keymap::default(),
+            max_fps: 30,
+            alert: Alert::default(),
+            sync: None,
+            profiles: BTreeMap::new(),
+        }
+    }
+}
+
+/// Where a setting came from. Later origins take precedence over earlier
+/// ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Origin {
+    Default,
+    /// `config.toml`.
+    File,
+    /// The current profile's section of `config.toml`, `[profiles.<name>]`.
+    Profile,
+    /// The `[config]` section of the book's sidecar.
+    Book,
+    /// Command line flags.
+    Flag,
+}
+
+impl Origin {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Default => "default",
+            Self::File => "config.toml",
+            Self::Profile => "profile",
+            Self::Book => "book",
+            Self::Flag => "flag",
+        }
+    }
+}
+
+/// Settings from each [`Origin`], to be combined into a [`Config`].
+#[derive(Debug, Clone, Default)]
+pub struct Layers(Vec<(Origin, toml::Table)>);
+
+impl Layers {
+    /// Starts from the config file, if there is one.
+    pub fn load() -> anyhow::Result<Self> {
+        let mut layers = Self::default();
+        let Some(path) = paths::config_file() else {
+            return Ok(layers);
+        };
+        match fs::read_to_string(&path) {
+            Ok(s) => layers.set(Origin::File, toml::from_str(&s)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        Ok(layers)
+    }
+
+    /// Adds settings from `origin`, over any it had already.
+    pub fn set(&mut self, origin: Origin, table: toml::Table) {
+        match self.0.iter_mut().find(|(s, _)| *s == origin) {
+            Some((_, layer)) => {
+                for (key, value) in table {
+                    merge(layer, key, value);
+                }
+            }
+            None => self.0.push((origin, table)),
+        }
+    }
+
+    /// Every layer, lowest precedence first, with the profile's section of
+    /// the config file in its place.
+    fn ordered(&self) -> Vec<(Origin, toml::Table)> {
+        let mut layers = self.0.clone();
+        layers.sort_by_key(|(origin, _)| *origin);
+        let profile = layers
+            .iter()
+            .rev()
+            .find_map(|(_, layer)| layer.get("profile")?.as_str())
+            .unwrap_or(store::DEFAULT_PROFILE);
+        let section = layers
+            .iter()
+            .find(|(origin, _)| *origin == Origin::File)
+            .and_then(|(_, file)| file.get("profiles")?.get(profile)?.as_table())
+            .cloned();
+        if let Some(section) = section {
+            layers.push((Origin::Profile, section));
+            layers.sort_by_key(|(origin, _)| *origin);
+        }
+        layers
+    }
+
+    /// The settings in effect, along with where each one set came from.
+    /// Settings left out of the map are defaults.
+    pub fn resolve(&self) -> anyhow::Result<(Config, BTreeMap<String, Origin>)> {
+        let mut merged = toml::Table::new();
+        let mut origins = BTreeMap::new();
+        for (origin, layer) in self.ordered() {
+            for (key, value) in layer {
+                origins.insert(key.clone(), origin);
+                merge(&mut merged, key, value);
+            }
+        }
+        let config: Config = toml::Value::Table(merged).try_into()?;
+        if !store::is_valid_profile(&config.profile) {
+            anyhow::bail!(
+                "invalid profile name {:?}: use letters, digits, '-' and '_'",
+                config.profile
+            );
+        }
+        Ok((config, origins))
+    }
+}
+
+/// Sets `key` in `table` to `value`, or if both are tables, sets each of
+/// `value`'s keys in turn.
+fn merge(table: &mut toml::Table, key: String, value: toml::Value) {
+    match (table.get_mut(&key), value) {
+        (Some(toml::Value::Table(old)), toml::Value::Table(new)) => {
+            for (key, value) in new {
+                merge(old, key, value);
+            }
+        }
+        (_, value) => {
+            table.insert(key, value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn merging_tables_keeps_keys_not_overridden() {
+        let mut pace = toml::Table::new();
+        pace.insert("wpm".to_owned(), 40.0.into());
+        pace.insert("delta".to_owned(), 5.0.into());
+        let mut table = toml::Table::new();
+        table.insert("pace".to_owned(), pace.into());
+
+        let mut flag = toml::Table::new();
+        flag.insert("wpm".to_owned(), 60.0.into());
+        merge(&mut table, "pace".to_owned(), flag.into());
+
+        let Some(toml::Value::Table(pace)) = table.get("pace") else {
+            panic!("pace isn't a table");
+        };
+        assert_eq!(pace.get("wpm"), Some(&toml::Value::Float(60.0)));
+        assert_eq!(pace.get("delta"), Some(&toml::Value::Float(5.0)));
+    }
+}
@@ -0,0 +1,272 @@
+//! Which characters of each chapter have ever been typed correctly, and so
+//! which books have been typed all the way through, along with where
+//! mistakes have been made.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Fixed-size set of character indices.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Bitmap {
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl Bitmap {
+    pub fn new(len: usize) -> Self {
+        Self {
+            len,
+            words: vec![0; len.div_ceil(64)],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn set(&mut self, i: usize) {
+        if i < self.len {
+            self.words[i / 64] |= 1 << (i % 64);
+        }
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        i < self.len && self.words[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.count() == self.len
+    }
+
+    /// Adds every index in `other`, if it's the same size.
+    pub fn union(&mut self, other: &Self) {
+        if other.len == self.len {
+            for (word, other) in self.words.iter_mut().zip(&other.words) {
+                *word |= other;
+            }
+        }
+    }
+
+    /// Lowest index not in the set.
+    pub fn first_unset(&self) -> Option<usize> {
+        let (i, word) = self
+            .words
+            .iter()
+            .enumerate()
+            .find(|(_, &w)| w != u64::MAX)?;
+        Some(i * 64 + word.trailing_ones() as usize).filter(|&i| i < self.len)
+    }
+}
+
+/// `<len>:<words in hex>`, which keeps long chapters to a reasonable size on
+/// disk.
+impl From<Bitmap> for String {
+    fn from(bitmap: Bitmap) -> Self {
+        let mut s = format!("{}:", bitmap.len);
+        for word in bitmap.words {
+            s.push_str(&format!("{word:016x}"));
+        }
+        s
+    }
+}
+
+impl TryFrom<String> for Bitmap {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let (len, hex) = s
+            .split_once(':')
+            .ok_or_else(|| anyhow::anyhow!("bitmap has no length"))?;
+        let len: usize = len.parse()?;
+        anyhow::ensure!(hex.len() == len.div_ceil(64) * 16, "bitmap has wrong size");
+        let words = (0..hex.len())
+            .step_by(16)
+            .map(|i| u64::from_str_radix(&hex[i..i + 16], 16))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { len, words })
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BookCoverage {
+    /// Display title, for listing finished books.
+    pub title: String,
+    /// Keyed by TOC index.
+    pub chapters: BTreeMap<usize, Bitmap>,
+    /// Characters a mistake has ever been made on, keyed by TOC index.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub mistakes: BTreeMap<usize, Bitmap>,
+    /// Seconds since the Unix epoch when the book was first completed.
+    pub completed: Option<u64>,
+}
+
+impl BookCoverage {
+    /// Marks `chars`, which are character indices into a chapter `len`
+    /// characters long, as typed correctly.
+    pub fn add(&mut self, chapter: usize, len: usize, chars: impl IntoIterator<Item = usize>) {
+        add_to(&mut self.chapters, chapter, len, chars);
+    }
+
+    /// Marks `chars` as having been mistyped, like [`add`](Self::add).
+    pub fn add_mistakes(
+        &mut self,
+        chapter: usize,
+        len: usize,
+        chars: impl IntoIterator<Item = usize>,
+    ) {
+        add_to(&mut self.mistakes, chapter, len, chars);
+    }
+
+    /// Adds what was typed in `other`, as kept on another machine. Chapters
+    /// whose text differs in length, from other filter settings, keep this
+    /// side's coverage.
+    pub fn merge(&mut self, other: &Self) {
+        if self.title.is_empty() {
+            self.title.clone_from(&other.title);
+        }
+        for (ours, theirs) in [
+            (&mut self.chapters, &other.chapters),
+            (&mut self.mistakes, &other.mistakes),
+        ] {
+            for (&chapter, bitmap) in theirs {
+                ours.entry(chapter)
+                    .and_modify(|b| b.union(bitmap))
+                    .or_insert_with(|| bitmap.clone());
+            }
+        }
+        self.completed = match (self.completed, other.completed) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+
+    /// Whether every chapter in `body` is fully covered.
+    pub fn covers(&self, body: impl IntoIterator<Item = usize>) -> bool {
+        body.into_iter()
+            .all(|c| self.chapters.get(&c).is_some_and(Bitmap::is_full))
+    }
+}
+
+fn add_to(
+    chapters: &mut BTreeMap<usize, Bitmap>,
+    chapter: usize,
+    len: usize,
+    chars: impl IntoIterator<Item = usize>,
+) {
+    let bitmap = chapters.entry(chapter).or_insert_with(|| Bitmap::new(len));
+    // The chapter's text changes with filter settings; start over.
+    if bitmap.len() != len {
+        *bitmap = Bitmap::new(len);
+    }
+    for i in chars {
+        bitmap.set(i);
+    }
+}
+
+/// Coverage of every book typed under a profile, keyed by book key.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Coverage {
+    pub books: BTreeMap<String, BookCoverage>,
+}
+
+impl Coverage {
+    /// Adds the coverage of every book in `other`, see
+    /// [`BookCoverage::merge`].
+    pub fn merge(&mut self, other: &Self) {
+        for (key, book) in &other.books {
+            self.books.entry(key.clone()).or_default().merge(book);
+        }
+    }
+
+    /// Books completed so far, most recent first.
+    pub fn finished(&self) -> Vec<(&str, &BookCoverage)> {
+        let mut finished: Vec<_> = self
+            .books
+            .iter()
+            .filter(|(_, b)| b.completed.is_some())
+            .map(|(k, b)| (k.as_str(), b))
+            .collect();
+        finished.sort_by_key(|(_, b)| std::cmp::Reverse(b.completed));
+        finished
+    }
+}
+
+/// Chapter titles that mark front or back matter rather than the body of
+/// the book, which doesn't need typing for the book to count as finished.
+const NON_BODY_TITLES: &[&str] = &[
+    "acknowledgements",
+    "acknowledgments",
+    "also by",
+    "about the author",
+    "contents",
+    "copyright",
+    "cover",
+    "dedication",
+    "index",
+    "table of contents",
+    "title page",
+];
+
+pub fn is_body_matter(title: &str) -> bool {
+    let title = title.trim().to_lowercase();
+    !NON_BODY_TITLES.iter().any(|t| title.starts_with(t))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bitmaps_round_trip_through_strings() {
+        let mut bitmap = Bitmap::new(70);
+        bitmap.set(0);
+        bitmap.set(69);
+        let s = String::from(bitmap.clone());
+        assert_eq!(Bitmap::try_from(s).unwrap(), bitmap);
+        assert_eq!(bitmap.count(), 2);
+        assert!(bitmap.contains(69) && !bitmap.contains(68) && !bitmap.contains(70));
+    }
+
+    #[test]
+    fn it_finds_the_first_gap() {
+        let mut bitmap = Bitmap::new(66);
+        (0..65).for_each(|i| bitmap.set(i));
+        assert_eq!(bitmap.first_unset(), Some(65));
+        bitmap.set(65);
+        assert_eq!(bitmap.first_unset(), None);
+    }
+
+    #[test]
+    fn merging_keeps_what_either_side_typed() {
+        let mut here = BookCoverage::default();
+        here.add(0, 4, [0, 1]);
+        here.add(1, 3, [0]);
+        let mut there = BookCoverage {
+            completed: Some(5),
+            ..Default::default()
+        };
+        there.add(0, 4, [2, 3]);
+        there.add(1, 5, [4]);
+        there.add(2, 2, [1]);
+
+        here.merge(&there);
+        assert!(here.chapters[&0].is_full());
+        assert_eq!(here.chapters[&1], {
+            let mut b = Bitmap::new(3);
+            b.set(0);
+            b
+        });
+        assert_eq!(here.chapters[&2].count(), 1);
+        assert_eq!(here.completed, Some(5));
+    }
+}
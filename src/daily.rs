@@ -0,0 +1,188 @@
+//! Passage of the day: a short test picked from a list built into ept, the
+//! same for everyone on a given day, with a code to compare scores by.
+//!
+//! Days change at midnight UTC, so everyone has the same passage at once.
+//! Codes carry a hash of the text as well as the date, so a code from a
+//! version of ept with a different list is refused rather than typed
+//! against the wrong passage.
+
+use crate::date;
+
+pub struct Passage {
+    pub title: &'static str,
+    pub author: &'static str,
+    pub text: &'static str,
+}
+
+const PASSAGES: &[Passage] = &[
+    Passage {
+        title: "Pride and Prejudice",
+        author: "Jane Austen",
+        text: "It is a truth universally acknowledged, that a single man in possession of a \
+               good fortune, must be in want of a wife. However little known the feelings or \
+               views of such a man may be on his first entering a neighbourhood, this truth is \
+               so well fixed in the minds of the surrounding families, that he is considered \
+               the rightful property of some one or other of their daughters.",
+    },
+    Passage {
+        title: "A Tale of Two Cities",
+        author: "Charles Dickens",
+        text: "It was the best of times, it was the worst of times, it was the age of wisdom, \
+               it was the age of foolishness, it was the epoch of belief, it was the epoch of \
+               incredulity, it was the season of Light, it was the season of Darkness, it was \
+               the spring of hope, it was the winter of despair, we had everything before us, \
+               we had nothing before us, we were all going direct to Heaven, we were all going \
+               direct the other way.",
+    },
+    Passage {
+        title: "Frankenstein",
+        author: "Mary Shelley",
+        text: "It was on a dreary night of November that I beheld the accomplishment of my \
+               toils. With an anxiety that almost amounted to agony, I collected the \
+               instruments of life around me, that I might infuse a spark of being into the \
+               lifeless thing that lay at my feet.",
+    },
+    Passage {
+        title: "Anna Karenina",
+        author: "Leo Tolstoy",
+        text: "Happy families are all alike; every unhappy family is unhappy in its own way. \
+               Everything was in confusion in the Oblonskys' house. The wife had discovered \
+               that the husband was carrying on an intrigue with a French girl, who had been a \
+               governess in their family, and she had announced to her husband that she could \
+               not go on living in the same house with him.",
+    },
+    Passage {
+        title: "Metamorphosis",
+        author: "Franz Kafka",
+        text: "One morning, when Gregor Samsa woke from troubled dreams, he found himself \
+               transformed in his bed into a horrible vermin. He lay on his armour-like back, \
+               and if he lifted his head a little he could see his brown belly, slightly domed \
+               and divided by arches into stiff sections.",
+    },
+    Passage {
+        title: "Alice's Adventures in Wonderland",
+        author: "Lewis Carroll",
+        text: "Alice was beginning to get very tired of sitting by her sister on the bank, and \
+               of having nothing to do: once or twice she had peeped into the book her sister \
+               was reading, but it had no pictures or conversations in it, 'and what is the use \
+               of a book,' thought Alice 'without pictures or conversations?'",
+    },
+    Passage {
+        title: "A Christmas Carol",
+        author: "Charles Dickens",
+        text: "Marley was dead: to begin with. There is no doubt whatever about that. The \
+               register of his burial was signed by the clergyman, the clerk, the undertaker, \
+               and the chief mourner. Scrooge signed it: and Scrooge's name was good upon \
+               'Change, for anything he chose to put his hand to. Old Marley was as dead as a \
+               door-nail.",
+    },
+    Passage {
+        title: "Jane Eyre",
+        author: "Charlotte Brontë",
+        text: "There was no possibility of taking a walk that day. We had been wandering, \
+               indeed, in the leafless shrubbery an hour in the morning; but since dinner (Mrs. \
+               Reed, when there was no company, dined early) the cold winter wind had brought \
+               with it clouds so sombre, and a rain so penetrating, that further out-door \
+               exercise was now out of the question.",
+    },
+    Passage {
+        title: "The War of the Worlds",
+        author: "H. G. Wells",
+        text: "No one would have believed in the last years of the nineteenth century that \
+               this world was being watched keenly and closely by intelligences greater than \
+               man's and yet as mortal as his own; that as men busied themselves about their \
+               various concerns they were scrutinised and studied, perhaps almost as narrowly \
+               as a man with a microscope might scrutinise the transient creatures that swarm \
+               and multiply in a drop of water.",
+    },
+    Passage {
+        title: "Adventures of Huckleberry Finn",
+        author: "Mark Twain",
+        text: "You don't know about me without you have read a book by the name of The \
+               Adventures of Tom Sawyer; but that ain't no matter. That book was made by Mr. \
+               Mark Twain, and he told the truth, mainly. There was things which he stretched, \
+               but mainly he told the truth.",
+    },
+    Passage {
+        title: "A Scandal in Bohemia",
+        author: "Arthur Conan Doyle",
+        text: "To Sherlock Holmes she is always the woman. I have seldom heard him mention her \
+               under any other name. In his eyes she eclipses and predominates the whole of her \
+               sex. It was not that he felt any emotion akin to love for Irene Adler. All \
+               emotions, and that one particularly, were abhorrent to his cold, precise but \
+               admirably balanced mind.",
+    },
+    Passage {
+        title: "Great Expectations",
+        author: "Charles Dickens",
+        text: "My father's family name being Pirrip, and my Christian name Philip, my infant \
+               tongue could make of both names nothing longer or more explicit than Pip. So, I \
+               called myself Pip, and came to be called Pip.",
+    },
+    Passage {
+        title: "Emma",
+        author: "Jane Austen",
+        text: "Emma Woodhouse, handsome, clever, and rich, with a comfortable home and happy \
+               disposition, seemed to unite some of the best blessings of existence; and had \
+               lived nearly twenty-one years in the world with very little to distress or vex \
+               her.",
+    },
+];
+
+/// The passage for `day`, in days since 1970-01-01.
+pub fn for_day(day: i64) -> &'static Passage {
+    // Consecutive days would otherwise step through the list in order.
+    let mut x = (day as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^= x >> 31;
+    &PASSAGES[(x % PASSAGES.len() as u64) as usize]
+}
+
+/// FNV-1a hash of `text`, cut down to four hex digits for codes.
+fn fingerprint(text: &str) -> u16 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in text.bytes() {
+        hash ^= byte as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    (hash ^ (hash >> 16) ^ (hash >> 32) ^ (hash >> 48)) as u16
+}
+
+/// Code for the passage of `day`, such as `2026-10-16-3f2a`.
+pub fn code(day: i64) -> String {
+    let (year, month, date) = date::civil_from_days(day);
+    let fingerprint = fingerprint(for_day(day).text);
+    format!("{year:04}-{month:02}-{date:02}-{fingerprint:04x}")
+}
+
+/// The day a code from [`code`] is for.
+pub fn parse_code(code: &str) -> anyhow::Result<i64> {
+    let invalid = || anyhow::anyhow!("{code:?} isn't a passage code, like 2026-10-16-3f2a");
+    let (date, fingerprint) = code.trim().rsplit_once('-').ok_or_else(invalid)?;
+    let timestamp = date::parse_timestamp(date).ok_or_else(invalid)?;
+    let fingerprint = u16::from_str_radix(fingerprint, 16).map_err(|_| invalid())?;
+    let day = date::day_of(timestamp);
+    anyhow::ensure!(
+        fingerprint == self::fingerprint(for_day(day).text),
+        "{code} is for a passage this version of ept doesn't have"
+    );
+    Ok(day)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn codes_name_the_day_they_were_made_for() {
+        for day in [0, 20_000, 20_742] {
+            assert_eq!(parse_code(&code(day)).unwrap(), day);
+        }
+        let right = code(20_742);
+        let (date, _) = right.rsplit_once('-').unwrap();
+        let wrong = fingerprint(for_day(20_742).text) ^ 1;
+        assert!(parse_code(&format!("{date}-{wrong:04x}")).is_err());
+        assert!(parse_code("yesterday").is_err());
+    }
+}
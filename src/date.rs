@@ -0,0 +1,120 @@
+//! Just enough calendar arithmetic for timestamps, without pulling in a
+//! date crate. All dates are UTC and proleptic Gregorian.
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Days since 1970-01-01 for a civil date.
+/// <http://howardhinnant.github.io/date_algorithms.html#days_from_civil>
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = i64::from(month);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Civil date for days since 1970-01-01, as `(year, month, day)`.
+/// <http://howardhinnant.github.io/date_algorithms.html#civil_from_days>
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 for a Unix timestamp.
+pub fn day_of(timestamp: u64) -> i64 {
+    (timestamp / SECS_PER_DAY) as i64
+}
+
+/// Day of the week for days since 1970-01-01, counting from Monday as 0.
+pub fn weekday(days: i64) -> u32 {
+    // 1970-01-01 was a Thursday.
+    (days + 3).rem_euclid(7) as u32
+}
+
+/// `YYYY-MM-DD` for a Unix timestamp.
+pub fn format_date(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / SECS_PER_DAY) as i64);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// How long before `now` the day of `timestamp` was, roughly: "today",
+/// "yesterday", "3d ago", "5w ago" and so on.
+pub fn format_ago(timestamp: u64, now: u64) -> String {
+    match day_of(now) - day_of(timestamp) {
+        ..=0 => "today".to_owned(),
+        1 => "yesterday".to_owned(),
+        days @ 2..=13 => format!("{days}d ago"),
+        days @ 14..=59 => format!("{}w ago", days / 7),
+        days @ 60..=729 => format!("{}mo ago", days / 30),
+        days => format!("{}y ago", days / 365),
+    }
+}
+
+/// Parses `YYYY-MM-DD`, optionally followed by `T` or a space and
+/// `HH:MM[:SS]`, into seconds since the Unix epoch. Anything after the
+/// seconds, such as a fraction or `Z`, is ignored.
+pub fn parse_timestamp(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (date, time) = match s.split_once(['T', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (s, None),
+    };
+
+    let mut parts = date.splitn(3, '-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let mut secs = 0;
+    if let Some(time) = time {
+        let mut parts = time.splitn(3, ':');
+        let hours: u64 = parts.next()?.parse().ok()?;
+        let minutes: u64 = parts.next()?.parse().ok()?;
+        let seconds: u64 = match parts.next() {
+            Some(s) => s.get(..2).unwrap_or(s).parse().ok()?,
+            None => 0,
+        };
+        secs = hours * 3600 + minutes * 60 + seconds;
+    }
+
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(days * SECS_PER_DAY + secs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_parses_timestamps() {
+        assert_eq!(parse_timestamp("1970-01-01"), Some(0));
+        assert_eq!(parse_timestamp("2000-03-01 00:00:01"), Some(951868801));
+        assert_eq!(parse_timestamp("2024-02-29T12:30:00Z"), Some(1709209800));
+        assert_eq!(parse_timestamp("2024-13-01"), None);
+        assert_eq!(format_date(1709209800), "2024-02-29");
+        assert_eq!(weekday(day_of(1709209800)), 3);
+    }
+
+    #[test]
+    fn it_says_how_long_ago() {
+        let now = 1709209800;
+        assert_eq!(format_ago(now - 60, now), "today");
+        assert_eq!(format_ago(now - SECS_PER_DAY, now), "yesterday");
+        assert_eq!(format_ago(now - 3 * SECS_PER_DAY, now), "3d ago");
+        assert_eq!(format_ago(now - 20 * SECS_PER_DAY, now), "2w ago");
+        assert_eq!(format_ago(now - 800 * SECS_PER_DAY, now), "2y ago");
+    }
+}
@@ -0,0 +1,100 @@
+//! Rough estimate of how hard a text is to type, so speeds on different
+//! chapters can be compared fairly.
+
+/// Averages for ordinary English prose, which scores 1.
+const TYPICAL_WORD_LEN: f64 = 4.7;
+const TYPICAL_PUNCTUATION: f64 = 0.03;
+
+/// How much each extra punctuation mark or rare character per character of
+/// text adds to the score.
+const PUNCTUATION_WEIGHT: f64 = 4.0;
+const RARE_WEIGHT: f64 = 8.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub word_len: f64,
+    /// Punctuation marks per character.
+    pub punctuation: f64,
+    /// Characters outside letters, spaces and common punctuation, such as
+    /// digits, symbols and accented letters, per character.
+    pub rare: f64,
+}
+
+impl Difficulty {
+    pub fn of(text: &str) -> Self {
+        let mut chars = 0;
+        let mut punctuation = 0;
+        let mut rare = 0;
+        for c in text.chars().filter(|c| !c.is_whitespace()) {
+            chars += 1;
+            if is_common_punctuation(c) {
+                punctuation += 1;
+            } else if !c.is_ascii_alphabetic() {
+                rare += 1;
+            }
+        }
+        let words = text.split_whitespace().count();
+
+        let per_char = |n: usize| match chars {
+            0 => 0.0,
+            chars => n as f64 / chars as f64,
+        };
+        Self {
+            word_len: match words {
+                0 => 0.0,
+                words => (chars - punctuation) as f64 / words as f64,
+            },
+            punctuation: per_char(punctuation),
+            rare: per_char(rare),
+        }
+    }
+
+    /// Relative difficulty, where ordinary prose is 1 and higher is harder.
+    pub fn score(&self) -> f64 {
+        let words = self.word_len / TYPICAL_WORD_LEN;
+        let punctuation = PUNCTUATION_WEIGHT * (self.punctuation - TYPICAL_PUNCTUATION);
+        let rare = RARE_WEIGHT * self.rare;
+        (words + punctuation + rare).max(0.1)
+    }
+}
+
+fn is_common_punctuation(c: char) -> bool {
+    matches!(
+        c,
+        '.' | ','
+            | ';'
+            | ':'
+            | '!'
+            | '?'
+            | '\''
+            | '"'
+            | '-'
+            | '('
+            | ')'
+            | '‘'
+            | '’'
+            | '“'
+            | '”'
+            | '—'
+            | '–'
+    )
+}
+
+/// Speed scaled by `difficulty`, so a fast run through easy text and a
+/// slower one through hard text come out comparable.
+pub fn adjusted_wpm(wpm: f64, difficulty: f64) -> f64 {
+    wpm * difficulty
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn symbols_are_harder_than_prose() {
+        let prose =
+            Difficulty::of("It was a bright cold day in April, and the clocks were striking.");
+        let code = Difficulty::of("fn main() { let x: [u8; 4] = [0x1f, 2, 3, 4]; }");
+        assert!(code.score() > prose.score());
+    }
+}
@@ -0,0 +1,206 @@
+use std::{collections::BTreeMap, ops::Range};
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize, Serializer};
+
+/// Patterns matching text that shouldn't be typed, such as footnote markers
+/// or transcriber's notes. `^` and `$` match at paragraph boundaries.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct Filters(Vec<Regex>);
+
+impl TryFrom<Vec<String>> for Filters {
+    type Error = regex::Error;
+
+    fn try_from(patterns: Vec<String>) -> Result<Self, Self::Error> {
+        patterns
+            .iter()
+            .map(|p| RegexBuilder::new(p).multi_line(true).build())
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Serialize for Filters {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(Regex::as_str))
+    }
+}
+
+impl Filters {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Byte ranges of `text` to remove, sorted and non-overlapping. A match
+    /// covering a whole paragraph takes a line break with it.
+    pub fn spans(&self, text: &str) -> Vec<Range<usize>> {
+        let mut spans = self
+            .0
+            .iter()
+            .flat_map(|re| re.find_iter(text))
+            .filter(|m| !m.is_empty())
+            .map(|m| {
+                let mut range = m.range();
+                let starts_line = range.start == 0 || text[..range.start].ends_with('\n');
+                let ends_line = range.end == text.len() || text[range.end..].starts_with('\n');
+                if starts_line && ends_line {
+                    if range.end < text.len() {
+                        range.end += 1;
+                    } else if range.start > 0 {
+                        range.start -= 1;
+                    }
+                }
+                range
+            })
+            .collect::<Vec<_>>();
+        merge_spans(&mut spans);
+        spans
+    }
+}
+
+/// Patterns for text to change before typing, such as a book's OCR errors,
+/// each with what to put in its place. Replacements can refer to groups in
+/// the pattern as `$1`, `$name` and so on.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(try_from = "BTreeMap<String, String>")]
+pub struct Replacements(Vec<(Regex, String)>);
+
+impl TryFrom<BTreeMap<String, String>> for Replacements {
+    type Error = regex::Error;
+
+    fn try_from(rules: BTreeMap<String, String>) -> Result<Self, Self::Error> {
+        rules
+            .into_iter()
+            .map(|(p, with)| Ok((RegexBuilder::new(&p).multi_line(true).build()?, with)))
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl Serialize for Replacements {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.0.iter().map(|(re, with)| (re.as_str(), with)))
+    }
+}
+
+impl Replacements {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Adds `other`'s rules after these.
+    pub fn extend(&mut self, other: Self) {
+        self.0.extend(other.0);
+    }
+
+    /// Byte ranges of `text` to replace, sorted and non-overlapping, each
+    /// with what to replace it with. Of overlapping matches, the one starting
+    /// first is kept.
+    pub fn spans(&self, text: &str) -> Vec<(Range<usize>, String)> {
+        let mut spans = self
+            .0
+            .iter()
+            .flat_map(|(re, with)| {
+                re.captures_iter(text).map(move |caps| {
+                    let mut replacement = String::new();
+                    caps.expand(with, &mut replacement);
+                    (caps.get(0).unwrap().range(), replacement)
+                })
+            })
+            .filter(|(range, _)| !range.is_empty())
+            .collect::<Vec<_>>();
+        spans.sort_by_key(|(range, _)| range.start);
+        let mut end = 0;
+        spans.retain(|(range, _)| {
+            let keep = range.start >= end;
+            if keep {
+                end = range.end;
+            }
+            keep
+        });
+        spans
+    }
+}
+
+/// Keys on a QWERTY keyboard, by area, for the named [`KeySet`]s.
+const KEY_PRESETS: &[(&str, &str)] = &[
+    ("left-hand", "`12345qwertasdfgzxcvb~!@#$%"),
+    ("right-hand", "67890-=yuiop[]\\hjkl;'nm,./^&*()_+{}|:\"<>?"),
+    ("home-row", "asdfghjkl;'"),
+];
+
+/// Characters that can be typed with a limited set of keys. Whitespace is
+/// always allowed, and capitals whenever their letter is.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "String")]
+pub struct KeySet(Vec<char>);
+
+/// One of the preset names, or the characters allowed.
+impl From<String> for KeySet {
+    fn from(keys: String) -> Self {
+        let keys = KEY_PRESETS
+            .iter()
+            .find(|(name, _)| *name == keys)
+            .map_or(keys.as_str(), |(_, keys)| keys);
+        Self(keys.chars().flat_map(char::to_lowercase).collect())
+    }
+}
+
+impl Serialize for KeySet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&self.0.iter().collect::<String>())
+    }
+}
+
+impl KeySet {
+    pub fn allows(&self, c: char) -> bool {
+        c.is_whitespace()
+            || c.to_lowercase().all(|c| {
+                self.0.iter().any(|&key| {
+                    crate::backend::chars_are_equal_including_unicode_alternatives(c, key)
+                })
+            })
+    }
+
+    /// Byte ranges of the paragraphs in `text` with any character outside the
+    /// set, each along with its line break.
+    pub fn spans(&self, text: &str) -> Vec<Range<usize>> {
+        let mut spans: Vec<Range<usize>> = Vec::new();
+        let mut start = 0;
+        for paragraph in text.split_inclusive('\n') {
+            let end = start + paragraph.len();
+            if !paragraph.chars().all(|c| self.allows(c)) {
+                spans.push(start..end);
+            }
+            start = end;
+        }
+        merge_spans(&mut spans);
+        spans
+    }
+}
+
+/// Sorts `spans` and merges any that overlap or touch.
+pub fn merge_spans(spans: &mut Vec<Range<usize>>) {
+    spans.sort_unstable_by_key(|r| r.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(spans.len());
+    for span in spans.drain(..) {
+        match merged.last_mut() {
+            Some(last) if span.start <= last.end => last.end = last.end.max(span.end),
+            _ => merged.push(span),
+        }
+    }
+    *spans = merged;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_sets_keep_only_paragraphs_they_can_type() {
+        let keys = KeySet::from("left-hand".to_owned());
+        let text = "Wet grass\nA few dogs.\nDrab crews wade sea\nOh\n";
+        assert_eq!(keys.spans(text), [10..22, 42..45]);
+    }
+}
@@ -0,0 +1,109 @@
+//! A date to finish a book by, and how much typing a day it takes to get
+//! there.
+
+use crate::{
+    date,
+    stats::{Session, Totals},
+};
+
+const DAY: u64 = 24 * 60 * 60;
+/// How far back sessions count towards the recent daily volume.
+pub const RECENT_DAYS: u64 = 7;
+/// Characters to a word, as in words per minute.
+const WORD_LEN: f64 = 5.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Schedule {
+    /// Days to type in, today and the day of the goal included. Zero once the
+    /// goal has passed.
+    pub days_left: i64,
+    /// Words a day needed to finish in time.
+    pub words_per_day: f64,
+    /// How long those words take at the book's average speed, if there is
+    /// one yet.
+    pub minutes_per_day: Option<f64>,
+    /// Words a day typed in the book over the last [`RECENT_DAYS`].
+    pub recent_per_day: f64,
+    /// Day the book would be finished at the recent rate, in days since
+    /// 1970-01-01.
+    pub projected: Option<i64>,
+    goal: i64,
+}
+
+impl Schedule {
+    /// Works out what's left of a book with `remaining` characters still to
+    /// type, to finish by the day of `finish_by`. `sessions` are the book's,
+    /// for its average speed and how much has been typed lately.
+    pub fn new(remaining: usize, finish_by: u64, sessions: &[Session], now: u64) -> Self {
+        let today = date::day_of(now);
+        let goal = date::day_of(finish_by);
+        let days_left = (goal - today + 1).max(0);
+        let words = remaining as f64 / WORD_LEN;
+        let words_per_day = words / days_left.max(1) as f64;
+
+        let wpm = sessions.iter().collect::<Totals>().wpm();
+        let minutes_per_day = (wpm > 0.0).then(|| words_per_day / wpm);
+
+        let since = now.saturating_sub(RECENT_DAYS * DAY);
+        let recent: usize = sessions
+            .iter()
+            .filter(|s| s.timestamp > since)
+            .map(|s| s.chars)
+            .sum();
+        let recent_per_day = recent as f64 / WORD_LEN / RECENT_DAYS as f64;
+        let projected = match remaining {
+            0 => Some(today),
+            _ => (recent_per_day > 0.0).then(|| today + (words / recent_per_day).ceil() as i64 - 1),
+        };
+
+        Self {
+            days_left,
+            words_per_day,
+            minutes_per_day,
+            recent_per_day,
+            projected,
+            goal,
+        }
+    }
+
+    /// Whether the book would be finished by the goal at the recent rate.
+    pub fn on_schedule(&self) -> bool {
+        self.projected.is_some_and(|day| day <= self.goal)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn the_recent_rate_decides_whether_a_goal_is_on_schedule() {
+        let now = 100 * DAY;
+        let session = |days_ago: u64, chars: usize| Session {
+            timestamp: now - days_ago * DAY,
+            chars,
+            // A character a second is 12 wpm.
+            elapsed_ms: chars as u64 * 1000,
+            ..Session::default()
+        };
+        // 700 words over the last week is 100 a day, at 12 wpm.
+        let sessions = [session(1, 2000), session(3, 1500), session(20, 9000)];
+
+        // 1000 words left, due in 10 days counting today.
+        let schedule = Schedule::new(5000, now + 9 * DAY, &sessions, now);
+        assert_eq!(schedule.days_left, 10);
+        assert_eq!(schedule.words_per_day, 100.0);
+        assert_eq!(schedule.recent_per_day, 100.0);
+        assert!((schedule.minutes_per_day.unwrap() - 100.0 / 12.0).abs() < 1e-9);
+        assert_eq!(schedule.projected, Some(date::day_of(now) + 9));
+        assert!(schedule.on_schedule());
+
+        let schedule = Schedule::new(5000, now + 4 * DAY, &sessions, now);
+        assert_eq!(schedule.words_per_day, 200.0);
+        assert!(!schedule.on_schedule());
+
+        let schedule = Schedule::new(5000, now + 9 * DAY, &[], now);
+        assert_eq!((schedule.minutes_per_day, schedule.projected), (None, None));
+        assert!(!schedule.on_schedule());
+    }
+}
@@ -0,0 +1,86 @@
+//! Detection of Project Gutenberg boilerplate, so the license and catalogue
+//! header aren't typed along with the book.
+
+use std::ops::Range;
+
+/// Paragraphs containing these mark the end of the header.
+const START_MARKERS: &[&str] = &[
+    "start of the project gutenberg ebook",
+    "start of this project gutenberg ebook",
+];
+
+/// Paragraphs containing these mark the start of the footer.
+const END_MARKERS: &[&str] = &[
+    "end of the project gutenberg ebook",
+    "end of this project gutenberg ebook",
+    "the full project gutenberg license",
+    "start: full license",
+];
+
+/// Paragraphs containing these are removed wherever they appear.
+const INLINE_MARKERS: &[&str] = &[
+    "this ebook is for the use of anyone anywhere",
+    "www.gutenberg.org",
+    "project gutenberg license included with this ebook",
+];
+
+/// Whether a TOC entry is a Gutenberg header, footer or license section.
+pub fn is_boilerplate_title(name: &str) -> bool {
+    name.to_ascii_lowercase().contains("project gutenberg")
+}
+
+/// Byte ranges of `text` that are Gutenberg boilerplate, possibly
+/// overlapping.
+pub fn spans(text: &str) -> Vec<Range<usize>> {
+    // ASCII lowercasing keeps byte offsets intact.
+    let lower = text.to_ascii_lowercase();
+    let mut spans = Vec::new();
+    let mut body = 0..text.len();
+    let mut start = 0;
+    for paragraph in lower.split('\n') {
+        let range = start..start + paragraph.len();
+        start = range.end + 1;
+
+        let contains_any = |markers: &[&str]| markers.iter().any(|m| paragraph.contains(m));
+        if range.start >= body.end {
+            break;
+        } else if contains_any(START_MARKERS) {
+            body.start = start.min(text.len());
+        } else if contains_any(END_MARKERS) {
+            body.end = range.start.saturating_sub(1);
+        } else if contains_any(INLINE_MARKERS) {
+            spans.push(range.start..start.min(text.len()));
+        }
+    }
+    if body.start > 0 {
+        spans.push(0..body.start);
+    }
+    if body.end < text.len() {
+        spans.push(body.end..text.len());
+    }
+    spans
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_strips_header_and_footer() {
+        let text = "Title: Foo\n*** START OF THE PROJECT GUTENBERG EBOOK FOO ***\nIt was a dark night.\n*** END OF THE PROJECT GUTENBERG EBOOK FOO ***\nLicense";
+        let spans = spans(text);
+        assert_eq!(spans, [0..60, 80..text.len()]);
+        assert_eq!(&text[60..80], "It was a dark night.");
+    }
+
+    #[test]
+    fn it_strips_inline_paragraphs() {
+        let text = "One.\nThis eBook is for the use of anyone anywhere.\nTwo.\nwww.gutenberg.org";
+        assert_eq!(spans(text), [5..51, 56..text.len()]);
+    }
+
+    #[test]
+    fn it_leaves_other_text_alone() {
+        assert!(spans("Nothing to see here.\nOr here.").is_empty());
+    }
+}
@@ -0,0 +1,135 @@
+//! Importers for results exported from other typing tools, so the baseline
+//! speed reflects practice done elsewhere.
+
+use crate::{date, stats::Sample};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    /// CSV from Monkeytype's account page.
+    Monkeytype,
+    /// CSV of race history, as exported from TypeRacer or TypeRacerData.
+    Typeracer,
+}
+
+impl std::str::FromStr for Source {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "monkeytype" => Ok(Self::Monkeytype),
+            "typeracer" => Ok(Self::Typeracer),
+            _ => Err(anyhow::anyhow!("unknown import source `{s}`")),
+        }
+    }
+}
+
+impl Source {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Monkeytype => "monkeytype",
+            Self::Typeracer => "typeracer",
+        }
+    }
+}
+
+/// Reads every result in an export. Rows that can't be understood are
+/// skipped; an export with none that can is an error.
+pub fn parse(source: Source, csv: &str) -> anyhow::Result<Vec<Sample>> {
+    let mut rows = csv.lines().filter(|l| !l.trim().is_empty()).map(split_row);
+    let Some(header) = rows.next() else {
+        anyhow::bail!("export is empty");
+    };
+    let column = |names: &[&str]| {
+        header
+            .iter()
+            .position(|h| names.iter().any(|n| h.trim().eq_ignore_ascii_case(n)))
+    };
+
+    let (wpm, acc, time) = match source {
+        Source::Monkeytype => (column(&["wpm"]), column(&["acc"]), column(&["timestamp"])),
+        Source::Typeracer => (
+            column(&["wpm", "speed"]),
+            column(&["accuracy", "acc"]),
+            column(&["date/time (utc)", "date", "timestamp"]),
+        ),
+    };
+    let (Some(wpm), Some(time)) = (wpm, time) else {
+        anyhow::bail!("export has no {} speed or date columns", source.name());
+    };
+
+    let mut samples = Vec::new();
+    for (i, row) in rows.enumerate() {
+        let sample = (|| {
+            let timestamp = match source {
+                // Milliseconds since the epoch.
+                Source::Monkeytype => row.get(time)?.trim().parse::<u64>().ok()? / 1000,
+                Source::Typeracer => date::parse_timestamp(row.get(time)?)?,
+            };
+            Some(Sample {
+                source: source.name().to_owned(),
+                timestamp,
+                wpm: row.get(wpm)?.trim().parse().ok()?,
+                accuracy: acc.and_then(|a| parse_accuracy(row.get(a)?)),
+            })
+        })();
+        match sample {
+            Some(sample) => samples.push(sample),
+            None => log::warn!("skipping unreadable {} row {}", source.name(), i + 2),
+        }
+    }
+    anyhow::ensure!(!samples.is_empty(), "no results found in export");
+    Ok(samples)
+}
+
+/// Accepts `0.97`, `97` and `97%`.
+fn parse_accuracy(s: &str) -> Option<f64> {
+    let s = s.trim();
+    let (s, percent) = match s.strip_suffix('%') {
+        Some(s) => (s, true),
+        None => (s, false),
+    };
+    let acc: f64 = s.trim().parse().ok()?;
+    Some(if percent || acc > 1.0 {
+        acc / 100.0
+    } else {
+        acc
+    })
+}
+
+/// Splits a CSV row, honouring double-quoted fields.
+fn split_row(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_reads_monkeytype_exports() {
+        let csv = "_id,isPb,wpm,acc,rawWpm,charStats,timestamp\n\
+                   a,true,81.5,96.2,85,\"50;1;0;0\",1700000000000\n\
+                   b,false,oops,90,80,\"1;2;3;4\",1700000100000\n";
+        let samples = parse(Source::Monkeytype, csv).unwrap();
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].wpm, 81.5);
+        assert_eq!(samples[0].timestamp, 1700000000);
+        assert!((samples[0].accuracy.unwrap() - 0.962).abs() < 1e-9);
+    }
+}
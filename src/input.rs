@@ -0,0 +1,118 @@
+//! Terminal input read on its own thread, so the event loop can wait on key
+//! presses and timers at once.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, RecvTimeoutError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::{self, Event, KeyEvent, KeyEventKind};
+
+pub enum Input {
+    Key {
+        event: KeyEvent,
+        /// When the event was read from the terminal.
+        received: Instant,
+    },
+    /// A key let go of. Only reported by terminals with the kitty keyboard
+    /// protocol.
+    Release { event: KeyEvent, received: Instant },
+    /// The terminal gained or lost focus.
+    Focus(bool),
+    /// Text pasted into the terminal.
+    Paste(String),
+    /// The terminal was resized to this many columns and rows.
+    Resize(u16, u16),
+    /// Nothing arrived before the timeout; time for timers to run.
+    Tick,
+}
+
+/// How often the input thread checks whether it's been dropped.
+const STOP_POLL: Duration = Duration::from_millis(50);
+
+pub struct InputThread {
+    rx: Receiver<anyhow::Result<Input>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl InputThread {
+    /// Starts reading key events. Nothing else should read terminal events
+    /// while this is running; dropping it stops the thread.
+    pub fn spawn() -> Self {
+        let (tx, rx) = mpsc::channel();
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        let handle = thread::spawn(move || loop {
+            if stopped.load(Ordering::Relaxed) {
+                return;
+            }
+            // Waiting in short polls rather than one read lets the thread
+            // notice it's been dropped, without delaying events that arrive.
+            match event::poll(STOP_POLL) {
+                Ok(false) => continue,
+                Ok(true) => {}
+                Err(e) => {
+                    let _ = tx.send(Err(e.into()));
+                    return;
+                }
+            }
+            let result = match event::read() {
+                Ok(Event::Key(event)) if event.kind == KeyEventKind::Release => {
+                    Ok(Input::Release {
+                        event,
+                        received: Instant::now(),
+                    })
+                }
+                Ok(Event::Key(event)) => Ok(Input::Key {
+                    event,
+                    received: Instant::now(),
+                }),
+                Ok(Event::FocusGained) => Ok(Input::Focus(true)),
+                Ok(Event::FocusLost) => Ok(Input::Focus(false)),
+                Ok(Event::Paste(text)) => Ok(Input::Paste(text)),
+                Ok(Event::Resize(width, height)) => Ok(Input::Resize(width, height)),
+                Ok(_) => continue,
+                Err(e) => Err(e.into()),
+            };
+            let failed = result.is_err();
+            if tx.send(result).is_err() || failed {
+                return;
+            }
+        });
+        Self {
+            rx,
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Waits up to `timeout` for an event, or indefinitely without one.
+    pub fn next(&self, timeout: Option<Duration>) -> anyhow::Result<Input> {
+        let Some(timeout) = timeout else {
+            return self
+                .rx
+                .recv()
+                .map_err(|_| anyhow::anyhow!("input thread stopped"))?;
+        };
+        match self.rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Ok(Input::Tick),
+            Err(RecvTimeoutError::Disconnected) => anyhow::bail!("input thread stopped"),
+        }
+    }
+}
+
+impl Drop for InputThread {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
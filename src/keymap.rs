@@ -0,0 +1,209 @@
+//! Keys for moving around and for commands while typing, which can be
+//! changed under `[bindings]` in the config.
+
+use std::fmt;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// A key along with the modifiers held for it, written like `j`, `pageup`
+/// or `ctrl+w`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Binding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+/// Names of keys without a character of their own.
+const NAMED: &[(&str, KeyCode)] = &[
+    ("up", KeyCode::Up),
+    ("down", KeyCode::Down),
+    ("left", KeyCode::Left),
+    ("right", KeyCode::Right),
+    ("pageup", KeyCode::PageUp),
+    ("pagedown", KeyCode::PageDown),
+    ("home", KeyCode::Home),
+    ("end", KeyCode::End),
+    ("enter", KeyCode::Enter),
+    ("esc", KeyCode::Esc),
+    ("tab", KeyCode::Tab),
+    ("backspace", KeyCode::Backspace),
+    ("delete", KeyCode::Delete),
+    ("space", KeyCode::Char(' ')),
+];
+
+const MODIFIERS: &[(&str, KeyModifiers)] = &[
+    ("ctrl", KeyModifiers::CONTROL),
+    ("alt", KeyModifiers::ALT),
+    ("shift", KeyModifiers::SHIFT),
+];
+
+impl Binding {
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        // Shift is part of the character typed, as in `G`, and terminals
+        // disagree on whether to report it as well.
+        let ignored = match event.code {
+            KeyCode::Char(_) => KeyModifiers::SHIFT,
+            _ => KeyModifiers::NONE,
+        };
+        event.code == self.code && event.modifiers & !ignored == self.modifiers & !ignored
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        // A `+` on its own, or at the end as in `ctrl++`, is the key.
+        let (mods, key) = match s.strip_suffix("++") {
+            Some(mods) => (mods, "+"),
+            None if s == "+" => ("", "+"),
+            None => s.rsplit_once('+').unwrap_or(("", &s)),
+        };
+        let mut modifiers = KeyModifiers::NONE;
+        for name in mods.split('+').filter(|m| !m.is_empty()) {
+            let Some(&(_, m)) = MODIFIERS.iter().find(|(n, _)| name.eq_ignore_ascii_case(n)) else {
+                return Err(format!("unknown modifier {name:?} in key {s:?}"));
+            };
+            modifiers |= m;
+        }
+        let mut chars = key.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match NAMED.iter().find(|(n, _)| key.eq_ignore_ascii_case(n)) {
+                Some(&(_, code)) => code,
+                None => return Err(format!("unknown key {key:?}")),
+            },
+        };
+        Ok(Self { code, modifiers })
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_string()
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for &(name, m) in MODIFIERS {
+            if self.modifiers.contains(m) {
+                write!(f, "{name}+")?;
+            }
+        }
+        match NAMED.iter().find(|&&(_, code)| code == self.code) {
+            Some((name, _)) => f.write_str(name),
+            None => match self.code {
+                KeyCode::Char(c) => write!(f, "{c}"),
+                code => write!(f, "{code:?}"),
+            },
+        }
+    }
+}
+
+/// Keys for each command, any of which does it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keymap {
+    /// Move through chapter select.
+    pub up: Vec<Binding>,
+    pub down: Vec<Binding>,
+    pub page_up: Vec<Binding>,
+    pub page_down: Vec<Binding>,
+    pub first: Vec<Binding>,
+    pub last: Vec<Binding>,
+    /// Open the chapter selected.
+    pub open: Vec<Binding>,
+    /// Leave the screen, or quit from chapter select.
+    pub quit: Vec<Binding>,
+    /// Select the first chapter not yet typed to the end.
+    pub next_unfinished: Vec<Binding>,
+    /// Search the book from chapter select.
+    pub search: Vec<Binding>,
+    /// Show an overview of the book.
+    pub info: Vec<Binding>,
+    /// Look over the selected chapter's vocabulary.
+    pub vocabulary: Vec<Binding>,
+    /// Set the pace caret's speed.
+    pub pace: Vec<Binding>,
+    /// Show how every profile has done on the book.
+    pub leaderboard: Vec<Binding>,
+    /// Tag the session just typed, from its score screen.
+    pub tag: Vec<Binding>,
+    /// Set a date to finish the book by, from its overview.
+    pub goal: Vec<Binding>,
+    /// Go back to the start of the paragraph being typed.
+    pub restart: Vec<Binding>,
+    /// Go back to the last error still standing.
+    pub retry_error: Vec<Binding>,
+    /// Delete the word before the cursor.
+    pub delete_word: Vec<Binding>,
+    /// Turn stopping on errors on or off while typing.
+    pub stop_on_error: Vec<Binding>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| {
+            keys.iter()
+                .map(|k| Binding::try_from(k.to_string()).unwrap())
+                .collect()
+        };
+        Self {
+            up: keys(&["up", "k"]),
+            down: keys(&["down", "j"]),
+            page_up: keys(&["pageup"]),
+            page_down: keys(&["pagedown"]),
+            first: keys(&["home"]),
+            last: keys(&["end"]),
+            open: keys(&["enter"]),
+            quit: keys(&["esc"]),
+            next_unfinished: keys(&["n"]),
+            search: keys(&["/"]),
+            info: keys(&["i"]),
+            vocabulary: keys(&["v"]),
+            pace: keys(&["p"]),
+            leaderboard: keys(&["l"]),
+            tag: keys(&["t"]),
+            goal: keys(&["g"]),
+            restart: keys(&["ctrl+r"]),
+            retry_error: keys(&["ctrl+e"]),
+            delete_word: keys(&["ctrl+backspace", "ctrl+w"]),
+            stop_on_error: keys(&["ctrl+s"]),
+        }
+    }
+}
+
+/// Whether `event` is any of `bindings`.
+pub fn matches(bindings: &[Binding], event: &KeyEvent) -> bool {
+    bindings.iter().any(|b| b.matches(event))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn bindings_are_read_and_written_the_same_way() {
+        for key in [
+            "j",
+            "ctrl+w",
+            "ctrl+backspace",
+            "pageup",
+            "alt+shift+x",
+            "ctrl++",
+            "+",
+        ] {
+            let binding = Binding::try_from(key.to_owned()).unwrap();
+            assert_eq!(binding.to_string(), key);
+        }
+        assert!(Binding::try_from("hyper+x".to_owned()).is_err());
+        assert!(Binding::try_from("pgup".to_owned()).is_err());
+
+        let binding = Binding::try_from("G".to_owned()).unwrap();
+        assert!(binding.matches(&KeyEvent::new(KeyCode::Char('G'), KeyModifiers::SHIFT)));
+        assert!(!binding.matches(&KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE)));
+    }
+}
@@ -0,0 +1,72 @@
+//! Keystroke latency instrumentation: time from a key event arriving to the
+//! frame it causes being flushed.
+
+use std::{collections::VecDeque, time::Duration};
+
+/// Frames kept for percentiles, so they follow recent behaviour.
+const WINDOW: usize = 1000;
+
+/// Longest a typical keystroke should take to render. Exceeding it shows up
+/// as lag over slow links.
+pub const BUDGET: Duration = Duration::from_millis(4);
+
+#[derive(Debug, Default)]
+pub struct Latency {
+    samples: VecDeque<Duration>,
+}
+
+impl Latency {
+    pub fn record(&mut self, latency: Duration) {
+        if self.samples.len() == WINDOW {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(latency);
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The latency `p` percent of frames came in under, by nearest rank.
+    pub fn percentile(&self, p: f64) -> Option<Duration> {
+        let mut sorted: Vec<Duration> = self.samples.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+        sorted.get(rank.clamp(1, sorted.len().max(1)) - 1).copied()
+    }
+
+    /// One-line summary for the debug overlay.
+    pub fn summary(&self) -> String {
+        let ms = |p| {
+            self.percentile(p)
+                .map_or(0.0, |d: Duration| d.as_secs_f64() * 1000.0)
+        };
+        format!(
+            "p95 {:.2}ms  p99 {:.2}ms  ({} frames)",
+            ms(95.0),
+            ms(99.0),
+            self.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_takes_nearest_rank_percentiles() {
+        let mut latency = Latency::default();
+        assert_eq!(latency.percentile(95.0), None);
+        for ms in 1..=100 {
+            latency.record(Duration::from_millis(ms));
+        }
+        assert_eq!(latency.percentile(95.0), Some(Duration::from_millis(95)));
+        assert_eq!(latency.percentile(99.0), Some(Duration::from_millis(99)));
+        assert_eq!(latency.percentile(100.0), Some(Duration::from_millis(100)));
+    }
+}
@@ -1,4 +1,41 @@
+pub mod analytics;
+pub mod assignment;
 pub mod backend;
+#[cfg(feature = "backup")]
+pub mod backup;
+pub mod cache;
+pub mod card;
+pub mod config;
+pub mod coverage;
+pub mod daily;
+pub mod date;
+pub mod difficulty;
 // pub mod epub;
+pub mod filter;
+pub mod goal;
+pub mod gutenberg;
+pub mod import;
+pub mod input;
+pub mod keymap;
+pub mod latency;
+pub mod library;
+pub mod logger;
+pub mod migrate;
+pub mod pace;
+pub mod paths;
+pub mod quotes;
+pub mod replay;
+pub mod review;
+pub mod rewrap;
+pub mod shelf;
+pub mod sidecar;
+pub mod stats;
+pub mod store;
 pub mod style;
+#[cfg(feature = "sync")]
+pub mod sync;
 pub mod term;
+pub mod transform;
+pub mod translate;
+pub mod vocabulary;
+pub mod warmup;
@@ -0,0 +1,53 @@
+//! Books found in a directory, to pick one to open with `ept library`.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use lepu::Epub;
+
+#[derive(Debug, Clone)]
+pub struct Book {
+    pub path: PathBuf,
+    pub title: String,
+    pub author: Option<String>,
+}
+
+/// Every EPUB under `dir`, by author then title. Files that don't open as
+/// books are left out.
+pub fn scan(dir: &Path) -> anyhow::Result<Vec<Book>> {
+    let mut books = Vec::new();
+    for path in epubs(dir)? {
+        match fs::read(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|bytes| Ok(Epub::new(bytes)?))
+        {
+            Ok(book) => books.push(Book {
+                title: book.title().to_owned(),
+                author: book.author().map(str::to_owned),
+                path,
+            }),
+            Err(e) => log::warn!("skipping {}: {e}", path.display()),
+        }
+    }
+    books.sort_by(|a, b| (&a.author, &a.title).cmp(&(&b.author, &b.title)));
+    Ok(books)
+}
+
+/// Paths of the `.epub` files in `dir` and the directories in it.
+fn epubs(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            paths.extend(epubs(&path)?);
+        } else if path
+            .extension()
+            .is_some_and(|e| e.eq_ignore_ascii_case("epub"))
+        {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
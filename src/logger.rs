@@ -0,0 +1,48 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::Write,
+    sync::Mutex,
+};
+
+use crate::paths;
+
+struct FileLogger(Mutex<File>);
+
+impl log::Log for FileLogger {
+    fn enabled(&self, _: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if let Ok(mut file) = self.0.lock() {
+            let _ = writeln!(
+                file,
+                "[{}] {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut file) = self.0.lock() {
+            let _ = file.flush();
+        }
+    }
+}
+
+/// Sends log output to a file in the data directory, since the terminal is
+/// taken up by the UI.
+pub fn init() -> anyhow::Result<()> {
+    let Some(path) = paths::log_file() else {
+        return Ok(());
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    log::set_boxed_logger(Box::new(FileLogger(Mutex::new(file))))?;
+    log::set_max_level(log::LevelFilter::Info);
+    Ok(())
+}
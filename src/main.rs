@@ -1,68 +1,1058 @@
-use std::{fs, io, num::NonZeroUsize, path::PathBuf};
+use std::{
+    ffi::OsString,
+    fs, io,
+    num::NonZeroUsize,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use crossterm::event::{self, Event, KeyEvent};
 use lepu::Epub;
 
-use ept::term::Display;
+use ept::{
+    analytics::{self, ClassStats, ShiftStats},
+    assignment::{self, Assignment, Results},
+    backend::BackspaceMode,
+    cache::ChapterCache,
+    config::{Config, Layers, Origin},
+    daily, date,
+    import::{self, Source},
+    input::{Input, InputThread},
+    logger, replay, review,
+    shelf::Shelved,
+    sidecar::Sidecar,
+    stats::{self, ReportFormat, Session, Totals},
+    store::{self, Store},
+    term::{Display, LibraryScreen, Pick, Picker, Playback, Practice, ShelfScreen},
+};
 
 // TODO: features
 // - nicer virtual styling
-// - book select maybe?
-// - progress saving
-// - scorescreen; wpm/acc display at end
 // - score annotations per paragraph
-// - window resize
 // - sixel images
 
-// TODO: annoyances/bugs
-// - british english uses single quotation marks for speech, prefer double--
-//   convert single quotation marks to double quotations? messes up quotes
-//   within quotes, so will have to parse nested and convert accordingly
+/// `ept assign` has a `run` of its own, which can't share a flags block with
+/// the top-level `ept run`, so it's parsed from what's left after `assign`.
+mod assign_flags {
+    use std::path::PathBuf;
+
+    xflags::xflags! {
+        cmd assign {
+            /// Type the assigned chapters, then write a results file next to
+            /// the assignment.
+            cmd run {
+                required file: PathBuf
+            }
+            /// Check that a results file hasn't been edited.
+            cmd verify {
+                required assignment: PathBuf
+                required results: PathBuf
+            }
+        }
+    }
+}
 
 fn main() -> anyhow::Result<()> {
     xflags::xflags! {
         cmd ept {
-            /// Path to book.
-            required path: PathBuf
             /// Width of text view, in characters.
             /// Defaults to 60.
             optional -w,--width width: NonZeroUsize
+            /// How far backspace may reach: `unlimited`, `word` or `off`.
+            /// Defaults to unlimited.
+            optional -b,--backspace mode: BackspaceMode
+            /// Keep Project Gutenberg license text and boilerplate.
+            optional --keep-gutenberg
+            /// Profile to record sessions under.
+            optional -p,--profile name: String
+            /// Show a pace caret moving at this many words per minute.
+            optional --pace wpm: f64
+            /// End each session after this many words.
+            optional --words n: NonZeroUsize
+            /// End each session after this many characters.
+            optional --chars n: NonZeroUsize
+            /// Don't save sessions, progress, replays or warm-ups.
+            optional --incognito
+            /// Keep the cursor on each mistake until it's typed right.
+            optional --stop-on-error
+
+            /// Type a book.
+            default cmd read {
+                /// Path to book. Without one, pick from the books you're
+                /// reading.
+                optional path: PathBuf
+            }
+
+            /// Type one session in a chapter and print how it went, for
+            /// scripts.
+            cmd run {
+                /// Path to book.
+                required path: PathBuf
+                /// Table of contents entry to type, counting from 1.
+                required -c,--chapter n: NonZeroUsize
+                /// `text` or `json`. Defaults to text.
+                optional --report format: ReportFormat
+            }
+
+            /// Work through assignment files: `run <file>` types the
+            /// assigned chapters, `verify <assignment> <results>` checks a
+            /// results file hasn't been edited.
+            cmd assign {
+                repeated args: OsString
+            }
+
+            /// Pick a book to type from a directory of them.
+            cmd library {
+                /// Directory to look for books in. Defaults to `library` in
+                /// the config.
+                optional dir: PathBuf
+            }
+
+            /// Merge this profile's sessions with the server in the config.
+            cmd sync {}
+
+            /// Write the config file and every profile's history to one
+            /// archive, to move to another machine.
+            cmd backup {
+                /// Archive to write, as `.tar.zst`.
+                required file: PathBuf
+            }
+
+            /// Unpack an archive written by `ept backup`.
+            cmd restore {
+                required file: PathBuf
+                /// Replace files that are already here.
+                optional --force
+            }
+
+            /// List the books this profile has typed all the way through.
+            cmd finished {}
+
+            /// Work with settings.
+            cmd config {
+                /// Print the settings that have been set, and where.
+                cmd show {
+                    /// Print every setting, including defaults.
+                    optional --resolved
+                    /// Include the settings in this book's sidecar.
+                    optional --book path: PathBuf
+                }
+            }
+
+            /// Look over and clear out what's kept for this profile.
+            cmd data {
+                /// List what's kept for each book.
+                default cmd list {}
+                /// Drop sessions where nothing was typed, and replays whose
+                /// session is gone.
+                cmd prune {}
+                /// Delete everything kept for a book.
+                cmd delete {
+                    /// Path to a book, or part of its title or author.
+                    optional book: String
+                    /// Delete the whole profile instead.
+                    optional --all
+                }
+                /// Rewrite the profile's logs without unreadable lines or
+                /// repeated sessions.
+                cmd vacuum {}
+            }
+
+            /// Look over this profile's sessions.
+            cmd stats {
+                /// Only count sessions with this tag.
+                optional -t,--tag tag: String
+                /// Only count sessions typed on this keyboard.
+                optional -k,--keyboard name: String
+
+                /// Show a calendar of the past year's typing, and speed and
+                /// accuracy by kind of character across every session.
+                default cmd summary {}
+                /// Compare speed, accuracy and difficulty between two books.
+                cmd compare {
+                    /// Path to a book, or part of its title or author.
+                    required book_a: String
+                    required book_b: String
+                }
+            }
+
+            /// Type the passage of the day, the same for everyone, and get a
+            /// code to compare scores with.
+            cmd daily {
+                /// Type the passage a friend's code is for instead.
+                optional --code code: String
+            }
+
+            /// Play back a recorded session.
+            cmd replay {
+                /// Replay file, from `replays/` in the profile's directory.
+                required file: PathBuf
+                /// The book it was typed from.
+                required book: PathBuf
+            }
+
+            /// Add results exported from another typing tool to the profile.
+            cmd import {
+                /// `monkeytype` or `typeracer`.
+                required source: Source
+                /// Exported CSV file.
+                required file: PathBuf
+            }
         }
     };
 
     let args = Ept::from_env()?;
-    let book = fs::read(args.path)
-        .map_err(Into::into)
-        .and_then(Epub::new)?;
+    // The log file would keep what was read, so nothing goes in it either.
+    if args.incognito {
+        store::go_incognito();
+    } else {
+        logger::init()?;
+    }
+
+    let mut layers = Layers::load()?;
+    let mut flags = toml::Table::new();
+    if let Some(width) = args.width.and_then(|x| i64::try_from(x.get()).ok()) {
+        flags.insert("width".to_owned(), width.into());
+    }
+    if let Some(backspace) = args.backspace {
+        flags.insert("backspace".to_owned(), toml::Value::try_from(backspace)?);
+    }
+    if args.keep_gutenberg {
+        flags.insert("strip_gutenberg".to_owned(), false.into());
+    }
+    if let Some(wpm) = args.pace {
+        let mut pace = toml::Table::new();
+        pace.insert("wpm".to_owned(), wpm.into());
+        pace.insert("adaptive".to_owned(), false.into());
+        flags.insert("pace".to_owned(), pace.into());
+    }
+    let limit = match (args.words, args.chars) {
+        (Some(_), Some(_)) => anyhow::bail!("--words and --chars can't be used together"),
+        (Some(n), None) => Some(("words", n)),
+        (None, Some(n)) => Some(("chars", n)),
+        (None, None) => None,
+    };
+    if let Some((unit, n)) = limit {
+        let mut table = toml::Table::new();
+        table.insert(unit.to_owned(), i64::try_from(n.get())?.into());
+        flags.insert("limit".to_owned(), table.into());
+    }
+    if args.stop_on_error {
+        flags.insert("stop_on_error".to_owned(), true.into());
+    }
+    if let Some(profile) = args.profile {
+        flags.insert("profile".to_owned(), profile.into());
+    }
+    layers.set(Origin::Flag, flags);
+    let (config, origins) = layers.resolve()?;
+    let config = Arc::new(config);
+
+    match args.subcommand {
+        EptCmd::Read(read) => match read.path {
+            Some(path) => {
+                run(&path, &layers, None)?;
+            }
+            // Back to the shelf after each book, until it's left.
+            None => loop {
+                match pick_from_shelf(&config)? {
+                    Pick::Open(book) => {
+                        run(&book.path, &layers, None)?;
+                    }
+                    Pick::Review => review(&config)?,
+                    Pick::Quit => break,
+                }
+            },
+        },
+        EptCmd::Library(cmd) => {
+            let Some(dir) = cmd.dir.or_else(|| config.library.clone()) else {
+                anyhow::bail!("no directory given, and no `library` in the config");
+            };
+            // Back to the library after each book, until it's left.
+            while let Some(path) = pick_from_library(&config, &dir)? {
+                run(&path, &layers, None)?;
+            }
+        }
+        EptCmd::Run(cmd) => {
+            let mut display = open(&cmd.path, &layers)?;
+            let chapter = cmd.chapter.get() - 1;
+            anyhow::ensure!(
+                display.open_at(chapter, 0) && !display.is_between_sessions(),
+                "chapter {} can't be typed; check it's in the book and not left out",
+                cmd.chapter
+            );
+            drive(&mut display, true)?;
+            let Some(session) = display.sessions().last() else {
+                anyhow::bail!("nothing was typed");
+            };
+            print!("{}", cmd.report.unwrap_or_default().render(session)?);
+        }
+        EptCmd::Assign(assign) => match assign_flags::Assign::from_vec(assign.args)?.subcommand {
+            assign_flags::AssignCmd::Run(run_cmd) => {
+                let assignment = Assignment::load(&run_cmd.file)?;
+                let (book, sessions) = run(&assignment.book, &layers, Some(assignment.chapters()))?;
+                let results =
+                    Results::new(&assignment, &config.profile, book, store::now(), sessions)?;
+                let path = assignment::results_path(&run_cmd.file, &config.profile);
+                results.save(&path)?;
+                if results.flagged > 0 {
+                    println!(
+                        "{} sessions don't look typed by hand and were flagged",
+                        results.flagged
+                    );
+                }
+                println!(
+                    "{}: results written to {}",
+                    if results.passed {
+                        "passed"
+                    } else {
+                        "not passed"
+                    },
+                    path.display()
+                );
+            }
+            assign_flags::AssignCmd::Verify(verify) => {
+                let assignment = Assignment::load(&verify.assignment)?;
+                let results = Results::load(&verify.results)?;
+                if !results.verify(&assignment.key)? {
+                    anyhow::bail!("{} has been modified", verify.results.display());
+                }
+                println!(
+                    "{}: {}",
+                    results.profile,
+                    if results.passed {
+                        "passed"
+                    } else {
+                        "not passed"
+                    }
+                );
+                if results.flagged > 0 {
+                    println!("{} sessions flagged as not typed by hand", results.flagged);
+                }
+            }
+        },
+        EptCmd::Sync(_) => {
+            anyhow::ensure!(!store::is_incognito(), "can't sync while incognito");
+            sync(&config)?;
+        }
+        EptCmd::Backup(cmd) => backup(&cmd.file)?,
+        EptCmd::Restore(cmd) => {
+            anyhow::ensure!(!store::is_incognito(), "can't restore while incognito");
+            restore(&cmd.file, cmd.force)?;
+        }
+        EptCmd::Config(Config {
+            subcommand: ConfigCmd::Show(show),
+        }) => {
+            let (config, origins) = match &show.book {
+                Some(path) => {
+                    let book = Epub::new(fs::read(path)?)?;
+                    let sidecar = Sidecar::load(path, &book)?;
+                    let mut layers = layers.clone();
+                    layers.set(Origin::Book, sidecar.config);
+                    layers.resolve()?
+                }
+                None => ((*config).clone(), origins),
+            };
+            let toml::Value::Table(settings) = toml::Value::try_from(&config)? else {
+                unreachable!("config is a table");
+            };
+            for (key, value) in settings {
+                let origin = origins.get(&key).copied().unwrap_or(Origin::Default);
+                if key == "profiles" || (origin == Origin::Default && !show.resolved) {
+                    continue;
+                }
+                println!("{key} = {value}  # {}", origin.name());
+            }
+        }
+        EptCmd::Data(data) => {
+            let store = open_store(&config)?;
+            match data.subcommand {
+                DataCmd::List(_) => {
+                    let usage = store.usage()?;
+                    if usage.is_empty() {
+                        println!("nothing kept for {}", config.profile);
+                    }
+                    for (book, usage) in usage {
+                        let last = usage.last_typed.map(date::format_date);
+                        println!(
+                            "{:10}  {book}: {} sessions, {} replays ({} KB)",
+                            last.unwrap_or_default(),
+                            usage.sessions,
+                            usage.replays,
+                            usage.replay_bytes.div_ceil(1024),
+                        );
+                    }
+                }
+                DataCmd::Prune(_) => {
+                    let (sessions, replays) = store.prune()?;
+                    println!("dropped {sessions} empty sessions and {replays} orphaned replays");
+                }
+                DataCmd::Delete(Delete { all: true, .. }) => {
+                    store.delete_all()?;
+                    println!("deleted everything kept for {}", config.profile);
+                }
+                DataCmd::Delete(Delete {
+                    book: Some(book), ..
+                }) => {
+                    let usage = store.usage()?;
+                    let keys: Vec<_> = usage.keys().map(String::as_str).collect();
+                    let key = book_argument(&keys, &book)?;
+                    let sessions = store.delete_book(&key)?;
+                    println!("deleted {key}: {sessions} sessions");
+                }
+                DataCmd::Delete(_) => anyhow::bail!("name a book to delete, or pass --all"),
+                DataCmd::Vacuum(_) => {
+                    store.vacuum()?;
+                    println!("rewrote the logs for {}", config.profile);
+                }
+            }
+        }
+        EptCmd::Finished(_) => {
+            let coverage = open_store(&config)?.coverage()?;
+            let finished = coverage.finished();
+            if finished.is_empty() {
+                println!("no finished books yet");
+            }
+            for (_, book) in finished {
+                let date = book.completed.map(date::format_date).unwrap_or_default();
+                println!("{date}  {}", book.title);
+            }
+        }
+        EptCmd::Stats(Stats {
+            tag,
+            keyboard,
+            subcommand: StatsCmd::Compare(compare),
+        }) => {
+            let sessions = filtered_sessions(&config, tag.as_deref(), keyboard.as_deref())?;
+            let keys: Vec<_> = sessions.iter().map(|s| s.book.as_str()).collect();
+            let a = book_argument(&keys, &compare.book_a)?;
+            let b = book_argument(&keys, &compare.book_b)?;
+            let totals =
+                |key: &str| -> Totals { sessions.iter().filter(|s| s.book == key).collect() };
+            print!(
+                "{}",
+                analytics::compare_report((&a, &totals(&a)), (&b, &totals(&b)))
+            );
+        }
+        EptCmd::Stats(Stats {
+            tag,
+            keyboard,
+            subcommand: StatsCmd::Summary(_),
+        }) => {
+            let sessions = filtered_sessions(&config, tag.as_deref(), keyboard.as_deref())?;
+            print!(
+                "{}",
+                analytics::heat_calendar(&sessions, date::day_of(store::now()))
+            );
+            println!();
+            print!(
+                "{}",
+                analytics::trend_report(&sessions, date::day_of(store::now()))
+            );
+            println!();
+            let mut classes = ClassStats::default();
+            let mut shift = ShiftStats::default();
+            for session in &sessions {
+                classes.merge(&session.classes);
+                shift.merge(&session.shift);
+            }
+            if classes.is_empty() {
+                println!("no sessions with a breakdown yet");
+            } else {
+                print!("{}", analytics::class_report(&classes));
+            }
+            if !shift.is_empty() {
+                println!();
+                print!("{}", analytics::shift_report(&shift));
+            }
+            if sessions.iter().any(|s| s.keyboard.is_some()) {
+                println!();
+                print!("{}", analytics::keyboard_report(&sessions));
+            }
+        }
+        EptCmd::Daily(cmd) => daily(&config, cmd.code.as_deref())?,
+        EptCmd::Replay(cmd) => play(&cmd.file, &cmd.book, &layers)?,
+        EptCmd::Import(import) => {
+            anyhow::ensure!(!store::is_incognito(), "can't import while incognito");
+            let samples = import::parse(import.source, &fs::read_to_string(&import.file)?)?;
+            let store = open_store(&config)?;
+            let added = store.record_imported(&samples)?;
+            println!("imported {added} of {} results", samples.len());
+            if let Some(wpm) = stats::baseline(&store.sessions()?, &store.imported()?) {
+                println!("baseline is now {wpm:.0} wpm");
+            }
+        }
+    }
+
+    Ok(())
+}
 
-    let width = args
-        .width
-        .and_then(|x| x.get().try_into().ok())
-        .unwrap_or(60u16);
+/// Runs the interactive display until the user quits, returning the book's
+/// key and the sessions typed. Books opened for the whole of them go on the
+/// shelf, and pick up where they were left off if they were already on it.
+fn run(
+    path: &Path,
+    layers: &Layers,
+    chapters: Option<RangeInclusive<usize>>,
+) -> anyhow::Result<(String, Vec<Session>)> {
+    let mut display = open(path, layers)?;
+    let book = display.book();
+    if let Some(chapters) = &chapters {
+        anyhow::ensure!(
+            *chapters.end() < book.chapter_count(),
+            "book only has {} chapters",
+            book.chapter_count()
+        );
+    }
+    let key = store::book_key(book);
+    let mut resume = None;
+    if chapters.is_none() {
+        let mut shelf = display.store().shelf()?;
+        shelf.put(Shelved {
+            key: key.clone(),
+            title: book.title().to_owned(),
+            author: book.author().map(str::to_owned),
+            path: fs::canonicalize(path)?,
+            opened: store::now(),
+            resume: None,
+            finish_by: None,
+        });
+        // Put keeps where the book was left off, if it was on the shelf.
+        resume = shelf.books[0].resume.clone();
+        display.store().write_shelf(&shelf)?;
+    }
 
     println!("{}'s {}", book.author().unwrap(), book.title());
 
+    if let Some(chapters) = chapters {
+        display.restrict_to(chapters)?;
+    }
+    if let Some(resume) = resume {
+        display.open_at(resume.chapter, resume.offset);
+    }
+    drive(&mut display, false)?;
+
+    Ok((key, display.sessions().to_vec()))
+}
+
+/// Reads the book at `path` and readies it for the terminal, with its
+/// sidecar applied.
+fn open(path: &Path, layers: &Layers) -> anyhow::Result<Display> {
+    let bytes = fs::read(path)?;
+    let book = Epub::new(bytes.clone())?;
+    let sidecar = Sidecar::load(path, &book)?;
+    let config = Arc::new(sidecar.resolve(layers)?);
+    let store = open_store(&config)?;
+    let cache = ChapterCache::spawn(bytes, book.chapter_count(), Arc::clone(&config));
     let (term_w, term_h) = crossterm::terminal::size()?;
+    let mut display = Display::new(book, cache, store, config, term_w, term_h);
+    display.arrange(&sidecar);
+    anyhow::ensure!(
+        !display.is_empty(),
+        "{}: no chapters with text",
+        path.display()
+    );
+    if std::env::var_os("EPT_LATENCY").is_some() {
+        display.instrument();
+    }
+    Ok(display)
+}
 
-    let mut w = io::stdout();
-    let mut display = Display::new(book, width, term_w, term_h);
+/// Where screens draw: stdout, buffered since frames are flushed explicitly.
+type Out = io::BufWriter<io::Stdout>;
 
-    display.enter(&mut w)?;
+/// A full-screen view, run by [`show`].
+trait Tui {
+    /// What the screen comes to once it's done.
+    type Output;
 
-    loop {
-        let ev = next_key_event()?;
-        if display.handle_input(ev)? {
-            break;
-        }
+    fn enter(&mut self, w: &mut Out) -> anyhow::Result<()>;
 
-        display.render(&mut w)?;
+    /// How long to wait for input before a tick, or `None` to wait for it.
+    fn timeout(&self) -> Option<Duration> {
+        None
     }
 
+    /// Acts on `input`, returning the outcome once the screen is done.
+    fn update(&mut self, input: Input, w: &mut Out) -> anyhow::Result<Option<Self::Output>>;
+}
+
+/// Takes over the terminal for `tui` until it's done, and gives it back
+/// even if the screen fails.
+fn show<T: Tui>(tui: &mut T) -> anyhow::Result<T::Output> {
+    // Frames are flushed explicitly, so buffer generously to send each one
+    // to the terminal in as few writes as possible.
+    let mut w = io::BufWriter::with_capacity(1 << 16, io::stdout());
+    let output = tui.enter(&mut w).and_then(|()| {
+        let input = InputThread::spawn();
+        loop {
+            if let Some(output) = tui.update(input.next(tui.timeout())?, &mut w)? {
+                break Ok(output);
+            }
+        }
+    });
     Display::exit(&mut w)?;
+    output
+}
+
+/// A book being typed, until the user quits or, if `single`, as soon as a
+/// session is over.
+struct Typing<'a> {
+    display: &'a mut Display,
+    single: bool,
+}
+
+impl Tui for Typing<'_> {
+    type Output = ();
+
+    fn enter(&mut self, w: &mut Out) -> anyhow::Result<()> {
+        self.display.enter(w)
+    }
 
+    fn timeout(&self) -> Option<Duration> {
+        self.display.timeout()
+    }
+
+    fn update(&mut self, input: Input, w: &mut Out) -> anyhow::Result<Option<()>> {
+        let display = &mut *self.display;
+        match input {
+            Input::Key { event, received } => {
+                if display.handle_input(event, received)?
+                    || (self.single && display.is_between_sessions())
+                {
+                    return Ok(Some(()));
+                }
+                display.render(w)?;
+                display.record_frame(w, received)?;
+            }
+            Input::Tick => display.tick(w)?,
+            Input::Focus(focused) => {
+                display.set_focus(focused);
+                display.render(w)?;
+            }
+            Input::Paste(text) => {
+                display.handle_paste(&text);
+                display.render(w)?;
+            }
+            Input::Resize(width, height) => {
+                display.resize(width, height);
+                display.render(w)?;
+            }
+            Input::Release { .. } => {}
+        }
+        Ok(None)
+    }
+}
+
+/// Shows `display` until the user quits, or as soon as a session is over if
+/// `single`.
+fn drive(display: &mut Display, single: bool) -> anyhow::Result<()> {
+    show(&mut Typing { display, single })
+}
+
+/// The shelf or the library, until a book is picked or it's left.
+struct Picking<'a, S> {
+    screen: S,
+    config: &'a Config,
+}
+
+impl<S: Picker> Tui for Picking<'_, S> {
+    type Output = Pick<S::Item>;
+
+    fn enter(&mut self, w: &mut Out) -> anyhow::Result<()> {
+        self.screen.enter(w)
+    }
+
+    fn update(&mut self, input: Input, w: &mut Out) -> anyhow::Result<Option<Self::Output>> {
+        match input {
+            Input::Key { event, .. } => {
+                if let Some(pick) = self.screen.handle_input(event) {
+                    return Ok(Some(pick));
+                }
+            }
+            Input::Resize(width, height) => self.screen.resize(self.config, width, height),
+            _ => return Ok(None),
+        }
+        self.screen.render(w)?;
+        Ok(None)
+    }
+}
+
+/// Passages typed one after another, until they've all been typed or one
+/// is left with escape. Passages typed to the end leave their accuracy in
+/// `typed`.
+struct Passages<'a> {
+    config: &'a Config,
+    texts: Vec<&'a str>,
+    practice: Practice,
+    size: (u16, u16),
+    typed: Vec<f64>,
+}
+
+impl<'a> Passages<'a> {
+    fn new(config: &'a Config, texts: Vec<&'a str>) -> anyhow::Result<Self> {
+        let size = crossterm::terminal::size()?;
+        let practice = Practice::new(config, texts.first().copied().unwrap_or(""), size.0, size.1);
+        Ok(Self {
+            config,
+            texts,
+            practice,
+            size,
+            typed: Vec::new(),
+        })
+    }
+}
+
+impl Tui for Passages<'_> {
+    type Output = ();
+
+    fn enter(&mut self, w: &mut Out) -> anyhow::Result<()> {
+        self.practice.enter(w)
+    }
+
+    fn update(&mut self, input: Input, w: &mut Out) -> anyhow::Result<Option<()>> {
+        match input {
+            Input::Key { event, received } => {
+                if self.practice.handle_input(event, received)? {
+                    if !self.practice.is_finished() {
+                        return Ok(Some(()));
+                    }
+                    self.typed.push(self.practice.stats().accuracy());
+                    let Some(text) = self.texts.get(self.typed.len()) else {
+                        return Ok(Some(()));
+                    };
+                    let (width, height) = self.size;
+                    self.practice = Practice::new(self.config, text, width, height);
+                    return self.practice.enter(w).map(|()| None);
+                }
+            }
+            Input::Resize(width, height) => {
+                self.size = (width, height);
+                self.practice.resize(self.config, width, height);
+            }
+            _ => return Ok(None),
+        }
+        self.practice.render(w)?;
+        Ok(None)
+    }
+}
+
+/// A replay played back, until it ends or a key is pressed.
+struct Replaying<'a> {
+    playback: Playback,
+    config: &'a Config,
+}
+
+impl Tui for Replaying<'_> {
+    type Output = ();
+
+    fn enter(&mut self, w: &mut Out) -> anyhow::Result<()> {
+        self.playback.enter(w)
+    }
+
+    fn timeout(&self) -> Option<Duration> {
+        Some(self.playback.timeout().unwrap_or_default())
+    }
+
+    fn update(&mut self, input: Input, w: &mut Out) -> anyhow::Result<Option<()>> {
+        match input {
+            Input::Key { .. } => return Ok(Some(())),
+            Input::Tick => self.playback.tick(w)?,
+            Input::Resize(width, height) => {
+                self.playback.resize(self.config, width, height);
+                self.playback.tick(w)?;
+            }
+            Input::Focus(_) | Input::Paste(_) | Input::Release { .. } => {}
+        }
+        Ok(self.playback.timeout().is_none().then_some(()))
+    }
+}
+
+/// Shows the books being read until one is picked or the shelf is left.
+fn pick_from_shelf(config: &Config) -> anyhow::Result<Pick<Shelved>> {
+    let store = open_store(config)?;
+    let shelf = store.shelf()?;
+    let due = store.reviews()?.due(store::now()).len();
+    if shelf.books.is_empty() && due == 0 {
+        println!("nothing on the shelf yet; open a book with `ept <path>` to put it there");
+        return Ok(Pick::Quit);
+    }
+
+    let (term_w, term_h) = crossterm::terminal::size()?;
+    let mut picking = Picking {
+        screen: ShelfScreen::new(config, shelf, due, term_w, term_h),
+        config,
+    };
+    let pick = show(&mut picking)?;
+    store.write_shelf(picking.screen.shelf())?;
+    Ok(pick)
+}
+
+/// Types the paragraphs due for review one after another, until they've all
+/// been typed or one is left with escape, and schedules each one typed.
+fn review(config: &Config) -> anyhow::Result<()> {
+    let store = open_store(config)?;
+    let mut reviews = store.reviews()?;
+    let due = reviews.due(store::now());
+    let pass = config.review_below.unwrap_or(review::DEFAULT_PASS);
+    if due.is_empty() {
+        println!("no paragraphs are due for review");
+        return Ok(());
+    }
+
+    let texts = due
+        .iter()
+        .map(|&i| reviews.passages[i].text.as_str())
+        .collect();
+    let mut passages = Passages::new(config, texts)?;
+    show(&mut passages)?;
+    let typed = passages.typed;
+    for (&i, &accuracy) in due.iter().zip(&typed) {
+        reviews.passages[i].grade(accuracy, pass, store::now());
+    }
+
+    let learned = reviews.passages.iter().filter(|r| r.is_learned()).count();
+    reviews.retire_learned();
+    store.write_reviews(&reviews)?;
+    if !typed.is_empty() {
+        let passed = typed.iter().filter(|&&a| a >= pass).count();
+        println!(
+            "reviewed {} of {} paragraphs, {passed} at {:.0}% accuracy or better",
+            typed.len(),
+            due.len(),
+            pass * 100.0
+        );
+    }
+    if learned > 0 {
+        println!("{learned} learned and taken out of review");
+    }
     Ok(())
 }
 
+/// Shows the books in `dir` until one is picked or the library is left.
+fn pick_from_library(config: &Config, dir: &Path) -> anyhow::Result<Option<PathBuf>> {
+    let books = ept::library::scan(dir)?;
+    if books.is_empty() {
+        println!("no books in {}", dir.display());
+        return Ok(None);
+    }
+
+    let (term_w, term_h) = crossterm::terminal::size()?;
+    let mut picking = Picking {
+        screen: LibraryScreen::new(config, books, term_w, term_h),
+        config,
+    };
+    match show(&mut picking)? {
+        Pick::Open(book) => Ok(Some(book.path)),
+        Pick::Review | Pick::Quit => Ok(None),
+    }
+}
+
+/// The profile's sessions, only those tagged `tag` and typed on `keyboard`
+/// if given.
+fn filtered_sessions(
+    config: &Config,
+    tag: Option<&str>,
+    keyboard: Option<&str>,
+) -> anyhow::Result<Vec<Session>> {
+    let mut sessions = open_store(config)?.sessions()?;
+    if let Some(tag) = tag {
+        sessions.retain(|s| s.has_tag(tag));
+        anyhow::ensure!(!sessions.is_empty(), "no sessions tagged {tag:?}");
+    }
+    if let Some(keyboard) = keyboard {
+        sessions.retain(|s| {
+            s.keyboard
+                .as_deref()
+                .is_some_and(|k| k.eq_ignore_ascii_case(keyboard))
+        });
+        anyhow::ensure!(!sessions.is_empty(), "no sessions typed on {keyboard:?}");
+    }
+    Ok(sessions)
+}
+
+/// Book key for a path to a book, or for a book in `keys` named by part of
+/// its title or author.
+fn book_argument(keys: &[&str], arg: &str) -> anyhow::Result<String> {
+    let path = Path::new(arg);
+    if path.is_file() {
+        return Ok(store::book_key(&Epub::new(fs::read(path)?)?));
+    }
+    Ok(analytics::find_book(keys.iter().copied(), arg)?.to_owned())
+}
+
+/// Types the passage of the day, or of the day `code` is for.
+fn daily(config: &Config, code: Option<&str>) -> anyhow::Result<()> {
+    let day = match code {
+        Some(code) => daily::parse_code(code)?,
+        None => date::day_of(store::now()),
+    };
+    let passage = daily::for_day(day);
+    let mut passages = Passages::new(config, vec![passage.text])?;
+    show(&mut passages)?;
+
+    println!("{}, {}", passage.title, passage.author);
+    let practice = &passages.practice;
+    if !practice.is_finished() {
+        println!("not finished");
+        return Ok(());
+    }
+    let stats = practice.stats();
+    println!(
+        "{}: {}, {:.1}% accuracy",
+        daily::code(day),
+        stats.format_speed(config.metric),
+        stats.accuracy() * 100.0
+    );
+    Ok(())
+}
+
+/// Plays back a replay until it ends or the user presses a key.
+fn play(file: &Path, path: &Path, layers: &Layers) -> anyhow::Result<()> {
+    let replay = replay::Replay::load(file)?;
+    let mut book = Epub::new(fs::read(path)?)?;
+    let config = &Sidecar::load(path, &book)?.resolve(layers)?;
+    anyhow::ensure!(
+        store::book_key(&book) == replay.book,
+        "this replay is of {}",
+        replay.book
+    );
+    let (term_w, term_h) = crossterm::terminal::size()?;
+    let playback = Playback::new(&mut book, config, replay, term_w, term_h)?;
+    let mut replaying = Replaying { playback, config };
+    show(&mut replaying)?;
+
+    let stats = replaying.playback.stats();
+    println!(
+        "{}, {:.1}% accuracy",
+        stats.format_speed(config.metric),
+        stats.accuracy() * 100.0
+    );
+    Ok(())
+}
+
+/// Profiles opened so far, so each is checked and migrated once a run, and
+/// a passphrase is only asked for the first time.
+static STORES: Mutex<Vec<Store>> = Mutex::new(Vec::new());
+
+fn open_store(config: &Config) -> anyhow::Result<Store> {
+    let mut stores = STORES.lock().unwrap();
+    let opened = stores
+        .iter()
+        .find(|s| s.profile() == config.profile && s.is_encrypted() == config.encrypt);
+    if let Some(store) = opened {
+        return Ok(store.clone());
+    }
+    let store = match config.encrypt {
+        false => Store::open(&config.profile),
+        true => {
+            let passphrase = match std::env::var("EPT_PASSPHRASE") {
+                Ok(passphrase) => passphrase,
+                Err(_) => {
+                    let prompt = format!("passphrase for {}: ", config.profile);
+                    let passphrase = read_passphrase(&prompt)?;
+                    // Nothing to check a new passphrase against, so a typo
+                    // would lock the profile away for good.
+                    if !Store::has_encrypted_files(&config.profile) {
+                        let again = read_passphrase("again: ")?;
+                        anyhow::ensure!(passphrase == again, "passphrases don't match");
+                    }
+                    passphrase
+                }
+            };
+            Store::open_encrypted(&config.profile, passphrase)?
+        }
+    };
+    store.migrate()?;
+    stores.push(store.clone());
+    Ok(store)
+}
+
+/// Reads a line from the terminal without echoing it. Esc and Ctrl-C give
+/// up, and an empty line is refused.
+fn read_passphrase(prompt: &str) -> anyhow::Result<String> {
+    use crossterm::{
+        event::{KeyCode, KeyModifiers},
+        terminal,
+    };
+
+    print!("{prompt}");
+    io::Write::flush(&mut io::stdout())?;
+    terminal::enable_raw_mode()?;
+    let mut passphrase = String::new();
+    let result = loop {
+        match next_key_event() {
+            Ok(ev) => match ev.code {
+                KeyCode::Enter if passphrase.is_empty() => {
+                    break Err(anyhow::anyhow!("the passphrase can't be empty"))
+                }
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err(anyhow::anyhow!("no passphrase given")),
+                KeyCode::Char('c') if ev.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("no passphrase given"))
+                }
+                KeyCode::Backspace => drop(passphrase.pop()),
+                KeyCode::Char(_)
+                    if ev
+                        .modifiers
+                        .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {}
+                KeyCode::Char(c) => passphrase.push(c),
+                _ => {}
+            },
+            Err(e) => break Err(e),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    println!();
+    result.map(|()| passphrase)
+}
+
+#[cfg(feature = "sync")]
+fn sync(config: &Config) -> anyhow::Result<()> {
+    let Some(sync) = &config.sync else {
+        anyhow::bail!("no [sync] section in the config file");
+    };
+    let report = ept::sync::sync(sync, &open_store(config)?)?;
+    println!(
+        "pulled {}, pushed {} sessions",
+        report.pulled, report.pushed
+    );
+    if report.progress_pulled {
+        println!("brought in progress typed elsewhere");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "sync"))]
+fn sync(_: &Config) -> anyhow::Result<()> {
+    anyhow::bail!("ept was built without the `sync` feature")
+}
+
+#[cfg(feature = "backup")]
+fn backup(file: &Path) -> anyhow::Result<()> {
+    let files = ept::backup::backup(file)?;
+    println!("backed up {files} files to {}", file.display());
+    Ok(())
+}
+
+#[cfg(feature = "backup")]
+fn restore(file: &Path, force: bool) -> anyhow::Result<()> {
+    let files = ept::backup::restore(file, force)?;
+    println!("restored {files} files");
+    Ok(())
+}
+
+#[cfg(not(feature = "backup"))]
+fn backup(_: &Path) -> anyhow::Result<()> {
+    anyhow::bail!("ept was built without the `backup` feature")
+}
+
+#[cfg(not(feature = "backup"))]
+fn restore(_: &Path, _: bool) -> anyhow::Result<()> {
+    anyhow::bail!("ept was built without the `backup` feature")
+}
+
 fn next_key_event() -> anyhow::Result<KeyEvent> {
     loop {
         if let Ok(Event::Key(event)) = event::read() {
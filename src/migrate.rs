@@ -0,0 +1,74 @@
+//! Bringing files written by older versions of ept up to date. Each kind of
+//! file has a list of steps, each rewriting the JSON of one format version
+//! into the next, so old files are read as they were meant to be rather
+//! than failing to parse or quietly losing fields.
+//!
+//! Version 1 is the format files had before they were versioned. Changing a
+//! format means adding a step to the end of its list, never editing one
+//! that's been released.
+
+use serde_json::Value;
+
+/// Rewrites a value in one format version into the next.
+pub type Step = fn(&mut Value) -> anyhow::Result<()>;
+
+/// Format version of a kind of file with these steps.
+pub const fn latest<S>(steps: &[S]) -> u32 {
+    steps.len() as u32 + 1
+}
+
+/// Runs `apply` on each of `steps` needed to bring `what`, in format
+/// `version`, up to date. Fails without applying any if `version` is newer
+/// than this ept knows, since reading it could lose what's new.
+pub fn upgrade<S>(
+    version: u32,
+    steps: &[S],
+    what: &str,
+    mut apply: impl FnMut(&S) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    let latest = latest(steps);
+    anyhow::ensure!(
+        version <= latest,
+        "{what} is format {version}, but this ept only reads up to {latest}; \
+         it was written by a newer version"
+    );
+    for step in &steps[version.max(1) as usize - 1..] {
+        apply(step)?;
+    }
+    Ok(())
+}
+
+/// The `version` field of `value`, or 1 if it has none.
+pub fn version_of(value: &Value) -> u32 {
+    value
+        .get("version")
+        .and_then(Value::as_u64)
+        .map_or(1, |v| v as u32)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn steps_run_from_the_version_given() {
+        let steps: [fn(&mut Vec<u32>); 2] = [|v| v.push(2), |v| v.push(3)];
+        let mut applied = vec![1];
+        upgrade(1, &steps, "test", |step| {
+            step(&mut applied);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(applied, [1, 2, 3]);
+
+        let mut applied = vec![2];
+        upgrade(2, &steps, "test", |step| {
+            step(&mut applied);
+            Ok(())
+        })
+        .unwrap();
+        assert_eq!(applied, [2, 3]);
+
+        assert!(upgrade(4, &steps, "test", |_| Ok(())).is_err());
+    }
+}
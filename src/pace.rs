@@ -0,0 +1,115 @@
+//! The pace caret: a marker that moves through each paragraph at a target
+//! speed, to type against.
+
+use std::time::{Duration, Instant};
+
+use crate::{
+    backend::Len,
+    stats::{self, Stats, Totals},
+};
+
+/// How far back sessions count towards an adaptive target.
+pub const ROLLING_DAYS: u64 = 7;
+
+/// Slowest pace the caret will move at.
+const MIN_WPM: f64 = 5.0;
+
+#[derive(Debug, Clone)]
+pub enum Target {
+    Fixed(f64),
+    /// Recent average speed plus `delta`, updated with the current session
+    /// at each paragraph.
+    Adaptive {
+        recent: Totals,
+        /// Used while there's nothing recent to average.
+        fallback: f64,
+        delta: f64,
+    },
+}
+
+impl Target {
+    fn wpm(&self, session: &Stats) -> f64 {
+        let wpm = match self {
+            Self::Fixed(wpm) => *wpm,
+            Self::Adaptive {
+                recent,
+                fallback,
+                delta,
+            } => {
+                let elapsed = recent.elapsed + session.elapsed;
+                match elapsed.is_zero() {
+                    true => fallback + delta,
+                    false => stats::wpm(recent.chars + session.chars, elapsed) + delta,
+                }
+            }
+        };
+        wpm.max(MIN_WPM)
+    }
+}
+
+#[derive(Debug)]
+pub struct Pace {
+    target: Target,
+    /// Speed for the current paragraph.
+    wpm: f64,
+    /// When and where the current paragraph was started, and where it ends.
+    run: Option<(Instant, Len, Len)>,
+}
+
+impl Pace {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            wpm: 0.0,
+            run: None,
+        }
+    }
+
+    /// Whether the caret is already moving through the paragraph `cursor`
+    /// is in.
+    pub fn is_running_at(&self, cursor: Len) -> bool {
+        self.run
+            .is_some_and(|(_, start, end)| start.chars <= cursor.chars && cursor.chars < end.chars)
+    }
+
+    /// Sets the caret off from `start`, towards `end`.
+    pub fn start_paragraph(&mut self, start: Len, end: Len, session: &Stats) {
+        self.wpm = self.target.wpm(session);
+        self.run = Some((Instant::now(), start, end));
+    }
+
+    /// Whether the caret is still on its way to the end of the paragraph.
+    pub fn is_moving(&self, now: Instant) -> bool {
+        self.run.is_some_and(|(started, start, end)| {
+            let chars_per_sec = self.wpm * stats::WORD_LEN / 60.0;
+            let elapsed = now.saturating_duration_since(started).as_secs_f64();
+            elapsed * chars_per_sec < (end.chars - start.chars) as f64
+        })
+    }
+
+    /// Holds the caret back by `by`, for time spent away.
+    pub fn postpone(&mut self, by: Duration) {
+        if let Some((started, ..)) = &mut self.run {
+            *started += by;
+        }
+    }
+
+    pub fn stop(&mut self) {
+        self.run = None;
+    }
+
+    /// Where the caret is in `text`, stopping at the end of the paragraph.
+    pub fn position(&self, text: &str, now: Instant) -> Option<Len> {
+        let (started, start, end) = self.run?;
+        let chars_per_sec = self.wpm * stats::WORD_LEN / 60.0;
+        let elapsed = now.saturating_duration_since(started);
+        let ahead = (elapsed.as_secs_f64() * chars_per_sec) as usize;
+        if ahead >= end.chars - start.chars {
+            // Rest on the paragraph's last character, its line break.
+            let last = text[..end.bytes].chars().next_back()?;
+            return Some(end - Len::new(last.len_utf8(), 1));
+        }
+        let (bytes, _) = text[start.bytes..].char_indices().nth(ahead)?;
+        Some(start + Len::new(bytes, ahead))
+    }
+}
@@ -0,0 +1,19 @@
+use std::path::PathBuf;
+
+const APP: &str = "ept";
+
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join(APP))
+}
+
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join(APP))
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|d| d.join("config.toml"))
+}
+
+pub fn log_file() -> Option<PathBuf> {
+    data_dir().map(|d| d.join("ept.log"))
+}
@@ -0,0 +1,136 @@
+//! Converting British-style dialogue, in single quotes with double quotes
+//! inside, to double quotes with single quotes inside.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Quote {
+    Single,
+    Double,
+}
+
+/// Words that start with an apostrophe rather than an opening quote.
+const ELISIONS: &[&str] = &[
+    "em", "tis", "twas", "twere", "til", "till", "cause", "round", "n", "im", "er", "ere",
+];
+
+fn is_opening_punctuation(c: char) -> bool {
+    matches!(c, '(' | '[' | '{' | '-' | '—' | '“' | '‘' | '"' | '\'')
+}
+
+/// Character indices of the quote marks in `paragraph`, along with how many
+/// quotations of each kind it opens. Apostrophes are left out.
+fn quote_marks(paragraph: &str) -> (Vec<usize>, usize, usize) {
+    let chars: Vec<char> = paragraph.chars().collect();
+    let mut marks = Vec::new();
+    let mut stack: Vec<Quote> = Vec::new();
+    let (mut singles, mut doubles) = (0, 0);
+
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1).copied();
+        let after_space = prev.is_none_or(|p| p.is_whitespace() || is_opening_punctuation(p));
+        let before_word = next.is_some_and(|n| !n.is_whitespace());
+
+        let (kind, opens) = match c {
+            '“' => (Quote::Double, true),
+            '”' => (Quote::Double, false),
+            '"' => (Quote::Double, after_space && before_word),
+            '‘' | '’' | '\'' => {
+                if prev.is_some_and(char::is_alphanumeric)
+                    && next.is_some_and(char::is_alphanumeric)
+                {
+                    // don't, it's
+                    continue;
+                }
+                let opens = c == '‘' || after_space && before_word;
+                // After a letter and before a space is as likely to be an
+                // apostrophe, as in "the boys' books" or "goin' home".
+                let closes = !after_space
+                    && stack.contains(&Quote::Single)
+                    && (prev.is_some_and(|p| !p.is_alphanumeric())
+                        || next.is_none_or(|n| !n.is_whitespace()));
+                if opens {
+                    let word: String = chars[i + 1..]
+                        .iter()
+                        .take_while(|c| c.is_alphanumeric())
+                        .collect::<String>()
+                        .to_lowercase();
+                    if c != '‘' && ELISIONS.contains(&word.as_str()) {
+                        continue;
+                    }
+                } else if !closes {
+                    continue;
+                }
+                (Quote::Single, opens)
+            }
+            _ => continue,
+        };
+
+        if opens {
+            stack.push(kind);
+            match kind {
+                Quote::Single => singles += 1,
+                Quote::Double => doubles += 1,
+            }
+        } else if let Some(depth) = stack.iter().rposition(|&q| q == kind) {
+            stack.truncate(depth);
+        }
+        marks.push(i);
+    }
+    (marks, singles, doubles)
+}
+
+/// Whether `text` mostly puts dialogue in single quotes.
+pub fn uses_single_quotes(text: &str) -> bool {
+    let (singles, doubles) = text.split('\n').fold((0, 0), |(s, d), paragraph| {
+        let (_, singles, doubles) = quote_marks(paragraph);
+        (s + singles, d + doubles)
+    });
+    singles > doubles
+}
+
+/// Swaps single quotation marks for double ones and the other way round,
+/// leaving apostrophes alone. Every character keeps its length, so offsets
+/// into the text stay valid.
+pub fn swap(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for (i, paragraph) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let (marks, _, _) = quote_marks(paragraph);
+        let mut marks = marks.into_iter().peekable();
+        for (i, c) in paragraph.chars().enumerate() {
+            if marks.next_if_eq(&i).is_none() {
+                out.push(c);
+                continue;
+            }
+            out.push(match c {
+                '‘' => '“',
+                '’' => '”',
+                '\'' => '"',
+                '“' => '‘',
+                '”' => '’',
+                '"' => '\'',
+                c => c,
+            });
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn it_swaps_nested_quotes_and_keeps_apostrophes() {
+        let text = "‘Don’t say “never”,’ she said. ‘’Tis the boys’ fault.’\n\
+                    'He said \"go\" and went.'";
+        assert!(uses_single_quotes(text));
+        assert_eq!(
+            swap(text),
+            "“Don’t say ‘never’,” she said. “’Tis the boys’ fault.”\n\
+             \"He said 'go' and went.\""
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Replay files: every keystroke of a session with when it happened, enough
+//! to play the session back or grade it again.
+
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    backend::{Backend, BackspaceMode, ChapterText},
+    config::Config,
+    migrate,
+    stats::Stats,
+    translate::Action,
+};
+
+/// Steps bringing older replays up to date, see [`migrate`].
+const STEPS: &[migrate::Step] = &[];
+
+/// Format version written to new replays.
+pub const VERSION: u32 = migrate::latest(STEPS);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Event {
+    /// Milliseconds since the first keystroke.
+    pub ms: u64,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    /// Key identifying the book, see [`crate::store::book_key`].
+    pub book: String,
+    /// Spine index of the chapter.
+    pub chapter: usize,
+    /// Character typing started from.
+    pub start: usize,
+    /// [`text_hash`] of the chapter's text as it was typed, which depends
+    /// on the filters in the config at the time.
+    pub text_hash: String,
+    pub backspace: BackspaceMode,
+    /// [`Config::grace_ms`] at the time.
+    #[serde(default)]
+    pub grace_ms: u64,
+    /// [`Config::repeat_words`] at the time.
+    #[serde(default)]
+    pub repeat_words: bool,
+    /// [`Config::stop_on_error`] at the start; toggling it is recorded as
+    /// an event.
+    #[serde(default)]
+    pub stop_on_error: bool,
+    pub events: Vec<Event>,
+}
+
+impl Replay {
+    pub fn new(book: String, chapter: usize, start: usize, text: &str, config: &Config) -> Self {
+        Self {
+            version: VERSION,
+            book,
+            chapter,
+            start,
+            text_hash: text_hash(text),
+            backspace: config.backspace,
+            grace_ms: config.grace_ms,
+            repeat_words: config.repeat_words,
+            stop_on_error: config.stop_on_error,
+            events: Vec::new(),
+        }
+    }
+
+    /// Adds `action`, taken `since_first` after the first one.
+    pub fn record(&mut self, since_first: Duration, action: Action) {
+        self.events.push(Event {
+            ms: since_first.as_millis() as u64,
+            action,
+        });
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_slice(&fs::read(path)?)?;
+        migrate::upgrade(migrate::version_of(&value), STEPS, "replay", |step| {
+            step(&mut value)
+        })?;
+        let mut replay: Self = serde_json::from_value(value)?;
+        replay.version = VERSION;
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(path, serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+
+    /// Whether `text` is the text this replay was typed against.
+    pub fn matches(&self, text: &str) -> bool {
+        self.text_hash == text_hash(text)
+    }
+
+    /// A backend for `text` set up as it was when the session was typed,
+    /// ready for the first keystroke. Fails if `text` isn't what was typed.
+    pub fn backend(&self, text: ChapterText) -> anyhow::Result<Backend> {
+        let mut backend = Backend::from_text(text, self.backspace);
+        anyhow::ensure!(
+            self.matches(backend.text()),
+            "the chapter's text differs from when this was recorded; \
+             check it's the same book, with the same filter settings"
+        );
+        backend.start_at(backend.position(self.start));
+        backend.set_grace(self.grace_ms);
+        backend.set_repeat_words(self.repeat_words);
+        backend.set_stop_on_error(self.stop_on_error);
+        Ok(backend)
+    }
+
+    /// Feeds every keystroke through a [`Backend`] at once, with the times
+    /// they were recorded at, and returns what it makes of them.
+    pub fn grade(&self, text: ChapterText) -> anyhow::Result<Stats> {
+        let mut backend = self.backend(text)?;
+        let base = Instant::now();
+        for event in &self.events {
+            backend.set_clock(base + Duration::from_millis(event.ms));
+            backend.apply(event.action);
+        }
+        Ok(backend.stats())
+    }
+}
+
+/// FNV-1a over the text, in hex.
+pub fn text_hash(text: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in text.bytes() {
+        hash ^= u64::from(b);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::style::Styling;
+
+    /// A recorded session from `tests/replays`, along with the text it was
+    /// typed against and the stats it should grade to.
+    #[derive(Deserialize)]
+    struct Case {
+        text: String,
+        replay: Replay,
+        expected: Expected,
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Expected {
+        chars: usize,
+        elapsed_ms: u64,
+        keystrokes: usize,
+        mistakes: usize,
+        uncorrected: usize,
+    }
+
+    #[test]
+    fn recorded_sessions_grade_the_same() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/replays");
+        let mut cases = 0;
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let case: Case = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+            let text = ChapterText {
+                text: case.text,
+                styling: Styling::builder().build(),
+                blocks: Vec::new(),
+            };
+            let stats = case.replay.grade(text).unwrap();
+            let graded = Expected {
+                chars: stats.chars,
+                elapsed_ms: stats.elapsed.as_millis() as u64,
+                keystrokes: stats.keystrokes,
+                mistakes: stats.mistakes,
+                uncorrected: stats.uncorrected,
+            };
+            assert_eq!(graded, case.expected, "{}", path.display());
+            cases += 1;
+        }
+        assert!(cases > 0);
+    }
+}
@@ -0,0 +1,148 @@
+//! Paragraphs typed below the accuracy in [`Config::review_below`], saved
+//! to type again on later days. Each is scheduled along the lines of SM-2:
+//! the better it goes, the longer until it comes up again, and a bad run
+//! starts it over from a day.
+//!
+//! [`Config::review_below`]: crate::config::Config::review_below
+
+use serde::{Deserialize, Serialize};
+
+const DAY: u64 = 24 * 60 * 60;
+/// Ease a review starts from, and the least it can fall to.
+const START_EASE: f64 = 2.5;
+const MIN_EASE: f64 = 1.3;
+/// Reviews that would next come up further off than this many days have
+/// been learned, and are let go.
+const LEARNED_DAYS: f64 = 60.0;
+/// Accuracy a review must be typed at to pass, when the config no longer
+/// says.
+pub const DEFAULT_PASS: f64 = 0.95;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Reviews {
+    pub passages: Vec<Review>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    /// See [`store::book_key`](crate::store::book_key).
+    pub book: String,
+    /// TOC index of the chapter.
+    pub chapter: usize,
+    /// Character in the chapter the paragraph starts at.
+    pub start: usize,
+    /// The paragraph as it was typed, so it can be typed again without the
+    /// book.
+    pub text: String,
+    /// How quickly the gap between reviews grows.
+    pub ease: f64,
+    /// Days from the last review to the next.
+    pub interval: f64,
+    /// Reviews in a row that went well.
+    pub streak: u32,
+    /// When it's next due, in seconds since the Unix epoch.
+    pub due: u64,
+}
+
+impl Review {
+    /// A paragraph first typed at `now`, due a day later.
+    pub fn new(book: String, chapter: usize, start: usize, text: String, now: u64) -> Self {
+        Self {
+            book,
+            chapter,
+            start,
+            text,
+            ease: START_EASE,
+            interval: 1.0,
+            streak: 0,
+            due: now + DAY,
+        }
+    }
+
+    /// Schedules the next review after typing it at `accuracy` at `now`,
+    /// where anything below `pass` counts as going badly.
+    pub fn grade(&mut self, accuracy: f64, pass: f64, now: u64) {
+        // SM-2 grades from 0 to 5, 3 being a pass.
+        let quality = match accuracy {
+            a if a >= 0.99 => 5.0,
+            a if a >= (pass + 0.99) / 2.0 => 4.0,
+            a if a >= pass => 3.0,
+            _ => 1.0,
+        };
+        self.ease =
+            (self.ease + 0.1 - (5.0 - quality) * (0.08 + (5.0 - quality) * 0.02)).max(MIN_EASE);
+        if quality < 3.0 {
+            self.streak = 0;
+            self.interval = 1.0;
+        } else {
+            self.streak += 1;
+            self.interval = match self.streak {
+                1 => 1.0,
+                2 => 6.0,
+                _ => self.interval * self.ease,
+            };
+        }
+        self.due = now + (self.interval * DAY as f64) as u64;
+    }
+
+    pub fn is_learned(&self) -> bool {
+        self.interval > LEARNED_DAYS
+    }
+}
+
+impl Reviews {
+    /// Saves a paragraph to review, unless it already is.
+    pub fn add(&mut self, review: Review) {
+        let saved = self
+            .passages
+            .iter()
+            .any(|r| r.book == review.book && r.chapter == review.chapter && r.text == review.text);
+        if !saved {
+            self.passages.push(review);
+        }
+    }
+
+    /// Indices of the reviews due at `now`, the longest overdue first.
+    pub fn due(&self, now: u64) -> Vec<usize> {
+        let mut due: Vec<usize> = (0..self.passages.len())
+            .filter(|&i| self.passages[i].due <= now)
+            .collect();
+        due.sort_by_key(|&i| self.passages[i].due);
+        due
+    }
+
+    /// Lets go of reviews that have been learned.
+    pub fn retire_learned(&mut self) {
+        self.passages.retain(|r| !r.is_learned());
+    }
+
+    /// Drops the reviews of the book with `key`. Returns whether there were
+    /// any.
+    pub fn remove_book(&mut self, key: &str) -> bool {
+        let len = self.passages.len();
+        self.passages.retain(|r| r.book != key);
+        self.passages.len() != len
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn good_runs_space_reviews_out_and_bad_ones_start_over() {
+        let mut review = Review::new("book".to_owned(), 0, 0, "text".to_owned(), 0);
+        assert_eq!(review.due, DAY);
+        let mut days = Vec::new();
+        for _ in 0..3 {
+            review.grade(0.995, 0.9, 0);
+            days.push(review.interval);
+        }
+        assert_eq!(days, [1.0, 6.0, 6.0 * review.ease]);
+        assert!(review.ease > START_EASE);
+
+        review.grade(0.5, 0.9, 0);
+        assert_eq!((review.interval, review.streak, review.due), (1.0, 0, DAY));
+        assert!(review.ease < START_EASE);
+    }
+}
@@ -0,0 +1,125 @@
+//! Joining lines that converted books break mid-sentence, left over from
+//! the plain text they were made from, so text wraps at the view's width.
+//! Words hyphenated across such breaks, as in scanned books, are put back
+//! together.
+
+use std::ops::Range;
+
+/// Shortest line taken to have been filled to the source's width rather
+/// than broken on purpose. Plain text is usually filled to 70 to 80
+/// columns, and verse and addresses seldom run this long.
+const MIN_FILLED_LINE: usize = 55;
+
+/// Words older books hyphenate, which shouldn't lose their hyphen when split
+/// across lines at it.
+const HYPHENATED: &[&str] = &[
+    "to-day",
+    "to-morrow",
+    "to-night",
+    "good-bye",
+    "good-night",
+    "good-morning",
+    "so-called",
+    "twenty-one",
+    "well-known",
+];
+
+/// Whether the break between `before` and `after`, consecutive lines of a
+/// paragraph, looks like the source's wrapping.
+fn is_hard_wrap(before: &str, after: &str) -> bool {
+    let before = before.trim_end();
+    let after = after.trim_start();
+    if before.is_empty() || after.is_empty() {
+        return false;
+    }
+    before.chars().count() >= MIN_FILLED_LINE || after.starts_with(char::is_lowercase)
+}
+
+/// If `before` ends in part of a word hyphenated across the break before
+/// `after`, the word's two halves.
+fn split_word<'a>(before: &'a str, after: &'a str) -> Option<(&'a str, &'a str)> {
+    let first = before.trim_end().strip_suffix('-')?;
+    let start = first
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphabetic())
+        .last()
+        .map_or(first.len(), |(i, _)| i);
+    let first = &first[start..];
+    let after = after.trim_start();
+    let second = &after[..after
+        .find(|c: char| !c.is_alphabetic())
+        .unwrap_or(after.len())];
+    let is_word = !first.is_empty() && second.starts_with(char::is_lowercase);
+    is_word.then_some((first, second))
+}
+
+/// Byte ranges of the hard wraps among `breaks`, line breaks inside
+/// paragraphs of `text`, with what to put in their place: a space, or
+/// nothing if there's one there already or the break splits a word.
+///
+/// A hyphen splitting a word goes too, unless the word is in `hyphenated`
+/// or ept's own list, or `text` has it hyphenated more often than not.
+pub fn spans(
+    text: &str,
+    breaks: &[usize],
+    hyphenated: &[String],
+) -> Vec<(Range<usize>, &'static str)> {
+    let lowercase = text.to_lowercase();
+    let keeps_hyphen = |first: &str, second: &str| {
+        let word = format!("{first}-{second}").to_lowercase();
+        HYPHENATED.contains(&word.as_str())
+            || hyphenated.iter().any(|w| w.to_lowercase() == word)
+            || lowercase.matches(&word).count() > lowercase.matches(&word.replace('-', "")).count()
+    };
+
+    let mut spans = Vec::new();
+    for &i in breaks {
+        let line_start = text[..i].rfind('\n').map_or(0, |j| j + 1);
+        let line_end = text[i + 1..].find('\n').map_or(text.len(), |j| i + 1 + j);
+        let (before, after) = (&text[line_start..i], &text[i + 1..line_end]);
+        if !is_hard_wrap(before, after) {
+            continue;
+        }
+        if let Some((first, second)) = split_word(before, after) {
+            let hyphen = line_start + before.trim_end().len() - 1;
+            let end = i + 1 + after.len() - after.trim_start().len();
+            match keeps_hyphen(first, second) {
+                true => spans.push((hyphen + 1..end, "")),
+                false => spans.push((hyphen..end, "")),
+            }
+            continue;
+        }
+        let spaced =
+            before.ends_with(char::is_whitespace) || after.starts_with(char::is_whitespace);
+        spans.push((i..i + 1, if spaced { "" } else { " " }));
+    }
+    spans
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn filled_lines_are_joined_and_verse_is_left() {
+        let text = "It was the best of times, it was the worst of times, it was\n\
+                    the age of wisdom.\n\
+                    Tyger Tyger, burning bright,\n\
+                    In the forests of the night;";
+        let breaks: Vec<usize> = text.match_indices('\n').map(|(i, _)| i).collect();
+        assert_eq!(spans(text, &breaks, &[]), [(breaks[0]..breaks[0] + 1, " ")]);
+    }
+
+    #[test]
+    fn words_split_across_lines_are_put_back_together() {
+        let text = "How wonder-\nful it is to-\nday, said the self-\nmade man.";
+        let breaks: Vec<usize> = text.match_indices('\n').map(|(i, _)| i).collect();
+        let kept = ["self-made".to_owned()];
+        assert_eq!(
+            spans(text, &breaks, &[]),
+            [(10..12, ""), (25..26, ""), (44..46, "")]
+        );
+        assert_eq!(spans(text, &breaks, &kept)[2], (45..46, ""));
+    }
+}
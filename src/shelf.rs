@@ -0,0 +1,108 @@
+//! Books being read, most recently opened first, with where to pick each one
+//! up again. `ept` without a book opens on the shelf.
+
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Shelf {
+    pub books: Vec<Shelved>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Shelved {
+    /// See [`store::book_key`](crate::store::book_key).
+    pub key: String,
+    pub title: String,
+    pub author: Option<String>,
+    /// Where the book was last opened from.
+    pub path: PathBuf,
+    /// When the book was last opened, in seconds since the Unix epoch.
+    pub opened: u64,
+    #[serde(default)]
+    pub resume: Option<Resume>,
+    /// Day to finish the book by, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finish_by: Option<u64>,
+}
+
+/// Where typing would pick up again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Resume {
+    /// TOC index of the chapter.
+    pub chapter: usize,
+    /// Character in the chapter where the cursor was left, or the first not
+    /// yet typed correctly.
+    pub offset: usize,
+    pub name: String,
+}
+
+impl Shelf {
+    /// Puts `book` at the front of the shelf, keeping where it was left off
+    /// if it was already there and `book` doesn't say.
+    pub fn put(&mut self, mut book: Shelved) {
+        if let Some(i) = self.books.iter().position(|b| b.key == book.key) {
+            let old = self.books.remove(i);
+            book.resume = book.resume.or(old.resume);
+            book.finish_by = book.finish_by.or(old.finish_by);
+        }
+        self.books.insert(0, book);
+    }
+
+    /// Records where to pick up the book with `key` again.
+    pub fn set_resume(&mut self, key: &str, resume: Option<Resume>) {
+        if let Some(book) = self.books.iter_mut().find(|b| b.key == key) {
+            book.resume = resume;
+        }
+    }
+
+    /// Sets the day to finish the book with `key` by. Returns whether it's on
+    /// the shelf.
+    pub fn set_finish_by(&mut self, key: &str, finish_by: Option<u64>) -> bool {
+        let book = self.books.iter_mut().find(|b| b.key == key);
+        book.map(|book| book.finish_by = finish_by).is_some()
+    }
+
+    /// Takes the book with `key` off the shelf. Returns whether it was on it.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.books.len();
+        self.books.retain(|b| b.key != key);
+        self.books.len() != len
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn book(key: &str, resume: Option<usize>) -> Shelved {
+        Shelved {
+            key: key.to_owned(),
+            title: key.to_owned(),
+            author: None,
+            path: PathBuf::from(format!("{key}.epub")),
+            opened: 0,
+            resume: resume.map(|chapter| Resume {
+                chapter,
+                offset: 0,
+                name: String::new(),
+            }),
+            finish_by: None,
+        }
+    }
+
+    #[test]
+    fn reopened_books_move_to_the_front_and_keep_their_place() {
+        let mut shelf = Shelf::default();
+        shelf.put(book("a", Some(3)));
+        shelf.put(book("b", None));
+        shelf.put(book("a", None));
+        let keys: Vec<_> = shelf.books.iter().map(|b| b.key.as_str()).collect();
+        assert_eq!(keys, ["a", "b"]);
+        assert_eq!(shelf.books[0].resume.as_ref().map(|r| r.chapter), Some(3));
+
+        assert!(shelf.remove("b"));
+        assert!(!shelf.remove("b"));
+    }
+}
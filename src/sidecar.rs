@@ -0,0 +1,121 @@
+//! Per-book settings, kept next to the book as `<name>.ept.toml`, for
+//! fixing up a badly made book without editing it.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use lepu::Epub;
+use serde::Deserialize;
+
+use crate::{
+    config::{Config, Layers, Origin},
+    filter::Replacements,
+};
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Sidecar {
+    /// Table of contents entries, counting from 1, in the order chapter
+    /// select should list them. Entries left out follow in book order.
+    pub order: Vec<usize>,
+    /// Entries to leave out of chapter select.
+    pub exclude: Vec<usize>,
+    /// Entries folded into the one listed before them, for books with an
+    /// entry per scene. Chapters are typed a whole file at a time, so only
+    /// entries sharing a file with the one before can be merged; their text
+    /// is typed along with it.
+    pub merge: Vec<usize>,
+    /// Text to change before typing, such as OCR errors, added to the
+    /// config's own rules.
+    pub replace: Replacements,
+    /// Settings for this book, over the config file's and the profile's.
+    pub config: toml::Table,
+}
+
+impl Sidecar {
+    /// Where the sidecar for the book at `book` lives.
+    pub fn path(book: &Path) -> PathBuf {
+        book.with_extension("ept.toml")
+    }
+
+    /// Reads the sidecar for `book`, kept at `path`, or returns an empty one
+    /// if there isn't one.
+    pub fn load(path: &Path, book: &Epub) -> anyhow::Result<Self> {
+        let chapter_count = book.chapter_count();
+        let path = Self::path(path);
+        let sidecar: Self = match fs::read_to_string(&path) {
+            Ok(s) => toml::from_str(&s)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(e.into()),
+        };
+        for &entry in sidecar
+            .order
+            .iter()
+            .chain(&sidecar.exclude)
+            .chain(&sidecar.merge)
+        {
+            anyhow::ensure!(
+                (1..=chapter_count).contains(&entry),
+                "{}: no chapter {entry}, the book has {chapter_count}",
+                path.display()
+            );
+        }
+        // Merging an entry from another file would drop its text, and let the
+        // book count as finished without it.
+        let file = |entry: usize| {
+            book.chapter_by_toc_index(entry - 1)
+                .map(|c| c.index_in_spine())
+        };
+        for &entry in &sidecar.merge {
+            anyhow::ensure!(
+                entry > 1 && file(entry) == file(entry - 1),
+                "{}: chapter {entry} can't be merged, it's in a different file from the one \
+                 before; use `exclude` to leave it out instead",
+                path.display()
+            );
+        }
+        Ok(sidecar)
+    }
+
+    /// The settings in effect for this book.
+    pub fn resolve(&self, layers: &Layers) -> anyhow::Result<Config> {
+        let mut layers = layers.clone();
+        layers.set(Origin::Book, self.config.clone());
+        let (mut config, _) = layers.resolve()?;
+        config.replace.extend(self.replace.clone());
+        Ok(config)
+    }
+
+    /// Drops excluded and merged entries from `toc`, a list of TOC indices,
+    /// and puts the rest in reading order.
+    pub fn arrange(&self, toc: &mut Vec<usize>) {
+        toc.retain(|i| !self.exclude.contains(&(i + 1)) && !self.merge.contains(&(i + 1)));
+        // Stable, so unlisted entries keep their order after the listed ones.
+        toc.sort_by_key(|i| {
+            self.order
+                .iter()
+                .position(|&o| o == i + 1)
+                .unwrap_or(self.order.len())
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arranging_reorders_and_drops_entries() {
+        let sidecar = Sidecar {
+            order: vec![4, 2],
+            exclude: vec![1],
+            merge: vec![6],
+            ..Default::default()
+        };
+        let mut toc: Vec<usize> = (0..7).collect();
+        sidecar.arrange(&mut toc);
+        assert_eq!(toc, [3, 1, 2, 4, 6]);
+    }
+}
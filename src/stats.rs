@@ -0,0 +1,710 @@
+use std::{
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    analytics::{Bigrams, ClassStats, Intervals, ShiftStats},
+    backend::BackspaceMode,
+    difficulty,
+};
+
+/// Characters per word in WPM calculations.
+pub const WORD_LEN: f64 = 5.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Stats {
+    pub mode: BackspaceMode,
+    /// Characters advanced past since typing started.
+    pub chars: usize,
+    /// Time from the first keystroke to the last.
+    pub elapsed: Duration,
+    /// Characters pushed, including ones later deleted.
+    pub keystrokes: usize,
+    /// Incorrect characters pushed, including ones later corrected.
+    pub mistakes: usize,
+    /// Errors still present in the typed text.
+    pub uncorrected: usize,
+    /// Mistakes fixed by overtyping them within the grace window, see
+    /// [`Config::grace_ms`](crate::config::Config::grace_ms).
+    pub forgiven: usize,
+}
+
+/// Which speed to show where a session is summed up in one number.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Metric {
+    /// Gross words per minute, counting every character typed.
+    #[default]
+    Wpm,
+    /// Gross WPM less a word for each uncorrected error per minute.
+    Net,
+    /// Characters per minute.
+    Cpm,
+}
+
+impl Metric {
+    pub fn unit(self) -> &'static str {
+        match self {
+            Self::Wpm => "wpm",
+            Self::Net => "net wpm",
+            Self::Cpm => "cpm",
+        }
+    }
+}
+
+impl Stats {
+    /// Gross WPM.
+    pub fn wpm(&self) -> f64 {
+        wpm(self.chars, self.elapsed)
+    }
+
+    pub fn net_wpm(&self) -> f64 {
+        net_wpm(self.chars, self.uncorrected, self.elapsed)
+    }
+
+    pub fn cpm(&self) -> f64 {
+        cpm(self.chars, self.elapsed)
+    }
+
+    pub fn speed(&self, metric: Metric) -> f64 {
+        match metric {
+            Metric::Wpm => self.wpm(),
+            Metric::Net => self.net_wpm(),
+            Metric::Cpm => self.cpm(),
+        }
+    }
+
+    /// Speed in `metric` with its unit, as in `72 net wpm`.
+    pub fn format_speed(&self, metric: Metric) -> String {
+        format!("{:.0} {}", self.speed(metric), metric.unit())
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        match self.keystrokes {
+            0 => 1.0,
+            n => (n - self.mistakes) as f64 / n as f64,
+        }
+    }
+}
+
+/// Gross words per minute: characters typed over [`WORD_LEN`], per minute.
+pub fn wpm(chars: usize, elapsed: Duration) -> f64 {
+    cpm(chars, elapsed) / WORD_LEN
+}
+
+/// Gross WPM less uncorrected errors per minute, never below zero.
+pub fn net_wpm(chars: usize, uncorrected: usize, elapsed: Duration) -> f64 {
+    match elapsed.as_secs_f64() {
+        secs if secs > 0.0 => (wpm(chars, elapsed) - uncorrected as f64 / (secs / 60.0)).max(0.0),
+        _ => 0.0,
+    }
+}
+
+/// Characters per minute.
+pub fn cpm(chars: usize, elapsed: Duration) -> f64 {
+    match elapsed.as_secs_f64() {
+        secs if secs > 0.0 => chars as f64 / (secs / 60.0),
+        _ => 0.0,
+    }
+}
+
+/// WPM over `buckets` equal slices of the session, from a history of
+/// `(time, characters advanced)` samples.
+pub fn speed_graph(history: &[(Instant, usize)], buckets: usize) -> Vec<f64> {
+    let (Some(&(first, _)), Some(&(last, _))) = (history.first(), history.last()) else {
+        return Vec::new();
+    };
+    let total = last - first;
+    if total.is_zero() || buckets == 0 {
+        return Vec::new();
+    }
+    let bucket_len = total / buckets as u32;
+
+    let mut speeds = Vec::with_capacity(buckets);
+    let mut samples = history.iter().peekable();
+    let mut progress = 0;
+    for i in 1..=buckets {
+        let end = first + bucket_len * i as u32;
+        let before = progress;
+        while let Some(&&(at, chars)) = samples.peek() {
+            if at > end && i < buckets {
+                break;
+            }
+            progress = chars;
+            samples.next();
+        }
+        speeds.push(wpm(progress.saturating_sub(before), bucket_len));
+    }
+    speeds
+}
+
+/// Sentences shorter than this, in characters, are left out of
+/// [`sentence_speeds`]; a few keystrokes make for a meaningless speed.
+const MIN_SENTENCE_LEN: usize = 12;
+
+/// How fast one sentence of a session was typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SentenceSpeed {
+    pub text: String,
+    pub wpm: f64,
+}
+
+/// Character ranges of the sentences in `text`, each ending after its
+/// terminating punctuation and any closing quotes or brackets, or at a line
+/// break. Punctuation followed by a lowercase word, as in `"Stop!" he said`,
+/// doesn't end one. Whitespace between sentences belongs to neither.
+pub fn sentences(text: &str) -> Vec<Range<usize>> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut sentences = Vec::new();
+    let mut start = None;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() && start.is_none() {
+            i += 1;
+            continue;
+        }
+        let begin = *start.get_or_insert(i);
+        if c == '\n' {
+            sentences.push(begin..i);
+            start = None;
+        } else if matches!(c, '.' | '!' | '?') {
+            let mut end = i + 1;
+            while end < chars.len()
+                && matches!(
+                    chars[end],
+                    '.' | '!' | '?' | '"' | '\'' | '’' | '”' | ')' | ']'
+                )
+            {
+                end += 1;
+            }
+            let next = chars[end..].iter().find(|c| !c.is_whitespace());
+            let at_break = chars.get(end).is_none_or(|c| c.is_whitespace());
+            if at_break && !next.is_some_and(|c| c.is_lowercase()) {
+                sentences.push(begin..end);
+                start = None;
+            }
+            i = end;
+            continue;
+        }
+        i += 1;
+    }
+    if let Some(begin) = start {
+        sentences.push(begin..chars.len());
+    }
+    sentences
+}
+
+/// Speed of each sentence typed in full, in the order they were typed.
+/// `text` is what's being typed from the position typing started at, and
+/// `history` is [`Backend::history`](crate::backend::Backend::history).
+///
+/// A sentence is timed from the keystroke that typed its first character to
+/// the one that typed its last, so its first character isn't counted, the
+/// same way [`Stats::elapsed`] leaves out the time before the first key.
+pub fn sentence_speeds(text: &str, history: &[(Instant, usize)]) -> Vec<SentenceSpeed> {
+    let reached = |chars: usize| {
+        history
+            .iter()
+            .find(|&&(_, progress)| progress >= chars)
+            .map(|&(at, _)| at)
+    };
+    let chars: Vec<char> = text.chars().collect();
+    sentences(text)
+        .into_iter()
+        .filter(|range| range.len() >= MIN_SENTENCE_LEN)
+        .map_while(|range| {
+            let end = reached(range.end)?;
+            let start = reached(range.start + 1)?;
+            let text = chars[range.clone()]
+                .iter()
+                .map(|&c| if c.is_whitespace() { ' ' } else { c })
+                .collect();
+            Some(SentenceSpeed {
+                text,
+                wpm: wpm(range.len() - 1, end - start),
+            })
+        })
+        .collect()
+}
+
+/// How one paragraph of a session went, kept with the session to compare
+/// against when the chapter is typed again.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ParagraphResult {
+    /// Character the paragraph starts at in the chapter.
+    pub start: usize,
+    pub wpm: f64,
+    pub mistakes: usize,
+}
+
+/// Results for each paragraph of `text` typed in full. `text` starts
+/// `offset` characters into the chapter, and `history` counts from there;
+/// `mistakes` are characters of the chapter.
+pub fn paragraph_results(
+    text: &str,
+    offset: usize,
+    history: &[(Instant, usize)],
+    mistakes: &[usize],
+) -> Vec<ParagraphResult> {
+    let reached = |chars: usize| {
+        history
+            .iter()
+            .find(|&&(_, progress)| progress >= chars)
+            .map(|&(at, _)| at)
+    };
+    let mut results = Vec::new();
+    let mut start = 0;
+    for paragraph in text.split('\n') {
+        let range = start..start + paragraph.chars().count();
+        start = range.end + 1;
+        if range.len() < MIN_SENTENCE_LEN {
+            continue;
+        }
+        let (Some(began), Some(ended)) = (reached(range.start + 1), reached(range.end)) else {
+            break;
+        };
+        // Counting a mistake on the line break after it.
+        let chars = offset + range.start..=offset + range.end;
+        results.push(ParagraphResult {
+            start: offset + range.start,
+            wpm: wpm(range.len() - 1, ended - began),
+            mistakes: mistakes.iter().filter(|i| chars.contains(i)).count(),
+        });
+    }
+    results
+}
+
+/// How a paragraph went compared to the last time it was typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParagraphChange {
+    /// Number of the paragraph in the chapter, counting from 1 as the
+    /// paragraph gutter does.
+    pub paragraph: usize,
+    pub wpm: f64,
+    pub mistakes: isize,
+}
+
+/// A chapter typed again, compared paragraph by paragraph with the run
+/// before.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Rerun {
+    /// Paragraphs typed in full both times, biggest change in speed first.
+    pub changes: Vec<ParagraphChange>,
+    /// Mistakes fewer than last time, across paragraphs that improved.
+    pub fixed: usize,
+    /// Mistakes more than last time, across paragraphs that got worse.
+    pub regressed: usize,
+}
+
+impl Rerun {
+    /// Compares the results of two runs through `text`, a chapter.
+    pub fn compare(text: &str, previous: &[ParagraphResult], current: &[ParagraphResult]) -> Self {
+        let starts: Vec<usize> = std::iter::once(0)
+            .chain(
+                text.chars()
+                    .enumerate()
+                    .filter(|&(_, c)| c == '\n')
+                    .map(|(i, _)| i + 1),
+            )
+            .collect();
+        let mut rerun = Self::default();
+        for now in current {
+            let Some(then) = previous.iter().find(|p| p.start == now.start) else {
+                continue;
+            };
+            let mistakes = now.mistakes as isize - then.mistakes as isize;
+            match mistakes {
+                m if m < 0 => rerun.fixed += m.unsigned_abs(),
+                m => rerun.regressed += m as usize,
+            }
+            rerun.changes.push(ParagraphChange {
+                paragraph: starts.partition_point(|&s| s <= now.start),
+                wpm: now.wpm - then.wpm,
+                mistakes,
+            });
+        }
+        rerun
+            .changes
+            .sort_by(|a, b| b.wpm.abs().total_cmp(&a.wpm.abs()));
+        rerun
+    }
+
+    /// Change in speed averaged over the paragraphs compared.
+    pub fn mean_wpm(&self) -> f64 {
+        match self.changes.len() {
+            0 => 0.0,
+            n => self.changes.iter().map(|c| c.wpm).sum::<f64>() / n as f64,
+        }
+    }
+}
+
+/// Formats as `m:ss`, or `h:mm:ss` past an hour.
+pub fn format_duration(d: Duration) -> String {
+    let secs = d.as_secs();
+    match secs / 3600 {
+        0 => format!("{}:{:02}", secs / 60, secs % 60),
+        h => format!("{h}:{:02}:{:02}", secs / 60 % 60, secs % 60),
+    }
+}
+
+/// A stretch of typing in one chapter, as persisted in the session log.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    /// Key identifying the book, see [`crate::store::book_key`].
+    pub book: String,
+    pub chapter: usize,
+    /// Seconds since the Unix epoch when the session ended.
+    pub timestamp: u64,
+    pub chars: usize,
+    pub elapsed_ms: u64,
+    pub keystrokes: usize,
+    pub mistakes: usize,
+    pub uncorrected: usize,
+    /// Whether the chapter was typed to the end.
+    #[serde(default)]
+    pub finished: bool,
+    /// [`Difficulty::score`](crate::difficulty::Difficulty::score) of the
+    /// chapter, if it was known.
+    #[serde(default)]
+    pub difficulty: Option<f64>,
+    /// Keystrokes by class of character.
+    #[serde(default, skip_serializing_if = "ClassStats::is_empty")]
+    pub classes: ClassStats,
+    /// Shifted keystrokes against unshifted ones.
+    #[serde(default, skip_serializing_if = "ShiftStats::is_empty")]
+    pub shift: ShiftStats,
+    /// Pairs of letters mistyped.
+    #[serde(default, skip_serializing_if = "Bigrams::is_empty")]
+    pub bigrams: Bigrams,
+    /// Time between keystrokes.
+    #[serde(default, skip_serializing_if = "Intervals::is_empty")]
+    pub intervals: Intervals,
+    /// Labels given on the score screen, such as the keyboard used or how
+    /// the typist felt.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// [`Config::keyboard`](crate::config::Config::keyboard) at the time.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyboard: Option<String>,
+    /// Pastes refused while typing.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub pastes: usize,
+    /// Mistakes overtyped within the grace window.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub forgiven: usize,
+    /// Each paragraph typed in full.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paragraphs: Vec<ParagraphResult>,
+    /// Characters of the chapter each mistake was made on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<usize>,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+impl Session {
+    pub fn new(
+        book: String,
+        chapter: usize,
+        timestamp: u64,
+        stats: &Stats,
+        finished: bool,
+        difficulty: Option<f64>,
+    ) -> Self {
+        Self {
+            book,
+            chapter,
+            timestamp,
+            finished,
+            difficulty,
+            chars: stats.chars,
+            elapsed_ms: stats.elapsed.as_millis() as u64,
+            keystrokes: stats.keystrokes,
+            mistakes: stats.mistakes,
+            uncorrected: stats.uncorrected,
+            forgiven: stats.forgiven,
+            classes: ClassStats::default(),
+            shift: ShiftStats::default(),
+            bigrams: Bigrams::default(),
+            intervals: Intervals::default(),
+            tags: Vec::new(),
+            keyboard: None,
+            pastes: 0,
+            paragraphs: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_millis(self.elapsed_ms)
+    }
+
+    pub fn wpm(&self) -> f64 {
+        wpm(self.chars, self.elapsed())
+    }
+
+    pub fn net_wpm(&self) -> f64 {
+        net_wpm(self.chars, self.uncorrected, self.elapsed())
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        match self.keystrokes {
+            0 => 1.0,
+            n => (n - self.mistakes) as f64 / n as f64,
+        }
+    }
+
+    /// Whether the session looks like it was typed by hand, rather than
+    /// pasted or scripted.
+    pub fn is_plausible(&self) -> bool {
+        self.pastes == 0 && self.intervals.suspicion().is_none()
+    }
+
+    /// WPM scaled by the chapter's difficulty, for comparing across chapters.
+    pub fn adjusted_wpm(&self) -> f64 {
+        difficulty::adjusted_wpm(self.wpm(), self.difficulty.unwrap_or(1.0))
+    }
+}
+
+/// How `ept run` prints the session it ran.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for ReportFormat {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            _ => Err(anyhow::anyhow!("unknown report format `{s}`")),
+        }
+    }
+}
+
+/// A session as reported to scripts. Field names are kept stable.
+#[derive(Serialize)]
+struct Report {
+    book: String,
+    /// TOC entry, counting from 1.
+    chapter: usize,
+    finished: bool,
+    chars: usize,
+    elapsed_ms: u64,
+    keystrokes: usize,
+    mistakes: usize,
+    uncorrected: usize,
+    forgiven: usize,
+    wpm: f64,
+    net_wpm: f64,
+    accuracy: f64,
+}
+
+impl ReportFormat {
+    /// `session` written out in this format, ending in a newline.
+    pub fn render(self, session: &Session) -> anyhow::Result<String> {
+        match self {
+            Self::Text => Ok(format!(
+                "{:.0} wpm, {:.0} net, {:.1}% accuracy, {} characters in {}\n",
+                session.wpm(),
+                session.net_wpm(),
+                session.accuracy() * 100.0,
+                session.chars,
+                format_duration(session.elapsed()),
+            )),
+            Self::Json => {
+                let round = |x: f64, places: i32| {
+                    let scale = 10f64.powi(places);
+                    (x * scale).round() / scale
+                };
+                let report = Report {
+                    book: session.book.clone(),
+                    chapter: session.chapter + 1,
+                    finished: session.finished,
+                    chars: session.chars,
+                    elapsed_ms: session.elapsed_ms,
+                    keystrokes: session.keystrokes,
+                    mistakes: session.mistakes,
+                    uncorrected: session.uncorrected,
+                    forgiven: session.forgiven,
+                    wpm: round(session.wpm(), 1),
+                    net_wpm: round(session.net_wpm(), 1),
+                    accuracy: round(session.accuracy(), 3),
+                };
+                Ok(serde_json::to_string(&report)? + "\n")
+            }
+        }
+    }
+}
+
+/// Aggregate over any number of sessions.
+#[derive(Debug, Clone, Default)]
+pub struct Totals {
+    pub sessions: usize,
+    pub chars: usize,
+    pub elapsed: Duration,
+    pub keystrokes: usize,
+    pub mistakes: usize,
+    pub best_wpm: f64,
+    /// Characters weighted by the difficulty of the chapter they were in.
+    pub adjusted_chars: f64,
+}
+
+impl Totals {
+    pub fn add(&mut self, session: &Session) {
+        self.sessions += 1;
+        self.chars += session.chars;
+        self.elapsed += session.elapsed();
+        self.keystrokes += session.keystrokes;
+        self.mistakes += session.mistakes;
+        self.best_wpm = self.best_wpm.max(session.wpm());
+        self.adjusted_chars += session.chars as f64 * session.difficulty.unwrap_or(1.0);
+    }
+
+    /// Average WPM, weighted by time spent in each session.
+    pub fn wpm(&self) -> f64 {
+        wpm(self.chars, self.elapsed)
+    }
+
+    /// Average WPM with each session scaled by its chapter's difficulty.
+    pub fn adjusted_wpm(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            secs if secs > 0.0 => self.adjusted_chars / WORD_LEN / (secs / 60.0),
+            _ => 0.0,
+        }
+    }
+
+    pub fn accuracy(&self) -> f64 {
+        match self.keystrokes {
+            0 => 1.0,
+            n => (n - self.mistakes) as f64 / n as f64,
+        }
+    }
+}
+
+impl<'a> FromIterator<&'a Session> for Totals {
+    fn from_iter<I: IntoIterator<Item = &'a Session>>(iter: I) -> Self {
+        let mut totals = Self::default();
+        for session in iter {
+            totals.add(session);
+        }
+        totals
+    }
+}
+
+/// A result imported from another typing tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sample {
+    /// Tool the result came from.
+    pub source: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub wpm: f64,
+    /// From 0 to 1, if the tool recorded it.
+    pub accuracy: Option<f64>,
+}
+
+/// Results considered when working out a baseline speed.
+const BASELINE_WINDOW: usize = 50;
+
+/// Typical recent speed: the mean WPM of the latest results, whether typed
+/// here or imported. `None` with no history at all.
+pub fn baseline(sessions: &[Session], imported: &[Sample]) -> Option<f64> {
+    let mut recent: Vec<(u64, f64)> = sessions
+        .iter()
+        .filter(|s| s.elapsed_ms > 0)
+        .map(|s| (s.timestamp, s.wpm()))
+        .chain(imported.iter().map(|s| (s.timestamp, s.wpm)))
+        .collect();
+    recent.sort_by_key(|&(timestamp, _)| std::cmp::Reverse(timestamp));
+    recent.truncate(BASELINE_WINDOW);
+    match recent.len() {
+        0 => None,
+        n => Some(recent.iter().map(|&(_, wpm)| wpm).sum::<f64>() / n as f64),
+    }
+}
+
+/// Tags from a comma separated list, trimmed and without repeats.
+pub fn parse_tags(s: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in s.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_owned());
+        }
+    }
+    tags
+}
+
+/// Totals over sessions in the `days` days before `now`.
+pub fn recent(sessions: &[Session], now: u64, days: u64) -> Totals {
+    let since = now.saturating_sub(days * 24 * 60 * 60);
+    sessions.iter().filter(|s| s.timestamp >= since).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_is_split_into_sentences() {
+        let text = "\"Stop!\" he said. It was 3.5 miles away.\nA heading\n\nEnd";
+        let sentences: Vec<String> = sentences(text)
+            .into_iter()
+            .map(|r| text.chars().skip(r.start).take(r.len()).collect())
+            .collect();
+        assert_eq!(
+            sentences,
+            [
+                "\"Stop!\" he said.",
+                "It was 3.5 miles away.",
+                "A heading",
+                "End"
+            ]
+        );
+    }
+
+    #[test]
+    fn reruns_are_compared_paragraph_by_paragraph() {
+        let result = |start, wpm, mistakes| ParagraphResult {
+            start,
+            wpm,
+            mistakes,
+        };
+        let text = format!("{}\n{}\n{}", "a".repeat(49), "b".repeat(39), "c".repeat(20));
+        let previous = [result(0, 40.0, 3), result(50, 45.0, 0), result(90, 50.0, 1)];
+        // The last paragraph wasn't reached this time.
+        let current = [result(0, 48.0, 1), result(50, 43.0, 2)];
+        let rerun = Rerun::compare(&text, &previous, &current);
+        assert_eq!(
+            rerun.changes,
+            [
+                ParagraphChange {
+                    paragraph: 1,
+                    wpm: 8.0,
+                    mistakes: -2
+                },
+                ParagraphChange {
+                    paragraph: 2,
+                    wpm: -2.0,
+                    mistakes: 2
+                },
+            ]
+        );
+        assert_eq!((rerun.fixed, rerun.regressed), (2, 2));
+        assert_eq!(rerun.mean_wpm(), 3.0);
+    }
+}
@@ -0,0 +1,755 @@
+//! Per-profile persistence. Each profile gets its own directory under
+//! `profiles/` in the data directory.
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use lepu::Epub;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{
+    coverage::Coverage,
+    migrate, paths,
+    replay::Replay,
+    review::Reviews,
+    shelf::Shelf,
+    stats::{Sample, Session},
+    warmup::Warmup,
+};
+
+#[cfg(feature = "encrypt")]
+mod crypt;
+
+#[cfg(not(feature = "encrypt"))]
+mod crypt {
+    pub fn encrypt(_: &str, _: &[u8]) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("ept was built without the `encrypt` feature")
+    }
+
+    pub fn decrypt(_: &str, _: &[u8]) -> anyhow::Result<Vec<u8>> {
+        anyhow::bail!("ept was built without the `encrypt` feature")
+    }
+}
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Steps bringing profiles written by older versions up to date, see
+/// [`migrate`]. Each is given the values in every file: the lines of each
+/// log, or the coverage document.
+const STEPS: &[ProfileStep] = &[];
+
+type ProfileStep = fn(File, &mut serde_json::Value) -> anyhow::Result<()>;
+
+/// Format version of the files in a profile, kept in its `format` file.
+pub const FORMAT: u32 = migrate::latest(STEPS);
+
+/// Set for the rest of the run by [`go_incognito`].
+static INCOGNITO: AtomicBool = AtomicBool::new(false);
+
+/// Stops every profile from saving anything for the rest of the run.
+/// Profiles can still be read, and writes succeed without doing anything.
+pub fn go_incognito() {
+    INCOGNITO.store(true, Ordering::Relaxed);
+}
+
+pub fn is_incognito() -> bool {
+    INCOGNITO.load(Ordering::Relaxed)
+}
+
+/// The files kept per profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum File {
+    /// JSON lines log of [`Session`]s.
+    Sessions,
+    /// JSON lines log of results brought over from other typing tools.
+    Imported,
+    /// JSON [`Coverage`] document.
+    Coverage,
+    /// JSON lines log of [`Warmup`]s.
+    Warmups,
+    /// JSON [`Shelf`] document.
+    Shelf,
+    /// JSON [`Reviews`] document.
+    Reviews,
+}
+
+impl File {
+    const ALL: [Self; 6] = [
+        Self::Sessions,
+        Self::Imported,
+        Self::Coverage,
+        Self::Warmups,
+        Self::Shelf,
+        Self::Reviews,
+    ];
+
+    /// Whether the file is a single JSON document rather than a log.
+    fn is_document(self) -> bool {
+        matches!(self, Self::Coverage | Self::Shelf | Self::Reviews)
+    }
+
+    fn plain_name(self) -> &'static str {
+        match self {
+            Self::Sessions => "sessions.jsonl",
+            Self::Imported => "imported.jsonl",
+            Self::Coverage => "coverage.json",
+            Self::Warmups => "warmups.jsonl",
+            Self::Shelf => "shelf.json",
+            Self::Reviews => "reviews.json",
+        }
+    }
+
+    fn encrypted_name(self) -> &'static str {
+        match self {
+            Self::Sessions => "sessions.jsonl.age",
+            Self::Imported => "imported.jsonl.age",
+            Self::Coverage => "coverage.json.age",
+            Self::Warmups => "warmups.jsonl.age",
+            Self::Shelf => "shelf.json.age",
+            Self::Reviews => "reviews.json.age",
+        }
+    }
+}
+
+/// What's kept for one book, see [`Store::usage`].
+#[derive(Debug, Default)]
+pub struct BookUsage {
+    pub sessions: usize,
+    pub replays: usize,
+    /// Size of the replays on disk.
+    pub replay_bytes: u64,
+    /// When the book was last typed, in seconds since the Unix epoch.
+    pub last_typed: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct Store {
+    profile: String,
+    /// `None` if the platform has no data directory.
+    dir: Option<PathBuf>,
+    /// Set if the profile's files are encrypted.
+    passphrase: Option<String>,
+}
+
+impl Store {
+    pub fn open(profile: &str) -> Self {
+        Self {
+            profile: profile.to_owned(),
+            dir: profiles_dir().map(|d| d.join(profile)),
+            passphrase: None,
+        }
+    }
+
+    /// Opens a profile whose files are encrypted with `passphrase`. Plaintext
+    /// files left from before encryption was enabled are encrypted and
+    /// removed. Fails if the profile's files were encrypted with another
+    /// passphrase, rather than opening it empty and writing over them.
+    pub fn open_encrypted(profile: &str, passphrase: String) -> anyhow::Result<Self> {
+        let plain = Self::open(profile);
+        let store = Self {
+            passphrase: Some(passphrase),
+            ..Self::open(profile)
+        };
+        let existing = File::ALL
+            .into_iter()
+            .filter_map(|file| store.path(file))
+            .find(|path| path.exists());
+        if let Some(path) = existing {
+            let bytes = fs::read(&path)?;
+            if let Err(e) = store.unseal(&bytes) {
+                log::error!("failed to decrypt {}: {e}", path.display());
+                anyhow::bail!("wrong passphrase for {profile}");
+            }
+        }
+        for file in File::ALL {
+            if is_incognito() {
+                break;
+            }
+            let (Some(from), Some(to)) = (plain.path(file), store.path(file)) else {
+                continue;
+            };
+            if from.exists() && !to.exists() {
+                if let Some(bytes) = plain.read_bytes(file)? {
+                    store.write_bytes(file, bytes)?;
+                }
+                fs::remove_file(from)?;
+                log::info!("encrypted {} for {profile}", file.plain_name());
+            }
+        }
+        Ok(store)
+    }
+
+    pub fn profile(&self) -> &str {
+        &self.profile
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        self.passphrase.is_some()
+    }
+
+    /// Whether `profile` has anything encrypted yet, and so a passphrase
+    /// to check against.
+    pub fn has_encrypted_files(profile: &str) -> bool {
+        let Some(dir) = profiles_dir().map(|d| d.join(profile)) else {
+            return false;
+        };
+        File::ALL
+            .into_iter()
+            .any(|file| dir.join(file.encrypted_name()).exists())
+    }
+
+    /// Name of `file` on disk, which differs once encrypted.
+    pub fn file_name(&self, file: File) -> &'static str {
+        match self.passphrase {
+            Some(_) => file.encrypted_name(),
+            None => file.plain_name(),
+        }
+    }
+
+    fn path(&self, file: File) -> Option<PathBuf> {
+        self.dir.as_ref().map(|d| d.join(self.file_name(file)))
+    }
+
+    pub fn record_session(&self, session: &Session) -> anyhow::Result<()> {
+        self.append(File::Sessions, session)
+    }
+
+    /// Replaces the recorded session for the same book and chapter that
+    /// ended at the same time as `session`.
+    pub fn update_session(&self, session: &Session) -> anyhow::Result<()> {
+        let mut sessions = self.sessions()?;
+        let Some(recorded) = sessions.iter_mut().rev().find(|s| {
+            s.timestamp == session.timestamp
+                && s.book == session.book
+                && s.chapter == session.chapter
+        }) else {
+            anyhow::bail!("session isn't in the log");
+        };
+        *recorded = session.clone();
+        self.write_sessions(&sessions)
+    }
+
+    /// Every recorded session, oldest first.
+    pub fn sessions(&self) -> anyhow::Result<Vec<Session>> {
+        self.read(File::Sessions)
+    }
+
+    /// Replaces the session log with `sessions`.
+    pub fn write_sessions(&self, sessions: &[Session]) -> anyhow::Result<()> {
+        self.write(File::Sessions, sessions)
+    }
+
+    /// Every imported result, oldest first.
+    pub fn imported(&self) -> anyhow::Result<Vec<Sample>> {
+        self.read(File::Imported)
+    }
+
+    /// Adds imported results, skipping any already imported. Returns how
+    /// many were new.
+    pub fn record_imported(&self, samples: &[Sample]) -> anyhow::Result<usize> {
+        let mut imported = self.imported()?;
+        let before = imported.len();
+        for sample in samples {
+            if !imported.contains(sample) {
+                imported.push(sample.clone());
+            }
+        }
+        let added = imported.len() - before;
+        if added > 0 {
+            imported.sort_by_key(|s| s.timestamp);
+            self.write(File::Imported, &imported)?;
+        }
+        Ok(added)
+    }
+
+    /// Where the replay of the session that ended at `timestamp` is kept.
+    pub fn replay_path(&self, timestamp: u64) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join("replays").join(format!("{timestamp}.json")))
+    }
+
+    /// Saves a session's replay. Replays are for sharing, so they're always
+    /// plaintext, and encrypted profiles don't keep them.
+    pub fn record_replay(&self, timestamp: u64, replay: &Replay) -> anyhow::Result<()> {
+        if is_incognito() || self.is_encrypted() || replay.events.is_empty() {
+            return Ok(());
+        }
+        match self.replay_path(timestamp) {
+            Some(path) => replay.save(&path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_warmup(&self, warmup: &Warmup) -> anyhow::Result<()> {
+        self.append(File::Warmups, warmup)
+    }
+
+    pub fn coverage(&self) -> anyhow::Result<Coverage> {
+        match self.read_bytes(File::Coverage)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(Coverage::default()),
+        }
+    }
+
+    pub fn write_coverage(&self, coverage: &Coverage) -> anyhow::Result<()> {
+        self.write_bytes(File::Coverage, serde_json::to_vec(coverage)?)
+    }
+
+    pub fn shelf(&self) -> anyhow::Result<Shelf> {
+        match self.read_bytes(File::Shelf)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(Shelf::default()),
+        }
+    }
+
+    pub fn write_shelf(&self, shelf: &Shelf) -> anyhow::Result<()> {
+        self.write_bytes(File::Shelf, serde_json::to_vec(shelf)?)
+    }
+
+    pub fn reviews(&self) -> anyhow::Result<Reviews> {
+        match self.read_bytes(File::Reviews)? {
+            Some(bytes) => Ok(serde_json::from_slice(&bytes)?),
+            None => Ok(Reviews::default()),
+        }
+    }
+
+    pub fn write_reviews(&self, reviews: &Reviews) -> anyhow::Result<()> {
+        self.write_bytes(File::Reviews, serde_json::to_vec(reviews)?)
+    }
+
+    /// Brings the profile's files up to date if an older version of ept
+    /// wrote them, keeping the originals alongside with the old format
+    /// number appended to their names. Fails if a newer version wrote them.
+    pub fn migrate(&self) -> anyhow::Result<()> {
+        let Some(dir) = self.dir.as_ref().filter(|d| d.exists()) else {
+            return Ok(());
+        };
+        let format_path = dir.join("format");
+        let version = match fs::read_to_string(&format_path) {
+            Ok(s) => s.trim().parse()?,
+            Err(e) if e.kind() == ErrorKind::NotFound => 1,
+            Err(e) => return Err(e.into()),
+        };
+        if version < FORMAT {
+            anyhow::ensure!(
+                !is_incognito(),
+                "{} needs upgrading first; open it once without --incognito",
+                self.profile
+            );
+        }
+
+        let mut files = Vec::new();
+        if version < FORMAT {
+            for file in File::ALL {
+                let Some(bytes) = self.read_bytes(file)? else {
+                    continue;
+                };
+                let values: Vec<serde_json::Value> = match file.is_document() {
+                    true => vec![serde_json::from_slice(&bytes)?],
+                    false => parse_lines(std::str::from_utf8(&bytes)?),
+                };
+                files.push((file, values));
+            }
+        }
+        migrate::upgrade(version, STEPS, &self.profile, |step| {
+            for (file, values) in &mut files {
+                for value in values {
+                    step(*file, value)?;
+                }
+            }
+            Ok(())
+        })?;
+        for (file, values) in &files {
+            if let Some(path) = self.path(*file) {
+                let mut backup = path.clone().into_os_string();
+                backup.push(format!(".v{version}"));
+                fs::copy(path, backup)?;
+            }
+            match file.is_document() {
+                true => self.write_bytes(*file, serde_json::to_vec(&values[0])?)?,
+                false => self.write(*file, values)?,
+            }
+            log::info!(
+                "upgraded {} for {} to format {FORMAT}",
+                file.plain_name(),
+                self.profile
+            );
+        }
+
+        if version < FORMAT || (!format_path.exists() && !is_incognito()) {
+            fs::write(format_path, format!("{FORMAT}\n"))?;
+        }
+        Ok(())
+    }
+
+    /// What's kept for each book, by book key.
+    pub fn usage(&self) -> anyhow::Result<BTreeMap<String, BookUsage>> {
+        let mut books: BTreeMap<String, BookUsage> = self
+            .coverage()?
+            .books
+            .into_keys()
+            .map(|key| (key, BookUsage::default()))
+            .collect();
+        for session in self.sessions()? {
+            let usage = books.entry(session.book).or_default();
+            usage.sessions += 1;
+            usage.last_typed = usage.last_typed.max(Some(session.timestamp));
+            let replay = self.replay_path(session.timestamp);
+            if let Some(Ok(meta)) = replay.map(fs::metadata) {
+                usage.replays += 1;
+                usage.replay_bytes += meta.len();
+            }
+        }
+        Ok(books)
+    }
+
+    /// Deletes the sessions, replays, coverage and reviews of the book with
+    /// `key`, and takes it off the shelf. Returns how many sessions there
+    /// were.
+    pub fn delete_book(&self, key: &str) -> anyhow::Result<usize> {
+        let (deleted, kept): (Vec<Session>, Vec<Session>) =
+            self.sessions()?.into_iter().partition(|s| s.book == key);
+        for session in &deleted {
+            self.remove_replay(session.timestamp)?;
+        }
+        self.write_sessions(&kept)?;
+        let mut coverage = self.coverage()?;
+        if coverage.books.remove(key).is_some() {
+            self.write_coverage(&coverage)?;
+        }
+        let mut shelf = self.shelf()?;
+        if shelf.remove(key) {
+            self.write_shelf(&shelf)?;
+        }
+        let mut reviews = self.reviews()?;
+        if reviews.remove_book(key) {
+            self.write_reviews(&reviews)?;
+        }
+        Ok(deleted.len())
+    }
+
+    /// Deletes everything kept for the profile.
+    pub fn delete_all(&self) -> anyhow::Result<()> {
+        match &self.dir {
+            Some(dir) if !is_incognito() && dir.exists() => Ok(fs::remove_dir_all(dir)?),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drops sessions where nothing was typed, and replays whose session is
+    /// no longer in the log. Returns how many of each went.
+    pub fn prune(&self) -> anyhow::Result<(usize, usize)> {
+        let (empty, sessions): (Vec<Session>, Vec<Session>) = self
+            .sessions()?
+            .into_iter()
+            .partition(|s| s.keystrokes == 0);
+        if !empty.is_empty() {
+            self.write_sessions(&sessions)?;
+        }
+        let mut orphans = 0;
+        let replays = self.dir.as_ref().map(|d| d.join("replays"));
+        let entries = replays.and_then(|d| fs::read_dir(d).ok());
+        for entry in entries.into_iter().flatten().flatten() {
+            let path = entry.path();
+            let timestamp = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| s.parse::<u64>().ok());
+            if timestamp.is_some_and(|t| !sessions.iter().any(|s| s.timestamp == t)) {
+                self.remove(&path)?;
+                orphans += 1;
+            }
+        }
+        Ok((empty.len(), orphans))
+    }
+
+    /// Rewrites the profile's logs without lines that no longer parse or
+    /// repeated sessions, and removes files left by interrupted writes.
+    pub fn vacuum(&self) -> anyhow::Result<()> {
+        if self.read_bytes(File::Sessions)?.is_some() {
+            self.write_sessions(&merge_sessions(&self.sessions()?, &[]))?;
+        }
+        for file in [File::Imported, File::Warmups] {
+            if self.read_bytes(file)?.is_some() {
+                let lines: Vec<serde_json::Value> = self.read(file)?;
+                self.write(file, &lines)?;
+            }
+        }
+        for file in File::ALL {
+            if let Some(tmp) = self.path(file).map(|p| p.with_extension("tmp")) {
+                if tmp.exists() {
+                    self.remove(&tmp)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn remove_replay(&self, timestamp: u64) -> anyhow::Result<()> {
+        match self.replay_path(timestamp) {
+            Some(path) if path.exists() => self.remove(&path),
+            _ => Ok(()),
+        }
+    }
+
+    fn remove(&self, path: &Path) -> anyhow::Result<()> {
+        if !is_incognito() {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    fn append<T: Serialize>(&self, file: File, item: &T) -> anyhow::Result<()> {
+        if is_incognito() {
+            return Ok(());
+        }
+        if self.is_encrypted() {
+            // Can't append to ciphertext; rewrite the whole log instead.
+            let mut lines: Vec<serde_json::Value> = self.read(file)?;
+            lines.push(serde_json::to_value(item)?);
+            return self.write(file, &lines);
+        }
+        let Some(path) = self.path(file) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut out = OpenOptions::new().create(true).append(true).open(path)?;
+        let mut line = serde_json::to_string(item)?;
+        line.push('\n');
+        out.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    fn read<T: DeserializeOwned>(&self, file: File) -> anyhow::Result<Vec<T>> {
+        match self.read_bytes(file)? {
+            Some(bytes) => Ok(parse_lines(std::str::from_utf8(&bytes)?)),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    fn write<T: Serialize>(&self, file: File, lines: &[T]) -> anyhow::Result<()> {
+        self.write_bytes(file, encode_lines(lines)?.into_bytes())
+    }
+
+    /// Decrypted contents of `file`, or `None` if there isn't one yet.
+    fn read_bytes(&self, file: File) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.is_encrypted()
+            && self
+                .dir
+                .as_ref()
+                .is_some_and(|d| d.join(file.encrypted_name()).exists())
+        {
+            anyhow::bail!("{} for {} is encrypted", file.plain_name(), self.profile);
+        }
+        let Some(path) = self.path(file) else {
+            return Ok(None);
+        };
+        match fs::read(&path) {
+            Ok(bytes) => Ok(Some(self.unseal(&bytes)?)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_bytes(&self, file: File, plaintext: Vec<u8>) -> anyhow::Result<()> {
+        if is_incognito() {
+            return Ok(());
+        }
+        let Some(path) = self.path(file) else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write then rename, so a crash can't leave half a file behind.
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, self.seal(plaintext)?)?;
+        fs::rename(tmp, path)?;
+        Ok(())
+    }
+
+    fn seal(&self, plaintext: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+        match &self.passphrase {
+            Some(passphrase) => crypt::encrypt(passphrase, &plaintext),
+            None => Ok(plaintext),
+        }
+    }
+
+    fn unseal(&self, bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match &self.passphrase {
+            Some(passphrase) => crypt::decrypt(passphrase, bytes),
+            None => Ok(bytes.to_vec()),
+        }
+    }
+
+    /// Serializes a log as it's stored on disk.
+    pub fn encode<T: Serialize>(&self, lines: &[T]) -> anyhow::Result<Vec<u8>> {
+        self.seal(encode_lines(lines)?.into_bytes())
+    }
+
+    pub fn decode<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<Vec<T>> {
+        Ok(parse_lines(std::str::from_utf8(&self.unseal(bytes)?)?))
+    }
+
+    /// [`encode`](Self::encode) for a single JSON document.
+    pub fn encode_document<T: Serialize>(&self, document: &T) -> anyhow::Result<Vec<u8>> {
+        self.seal(serde_json::to_vec(document)?)
+    }
+
+    pub fn decode_document<T: DeserializeOwned>(&self, bytes: &[u8]) -> anyhow::Result<T> {
+        Ok(serde_json::from_slice(&self.unseal(bytes)?)?)
+    }
+}
+
+/// Parses a JSON lines log. Lines that fail to parse are skipped rather than
+/// losing the rest of the log.
+fn parse_lines<T: DeserializeOwned>(log: &str) -> Vec<T> {
+    log.lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                log::warn!("skipping bad log line: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+fn encode_lines<T: Serialize>(lines: &[T]) -> anyhow::Result<String> {
+    let mut log = String::new();
+    for line in lines {
+        log.push_str(&serde_json::to_string(line)?);
+        log.push('\n');
+    }
+    Ok(log)
+}
+
+/// Union of two session logs, ordered by timestamp. A session is the one
+/// for its book and chapter that ended at its time, so copies of it that
+/// differ only because one was tagged since are kept once, tagged. Otherwise
+/// the copy in `a` is kept.
+pub fn merge_sessions(a: &[Session], b: &[Session]) -> Vec<Session> {
+    let mut merged: Vec<Session> = a.iter().chain(b).cloned().collect();
+    // Stable, so `a`'s copy of a session comes first.
+    merged.sort_by_key(|s| s.timestamp);
+    let mut unique: Vec<Session> = Vec::with_capacity(merged.len());
+    for session in merged {
+        // Copies share a timestamp, so only that run needs checking.
+        let kept = unique
+            .iter_mut()
+            .rev()
+            .take_while(|s| s.timestamp == session.timestamp)
+            .find(|s| s.book == session.book && s.chapter == session.chapter);
+        match kept {
+            Some(kept) if kept.tags.is_empty() && !session.tags.is_empty() => *kept = session,
+            Some(_) => {}
+            None => unique.push(session),
+        }
+    }
+    unique
+}
+
+fn profiles_dir() -> Option<PathBuf> {
+    paths::data_dir().map(|d| d.join("profiles"))
+}
+
+/// Names of every profile with stored data.
+pub fn profiles() -> Vec<String> {
+    let Some(entries) = profiles_dir().and_then(|d| fs::read_dir(d).ok()) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| e.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+/// Whether `name` is usable as a profile, which ends up as a directory name.
+pub fn is_valid_profile(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Identifies a book across sessions and profiles.
+pub fn book_key(book: &Epub) -> String {
+    match book.author() {
+        Some(author) => format!("{} / {}", author, book.title()),
+        None => book.title().to_owned(),
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn session(timestamp: u64, chars: usize) -> Session {
+        Session {
+            book: "book".to_owned(),
+            chapter: 0,
+            timestamp,
+            chars,
+            elapsed_ms: 1000,
+            keystrokes: chars,
+            mistakes: 0,
+            uncorrected: 0,
+            finished: false,
+            difficulty: None,
+            classes: Default::default(),
+            shift: Default::default(),
+            bigrams: Default::default(),
+            intervals: Default::default(),
+            tags: Vec::new(),
+            keyboard: None,
+            pastes: 0,
+            forgiven: 0,
+            paragraphs: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn merge_keeps_both_sides_once() {
+        let other_chapter = Session {
+            chapter: 1,
+            ..session(3, 31)
+        };
+        let local = [session(1, 10), session(3, 30)];
+        let remote = [session(1, 10), session(2, 20), other_chapter];
+        let merged = merge_sessions(&local, &remote);
+        let chars: Vec<_> = merged.iter().map(|s| s.chars).collect();
+        assert_eq!(chars, [10, 20, 30, 31]);
+    }
+
+    #[test]
+    fn merge_keeps_the_tagged_copy_of_a_session() {
+        let tagged = Session {
+            tags: vec!["tired".to_owned()],
+            ..session(1, 10)
+        };
+        for (a, b) in [(&tagged, &session(1, 10)), (&session(1, 10), &tagged)] {
+            let merged = merge_sessions(std::slice::from_ref(a), std::slice::from_ref(b));
+            assert_eq!(merged, std::slice::from_ref(&tagged));
+        }
+    }
+}
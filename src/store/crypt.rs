@@ -0,0 +1,25 @@
+//! Passphrase encryption of stored files, in the age format, so they can
+//! also be opened with the `age` command line tool.
+
+use std::io::{Read, Write};
+
+use age::secrecy::Secret;
+
+pub fn encrypt(passphrase: &str, plaintext: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let encryptor = age::Encryptor::with_user_passphrase(Secret::new(passphrase.to_owned()));
+    let mut ciphertext = Vec::new();
+    let mut writer = encryptor.wrap_output(&mut ciphertext)?;
+    writer.write_all(plaintext)?;
+    writer.finish()?;
+    Ok(ciphertext)
+}
+
+pub fn decrypt(passphrase: &str, ciphertext: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let age::Decryptor::Passphrase(decryptor) = age::Decryptor::new(ciphertext)? else {
+        anyhow::bail!("file is not passphrase-encrypted");
+    };
+    let mut reader = decryptor.decrypt(&Secret::new(passphrase.to_owned()), None)?;
+    let mut plaintext = Vec::new();
+    reader.read_to_end(&mut plaintext)?;
+    Ok(plaintext)
+}
@@ -1,5 +1,3 @@
-use std::marker::PhantomData;
-
 bitflags::bitflags! {
     #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
     pub struct Style: u8 {
@@ -8,10 +6,18 @@ bitflags::bitflags! {
     }
 }
 
+/// Styles over positions of type `T`, stored as the points where the style
+/// changes.
+///
+/// Ranges are half-open, so a range ending where another starts leaves no
+/// gap or overlap between them, and empty ranges style nothing. Overlapping
+/// ranges combine: a position is styled with every style any range covering
+/// it has.
 #[derive(Debug)]
 pub struct Styling<T> {
-    starts: Vec<Range<Start, T>>,
-    ends: Vec<Range<End, T>>,
+    /// Where each style starts, in increasing order. Text before the first
+    /// is unstyled, and no two in a row have the same style.
+    runs: Vec<(T, Style)>,
 }
 
 impl<T> Styling<T> {
@@ -24,51 +30,70 @@ impl<T> Styling<T>
 where
     T: std::ops::AddAssign + std::ops::SubAssign + Ord + Copy,
 {
+    /// Adds the styles of `other`, moved along by `offset`.
     pub fn add_from_disjoint_other(&mut self, mut other: Self, offset: T) {
-        other.offset(offset);
-        let Self { starts, ends } = other;
-        self.starts.extend(starts);
-        self.ends.extend(ends);
-    }
-
-    fn offset(&mut self, offset: T) {
-        for thing in self
-            .starts
-            .iter_mut()
-            .flat_map(|s| [&mut s.range.start, &mut s.range.end])
-            .chain(
-                self.ends
-                    .iter_mut()
-                    .flat_map(|s| [&mut s.range.start, &mut s.range.end]),
-            )
-        {
-            *thing += offset;
+        for (at, _) in &mut other.runs {
+            *at += offset;
         }
+        self.runs = union(&self.runs, &other.runs);
     }
 
+    /// Moves styles after `i` along for `removed` positions from `i` having
+    /// been replaced by `added` ones. Style changes inside the removed
+    /// positions are moved to `i`.
     pub fn offset_after(&mut self, i: T, removed: T, added: T) {
-        for [start, end] in self
-            .starts
-            .iter_mut()
-            .map(|s| [&mut s.range.start, &mut s.range.end])
-            .chain(
-                self.ends
-                    .iter_mut()
-                    .map(|s| [&mut s.range.start, &mut s.range.end]),
-            )
-        {
-            if *start > i {
-                *start -= removed;
-                *start += added;
+        let mut removed_end = i;
+        removed_end += removed;
+        for (at, _) in &mut self.runs {
+            if *at >= removed_end {
+                *at -= removed;
+                *at += added;
+            } else if *at > i {
+                *at = i;
             }
-            if *end > i {
-                *end -= removed;
-                *end += added;
+        }
+        // Changes moved to `i` can land on each other; the last one wins.
+        let runs = std::mem::take(&mut self.runs);
+        for (at, style) in runs {
+            if self.runs.last().is_some_and(|&(last, _)| last == at) {
+                self.runs.pop();
             }
+            push_run(&mut self.runs, at, style);
         }
     }
 }
 
+/// Pushes a change to `style` at `at`, unless the style is already `style`.
+fn push_run<T>(runs: &mut Vec<(T, Style)>, at: T, style: Style) {
+    let current = runs.last().map_or(Style::empty(), |&(_, s)| s);
+    if style != current {
+        runs.push((at, style));
+    }
+}
+
+/// Runs styled with the styles of both `a` and `b`.
+fn union<T: Ord + Copy>(a: &[(T, Style)], b: &[(T, Style)]) -> Vec<(T, Style)> {
+    let mut runs = Vec::with_capacity(a.len() + b.len());
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    let (mut a_style, mut b_style) = (Style::empty(), Style::empty());
+    loop {
+        let at = match (a.peek(), b.peek()) {
+            (Some(&&(x, _)), Some(&&(y, _))) => x.min(y),
+            (Some(&&(x, _)), None) => x,
+            (None, Some(&&(y, _))) => y,
+            (None, None) => break,
+        };
+        if let Some(&(_, style)) = a.next_if(|&&(x, _)| x == at) {
+            a_style = style;
+        }
+        if let Some(&(_, style)) = b.next_if(|&&(y, _)| y == at) {
+            b_style = style;
+        }
+        push_run(&mut runs, at, a_style | b_style);
+    }
+    runs
+}
+
 pub struct Builder<T> {
     styles: Vec<(Style, std::ops::Range<T>)>,
 }
@@ -77,72 +102,75 @@ impl<T> Builder<T>
 where
     T: Ord + Copy,
 {
+    /// Styles `r` with `s`. Empty ranges are ignored.
     pub fn add(&mut self, s: Style, r: std::ops::Range<T>) -> &mut Self {
-        'outer: for style in s.iter() {
-            for (os, or) in &mut self.styles {
-                if style == *os && r.start <= or.end && r.end >= or.start {
-                    *or = r.start.min(or.start)..r.end.max(or.end);
-                    continue 'outer;
-                }
-            }
-
-            self.styles.push((style, r.clone()));
+        if r.start < r.end {
+            self.styles.push((s, r));
         }
         self
     }
 
     pub fn build(&mut self) -> Styling<T> {
-        fn pair<T: Clone>(
-            (style, range): &(Style, std::ops::Range<T>),
-        ) -> (Range<Start, T>, Range<End, T>) {
-            (
-                Range::new(*style, range.clone()),
-                Range::new(*style, range.clone()),
-            )
-        }
-        let (mut starts, mut ends): (Vec<_>, Vec<_>) = self.styles.iter().map(pair).unzip();
-        fn cmp<T: Ord>(a: &impl StyleRange<T>, b: &impl StyleRange<T>) -> std::cmp::Ordering {
-            a.idx().cmp(&b.idx()).then(a.style().cmp(&b.style()))
+        // Each style flag is counted separately, so that one range ending
+        // doesn't end the style of another overlapping it.
+        let mut events: Vec<(T, Style, bool)> = self
+            .styles
+            .iter()
+            .flat_map(|(style, range)| [(range.start, *style, true), (range.end, *style, false)])
+            .collect();
+        events.sort_unstable_by_key(|&(at, ..)| at);
+
+        let mut counts = [0usize; Style::all().bits().count_ones() as usize];
+        let mut runs = Vec::new();
+        let mut events = events.into_iter().peekable();
+        while let Some(&(at, ..)) = events.peek() {
+            while let Some((_, style, starts)) = events.next_if(|&(x, ..)| x == at) {
+                for flag in style.iter() {
+                    let count = &mut counts[flag.bits().trailing_zeros() as usize];
+                    if starts {
+                        *count += 1;
+                    } else {
+                        *count -= 1;
+                    }
+                }
+            }
+            let style = Style::all()
+                .iter()
+                .filter(|flag| counts[flag.bits().trailing_zeros() as usize] > 0)
+                .fold(Style::empty(), |style, flag| style | flag);
+            push_run(&mut runs, at, style);
         }
-        starts.sort_unstable_by(cmp);
-        ends.sort_unstable_by(cmp);
-        Styling { starts, ends }
+        Styling { runs }
     }
 }
 
 impl<T: Ord + Copy> Styling<T> {
+    /// Runs of same-styled positions from `start` up to `end`, as each one's
+    /// style and length. The runs cover the whole of `start..end`, crossing
+    /// blocks of text freely, and no two in a row have the same style.
+    /// Nothing is returned for an empty range.
     pub fn iter(&self, start: T, end: T) -> StylingIter<'_, T> {
         assert!(end >= start);
-        let end_idx = self.ends.partition_point(|s| s.range.end <= start);
-        let start_idx = self
-            .ends
-            .get(end_idx)
-            .map(|e| (e.range.start, e.style))
-            .and_then(|e| {
-                self.starts
-                    .binary_search_by_key(&e, |s| (s.range.start, s.style))
-                    .ok()
-            })
-            .unwrap_or(end_idx);
-
+        let next = self.runs.partition_point(|&(at, _)| at <= start);
+        let style = next
+            .checked_sub(1)
+            .map_or(Style::empty(), |i| self.runs[i].1);
         StylingIter {
-            starts: self.starts[start_idx..].iter().peekable(),
-            ends: self.ends[end_idx..].iter().peekable(),
-            style: Style::empty(),
+            runs: &self.runs[next..],
+            style,
             idx: start,
             end,
-            ended: start == end,
         }
     }
 }
 
 pub struct StylingIter<'a, T> {
-    starts: std::iter::Peekable<std::slice::Iter<'a, Range<Start, T>>>,
-    ends: std::iter::Peekable<std::slice::Iter<'a, Range<End, T>>>,
+    /// Style changes after `idx`.
+    runs: &'a [(T, Style)],
+    /// Style at `idx`.
     style: Style,
     idx: T,
     end: T,
-    ended: bool,
 }
 
 impl<T> Iterator for StylingIter<'_, T>
@@ -152,107 +180,20 @@ where
     type Item = (Style, <T as std::ops::Sub>::Output);
 
     fn next(&mut self) -> Option<Self::Item> {
-        fn apply_prior_styles<T: Ord>(
-            mut style: Style,
-            it: &mut std::iter::Peekable<std::slice::Iter<'_, impl StyleRange<T>>>,
-            idx: T,
-        ) -> Style {
-            while let Some(peek) = it.peek() {
-                if peek.idx() <= idx {
-                    style = peek.apply(style);
-                } else {
-                    break;
-                }
-                let _ = it.next();
-            }
-            style
-        }
-
-        if self.ended {
+        if self.idx >= self.end {
             return None;
         }
-
-        self.style = apply_prior_styles(self.style, &mut self.ends, self.idx);
-        self.style = apply_prior_styles(self.style, &mut self.starts, self.idx);
-
-        let mut next_pos = match (self.starts.peek(), self.ends.peek()) {
-            (Some(s), Some(e)) => s.range.start.min(e.range.end),
-            (None, Some(e)) => e.range.end,
-            (Some(s), None) => s.range.start,
-            _ => {
-                self.ended = true;
-                self.end
+        let (to, next_style) = match self.runs.split_first() {
+            Some((&(at, style), rest)) if at < self.end => {
+                self.runs = rest;
+                (at, style)
             }
+            _ => (self.end, self.style),
         };
-
-        if next_pos <= self.idx {
-            self.ended = true;
-            return None;
-        } else if next_pos >= self.end {
-            self.ended = true;
-            next_pos = self.end;
-        }
-
-        let len = next_pos - self.idx;
-        self.idx = next_pos;
-        Some((self.style, len))
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Range<Marker, T> {
-    style: Style,
-    range: std::ops::Range<T>,
-    phantom: PhantomData<Marker>,
-}
-
-impl<Marker, T> Range<Marker, T> {
-    fn new(style: Style, range: std::ops::Range<T>) -> Self {
-        Self {
-            style,
-            range,
-            phantom: PhantomData,
-        }
-    }
-}
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct Start;
-
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct End;
-
-trait StyleRange<T> {
-    fn idx(&self) -> T;
-    fn style(&self) -> Style;
-    fn apply(&self, style: Style) -> Style;
-}
-
-impl<T: Copy> StyleRange<T> for Range<Start, T> {
-    fn idx(&self) -> T {
-        self.range.start
-    }
-
-    fn style(&self) -> Style {
-        self.style
-    }
-
-    fn apply(&self, style: Style) -> Style {
-        style | self.style
-    }
-}
-
-impl<T: Copy> StyleRange<T> for Range<End, T> {
-    fn idx(&self) -> T {
-        self.range.end
-    }
-
-    fn style(&self) -> Style {
-        self.style
-    }
-
-    fn apply(&self, style: Style) -> Style {
-        style & !self.style
+        let item = (self.style, to - self.idx);
+        self.idx = to;
+        self.style = next_style;
+        Some(item)
     }
 }
 
@@ -284,19 +225,16 @@ mod test {
     #[test]
     fn it_decomposes_style_flags() {
         let styles = Styling::builder()
-            .add(Style::ITALIC | Style::BOLD, 0..1)
+            .add(Style::ITALIC | Style::BOLD, 0..2)
+            .add(Style::BOLD, 1..3)
             .build();
 
         let expected = &[
-            Range::new(Style::ITALIC, 0..1),
-            Range::new(Style::BOLD, 0..1),
+            (0, Style::ITALIC | Style::BOLD),
+            (2, Style::BOLD),
+            (3, Style::empty()),
         ];
-        assert_eq!(&styles.starts[..], expected);
-        let expected = &[
-            Range::new(Style::ITALIC, 0..1),
-            Range::new(Style::BOLD, 0..1),
-        ];
-        assert_eq!(&styles.ends[..], expected);
+        assert_eq!(&styles.runs[..], expected);
     }
 
     #[test]
@@ -305,12 +243,12 @@ mod test {
             .add(Style::ITALIC, 0..2)
             .add(Style::ITALIC, 2..4)
             .add(Style::ITALIC, 3..5)
+            .add(Style::ITALIC, 7..9)
+            .add(Style::ITALIC, 4..8)
             .build();
 
-        let expected = &[Range::new(Style::ITALIC, 0..5)];
-        assert_eq!(&styles.starts[..], expected);
-        let expected = &[Range::new(Style::ITALIC, 0..5)];
-        assert_eq!(&styles.ends[..], expected);
+        let expected = &[(0, Style::ITALIC), (9, Style::empty())];
+        assert_eq!(&styles.runs[..], expected);
     }
 
     #[test]
@@ -324,4 +262,73 @@ mod test {
         let styles = Styling::<usize>::builder().build();
         assert_eq!(styles.iter(0, 0).next(), None);
     }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn it_ignores_empty_ranges() {
+        let styles = Styling::builder()
+            .add(Style::BOLD, 3..3)
+            .add(Style::ITALIC, 5..2)
+            .build();
+        assert_eq!(styles.iter(0, 6).collect::<Vec<_>>(), [(Style::empty(), 6)]);
+    }
+
+    #[test]
+    fn it_keeps_styles_that_started_before_the_query() {
+        // Bold starts first and outlasts italic, which ends inside the query.
+        let styles = Styling::builder()
+            .add(Style::BOLD, 0..10)
+            .add(Style::ITALIC, 2..6)
+            .build();
+        assert_eq!(
+            styles.iter(5, 8).collect::<Vec<_>>(),
+            [(Style::ITALIC | Style::BOLD, 1), (Style::BOLD, 2)]
+        );
+        assert_eq!(styles.iter(6, 6).next(), None);
+        assert_eq!(
+            styles.iter(10, 12).collect::<Vec<_>>(),
+            [(Style::empty(), 2)]
+        );
+    }
+
+    #[test]
+    fn it_spans_blocks() {
+        // Two italic paragraphs with a line break between them.
+        let styles = Styling::builder()
+            .add(Style::ITALIC, 0usize..4)
+            .add(Style::ITALIC, 5..9)
+            .build();
+        assert_eq!(
+            styles.iter(2, 7).collect::<Vec<_>>(),
+            [(Style::ITALIC, 2), (Style::empty(), 1), (Style::ITALIC, 2)]
+        );
+        let covered: usize = styles.iter(0, 20).map(|(_, len)| len).sum();
+        assert_eq!(covered, 20);
+    }
+
+    #[test]
+    fn it_moves_styles_around_edits() {
+        let mut styles = Styling::builder()
+            .add(Style::BOLD, 2..4)
+            .add(Style::ITALIC, 6..8)
+            .build();
+        // Replace 3..7 with a single position.
+        styles.offset_after(3, 4, 1);
+        assert_eq!(
+            &styles.runs[..],
+            &[(2, Style::BOLD), (3, Style::ITALIC), (5, Style::empty())]
+        );
+
+        let other = Styling::builder().add(Style::BOLD, 0..2).build();
+        styles.add_from_disjoint_other(other, 5);
+        assert_eq!(
+            &styles.runs[..],
+            &[
+                (2, Style::BOLD),
+                (3, Style::ITALIC),
+                (5, Style::BOLD),
+                (7, Style::empty())
+            ]
+        );
+    }
 }
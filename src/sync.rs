@@ -0,0 +1,110 @@
+//! Keeps a profile's session log and coverage in step with copies on a
+//! server. The server only needs to answer `GET` and `PUT` on a file URL,
+//! which covers WebDAV shares and most simple upload endpoints.
+//!
+//! Stats are worked out from sessions, so they follow. The shelf and
+//! reviews are left as they are on each machine.
+
+use std::{io::Read, time::Duration};
+
+use crate::{
+    config::SyncConfig,
+    coverage::Coverage,
+    store::{self, File, Store},
+};
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Sessions that were only on the server.
+    pub pulled: usize,
+    /// Sessions that were only here.
+    pub pushed: usize,
+    /// Whether coverage typed on another machine was brought in.
+    pub progress_pulled: bool,
+}
+
+/// Merges the local and remote session logs and coverage, and writes the
+/// result to whichever side was missing something. Sessions are never
+/// dropped: logs are append-only, so a merge is their union ordered by
+/// timestamp, and coverage only ever grows. Encrypted profiles are stored
+/// encrypted on the server too.
+pub fn sync(config: &SyncConfig, store: &Store) -> anyhow::Result<Report> {
+    let url = file_url(config, store, File::Sessions);
+    let remote = match fetch(&url, config)? {
+        Some(bytes) => store.decode(&bytes)?,
+        None => Vec::new(),
+    };
+    // Either log can hold duplicates the merge drops, so counts are taken
+    // against each without them.
+    let local = store::merge_sessions(&store.sessions()?, &[]);
+    let remote = store::merge_sessions(&remote, &[]);
+    let merged = store::merge_sessions(&local, &remote);
+
+    let mut report = Report {
+        pulled: merged.len().saturating_sub(local.len()),
+        pushed: merged.len().saturating_sub(remote.len()),
+        progress_pulled: false,
+    };
+    if merged != local {
+        store.write_sessions(&merged)?;
+    }
+    if merged != remote {
+        request(ureq::put(&url), config).send_bytes(&store.encode(&merged)?)?;
+    }
+
+    let url = file_url(config, store, File::Coverage);
+    let remote: Coverage = match fetch(&url, config)? {
+        Some(bytes) => store.decode_document(&bytes)?,
+        None => Coverage::default(),
+    };
+    let local = store.coverage()?;
+    let mut merged = local.clone();
+    merged.merge(&remote);
+    if merged != local {
+        store.write_coverage(&merged)?;
+        report.progress_pulled = true;
+    }
+    if merged != remote {
+        request(ureq::put(&url), config).send_bytes(&store.encode_document(&merged)?)?;
+    }
+
+    log::info!(
+        "synced {}: pulled {}, pushed {}",
+        store.profile(),
+        report.pulled,
+        report.pushed
+    );
+    Ok(report)
+}
+
+fn file_url(config: &SyncConfig, store: &Store, file: File) -> String {
+    format!(
+        "{}/{}/{}",
+        config.url.trim_end_matches('/'),
+        store.profile(),
+        store.file_name(file)
+    )
+}
+
+/// Contents of the file at `url`, or `None` if no machine has pushed it yet.
+fn fetch(url: &str, config: &SyncConfig) -> anyhow::Result<Option<Vec<u8>>> {
+    match request(ureq::get(url), config).call() {
+        Ok(response) => {
+            let mut bytes = Vec::new();
+            response.into_reader().read_to_end(&mut bytes)?;
+            Ok(Some(bytes))
+        }
+        Err(ureq::Error::Status(404, _)) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn request(request: ureq::Request, config: &SyncConfig) -> ureq::Request {
+    let request = request.timeout(TIMEOUT);
+    match &config.token {
+        Some(token) => request.set("Authorization", &format!("Bearer {token}")),
+        None => request,
+    }
+}
@@ -1,22 +1,70 @@
 use std::{
     cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
     io::Write,
-    ops::{Bound, RangeBounds},
-    sync::Arc,
+    ops::{Bound, Range, RangeBounds, RangeInclusive},
+    sync::{
+        atomic::{self, AtomicBool},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use crossterm::{
     cursor,
-    event::{KeyCode, KeyEvent, KeyModifiers},
+    event::{self, KeyCode, KeyEvent, KeyModifiers},
     queue,
-    style::{Attribute, Color, ResetColor, SetAttribute, SetForegroundColor},
+    style::{Attribute, Color, ResetColor, SetAttribute, SetBackgroundColor, SetForegroundColor},
     terminal,
 };
 use lepu::Epub;
 
+use self::{
+    animation::{Animation, FrameClock},
+    spans::SpanBuffer,
+};
 use crate::{
-    backend::{Backend, Len},
-    style::Style,
+    analytics::Bigrams,
+    backend::{Backend, ChapterText, Len},
+    cache::ChapterCache,
+    card::{self, Card},
+    config::{Alert, Column, Config, Gutter, PaceConfig},
+    coverage::{self, BookCoverage},
+    date,
+    difficulty::Difficulty,
+    goal, gutenberg, keymap,
+    latency::Latency,
+    pace::{self, Pace, Target},
+    replay::{self, Replay},
+    review::Review,
+    shelf::Resume,
+    sidecar::Sidecar,
+    stats::{self, Metric, Session},
+    store::{self, Store},
+    style::{Style, Styling},
+    translate::{Action, InputMethod, Translator},
+    warmup,
+};
+
+mod animation;
+mod caps;
+mod celebration;
+mod drill;
+mod info;
+mod large;
+mod leaderboard;
+mod library;
+mod modal;
+mod score;
+mod search;
+mod shelf;
+mod spans;
+mod toast;
+mod vocabulary;
+
+pub use self::{
+    library::LibraryScreen,
+    shelf::{Pick, Picker, ShelfScreen},
 };
 
 /* virtual styling
@@ -31,20 +79,24 @@ range-based styling
 
 */
 
-// const PARAGRAPH_TERMINATOR: &str = "↵";
-const PARAGRAPH_TERMINATOR: &str = "¬";
-// const PARAGRAPH_TERMINATOR: &str = " ";
-
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 enum Linebreak {
+    /// The text was wrapped to fit the width.
     Wrapped,
+    /// End of a paragraph; the line break is typed, and drawn as
+    /// [`Glyphs::paragraph_end`](caps::Glyphs::paragraph_end).
     Existing,
+    /// Spacing between paragraphs, with no text of its own.
+    Blank,
     Eof,
 }
 
+/// A row of the chapter as laid out on screen, indexed by its position in
+/// [`ChapterDisplay::lines`].
 #[derive(Debug)]
 struct VirtualLine {
-    line: usize,
+    /// Number of the paragraph, counting from 1, if the line starts one.
+    paragraph: Option<usize>,
     start: Len,
     end: Len,
     separator_len: Len,
@@ -70,46 +122,254 @@ impl ScreenLine<'_> {
 enum State {
     ChapterSelect,
     Chapter(ChapterDisplay),
+    Search(search::Search),
+    Score(score::Score),
+    Leaderboard(leaderboard::Leaderboard),
+    Info(info::BookInfo),
+    Vocabulary(vocabulary::Vocabulary),
+    Celebration(celebration::Celebration),
+    Warmup(drill::Drill),
 }
 
+/// How often the status corner is brought up to date.
+const STATUS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the screen edges stay lit after a mistake.
+const FLASH_DURATION: Duration = Duration::from_millis(150);
+
+/// Recent sessions whose mistakes pick the pairs of letters to warm up on.
+const WARMUP_SESSIONS: usize = 20;
+/// Pairs of letters practiced in a warm-up.
+const WEAK_BIGRAMS: usize = 5;
+/// Most words shown on a chapter's vocabulary screen.
+const VOCABULARY_WORDS: usize = 120;
+
+/// Smallest terminal anything is drawn in; below this there's only a
+/// placeholder asking for more room.
+const MIN_SCREEN_SIZE: (u16, u16) = (24, 8);
+/// Columns kept free either side of the text for the chapter select marker
+/// and gutters.
+const SIDE_MARGIN: u16 = 2;
+
+/// Whether kitty keyboard enhancements were turned on, and need turning off
+/// on the way out.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
 struct Dimensions {
     screen_size: (u16, u16),
     anchor: (u16, u16),
+    /// Columns of text on screen.
     width: u16,
+    /// Columns the text is wrapped at, wider than `width` when the screen is
+    /// too narrow for the configured width and has to scroll sideways.
+    wrap: u16,
+    /// Leftmost column and width of the context pane, if there is one.
+    context: Option<(u16, u16)>,
+    /// Rows at the top of the screen kept for the word being typed in large
+    /// letters, if it's shown.
+    top: u16,
+}
+
+impl Dimensions {
+    fn new(config: &Config, view_width: u16, view_height: u16) -> Self {
+        let (text_area, context) = match config.context_pane {
+            true => {
+                let half = view_width / 2;
+                let context = (
+                    half + SIDE_MARGIN,
+                    view_width.saturating_sub(half + 2 * SIDE_MARGIN),
+                );
+                (half, Some(context))
+            }
+            false => (view_width, None),
+        };
+        let (left, width, wrap) = text_column(config, text_area);
+        let top = match config.large_word {
+            // With a blank row between the letters and the text.
+            true => large::height() + 1,
+            false => 0,
+        };
+        let row = top + anchor_row(config.anchor, view_height.saturating_sub(top));
+        Self {
+            screen_size: (view_width, view_height),
+            anchor: (left, row.min(view_height.saturating_sub(1))),
+            width,
+            wrap,
+            context,
+            top,
+        }
+    }
 }
 
 pub struct Display {
     dimensions: Arc<Dimensions>,
+    config: Arc<Config>,
     book: Epub,
+    cache: ChapterCache,
+    store: Store,
+    /// Sessions recorded since the display was created.
+    sessions: Vec<Session>,
+    /// Shown after the score screen when a chapter finishes the book.
+    celebration: Option<celebration::Celebration>,
+    /// TOC indices of the chapters listed in chapter select.
+    toc: Vec<usize>,
+    /// Selected entry in `toc`.
     chapter: usize,
     state: State,
+    /// Frame latencies, when instrumentation is on.
+    latency: Option<Latency>,
+    clock: FrameClock,
+    toasts: toast::Toasts,
+    /// Question being asked over the current state, and what it's for.
+    modal: Option<(modal::Modal, Asking)>,
+    /// Pace caret settings, starting from the config's but changeable from
+    /// chapter select.
+    pace: Option<PaceConfig>,
+    /// Whether the warm-up has been offered this run.
+    warmed_up: bool,
+    /// TOC indices of chapters typed to the end at the course's targets.
+    passed: BTreeSet<usize>,
+    /// How much of each chapter has been typed, loaded for chapter select
+    /// and dropped whenever a session is recorded.
+    progress: Option<BTreeMap<usize, Progress>>,
+    /// TOC index of the chapter last left partway through this run, and
+    /// where the cursor was.
+    left_off: Option<(usize, usize)>,
+}
+
+/// How much of a chapter has been typed, for chapter select.
+#[derive(Debug, Default, Clone, Copy)]
+struct Progress {
+    /// Fraction of the chapter's characters typed correctly at some point.
+    typed: f64,
+    /// When the chapter was last typed, in seconds since the Unix epoch.
+    last: Option<u64>,
+}
+
+/// What a modal's answer is needed for.
+#[derive(Debug, Clone, Copy)]
+enum Asking {
+    Quit,
+    Pace,
+    Tags,
+    Goal,
 }
 
 impl Display {
-    pub fn new(book: Epub, width: u16, view_width: u16, view_height: u16) -> Self {
-        let width = width.min(view_width);
+    pub fn new(
+        book: Epub,
+        cache: ChapterCache,
+        store: Store,
+        config: Arc<Config>,
+        view_width: u16,
+        view_height: u16,
+    ) -> Self {
+        let toc = (0..book.chapter_count())
+            .filter(|&i| {
+                !config.strip_gutenberg
+                    || book
+                        .chapter_by_toc_index(i)
+                        .is_none_or(|c| !gutenberg::is_boilerplate_title(c.name()))
+            })
+            .collect();
+
+        let passed = match &config.course {
+            Some(target) => {
+                let key = store::book_key(&book);
+                let sessions = store.sessions().unwrap_or_default();
+                sessions
+                    .iter()
+                    .filter(|s| s.book == key && target.is_met_by(s))
+                    .map(|s| s.chapter)
+                    .collect()
+            }
+            None => BTreeSet::new(),
+        };
 
-        Self {
+        let mut display = Self {
             state: State::ChapterSelect,
             book,
+            cache,
+            store,
+            sessions: Vec::new(),
+            celebration: None,
+            toc,
             chapter: 0,
-            dimensions: Arc::new(Dimensions {
-                screen_size: (view_width, view_height),
-                anchor: (view_width / 2 - width / 2, view_height / 2),
-                width,
-            }),
+            latency: None,
+            clock: FrameClock::new(config.max_fps),
+            toasts: toast::Toasts::default(),
+            modal: None,
+            pace: config.pace.clone(),
+            warmed_up: false,
+            passed,
+            progress: None,
+            left_off: None,
+            dimensions: Arc::new(Dimensions::new(&config, view_width, view_height)),
+            config,
+        };
+        display.select_where_left_off();
+        display
+    }
+
+    /// Position in chapter select of the earliest chapter not yet typed to
+    /// the end.
+    fn first_unfinished(&mut self) -> Option<usize> {
+        let toc = self.toc.clone();
+        let progress = self.progress();
+        toc.iter()
+            .position(|i| progress.get(i).is_none_or(|p| p.typed < 1.0))
+    }
+
+    /// Selects the first unfinished chapter, if some of the book has been
+    /// typed before.
+    fn select_where_left_off(&mut self) {
+        if self.progress().is_empty() {
+            return;
+        }
+        if let Some(i) = self.first_unfinished() {
+            self.chapter = i;
         }
     }
 
+    /// Whether chapter select has nothing to list, once front and back matter
+    /// and anything the sidecar leaves out are gone.
+    pub fn is_empty(&self) -> bool {
+        self.toc.is_empty()
+    }
+
     pub fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
-        queue!(w, terminal::EnterAlternateScreen, cursor::Hide)?;
+        Self::enter_screen(w)?;
+        self.full_render(w)?;
+        Ok(())
+    }
+
+    fn enter_screen(w: &mut impl Write) -> anyhow::Result<()> {
+        queue!(
+            w,
+            terminal::EnterAlternateScreen,
+            cursor::Hide,
+            event::EnableFocusChange,
+            event::EnableBracketedPaste
+        )?;
         terminal::enable_raw_mode()?;
+        log::info!("terminal capabilities: {:?}", caps::get());
+        // Asking the terminal reads its reply from stdin, so this has to
+        // happen before the input thread starts.
+        if terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            queue!(
+                w,
+                event::PushKeyboardEnhancementFlags(
+                    event::KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | event::KeyboardEnhancementFlags::REPORT_EVENT_TYPES
+                )
+            )?;
+            KEYBOARD_ENHANCED.store(true, atomic::Ordering::Relaxed);
+        }
         let hook = std::panic::take_hook();
         std::panic::set_hook(Box::new(move |info: &std::panic::PanicInfo| {
             let _ = Self::cleanup(&mut std::io::stdout());
             hook(info);
         }));
-        self.full_render(w)?;
         Ok(())
     }
 
@@ -120,119 +380,325 @@ impl Display {
     }
 
     fn cleanup(w: &mut impl Write) -> anyhow::Result<()> {
+        if KEYBOARD_ENHANCED.swap(false, atomic::Ordering::Relaxed) {
+            queue!(w, event::PopKeyboardEnhancementFlags)?;
+        }
         terminal::disable_raw_mode()?;
-        queue!(w, ResetColor, cursor::Show, terminal::LeaveAlternateScreen)?;
+        queue!(
+            w,
+            event::DisableFocusChange,
+            event::DisableBracketedPaste,
+            ResetColor,
+            cursor::Show,
+            terminal::LeaveAlternateScreen
+        )?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Starts measuring frame latency and showing it in the corner.
+    pub fn instrument(&mut self) {
+        self.latency = Some(Latency::default());
+    }
+
+    /// Records a frame caused by a key event received at `received`, once
+    /// it's been flushed, and updates the overlay.
+    pub fn record_frame(&mut self, w: &mut impl Write, received: Instant) -> anyhow::Result<()> {
+        let Some(latency) = &mut self.latency else {
+            return Ok(());
+        };
+        latency.record(received.elapsed());
+        queue!(
+            w,
+            cursor::SavePosition,
+            cursor::MoveTo(0, 0),
+            SetAttribute(Attribute::Dim),
+        )?;
+        write!(w, "{}", latency.summary())?;
+        queue!(w, SetAttribute(Attribute::Reset), cursor::RestorePosition)?;
         w.flush()?;
         Ok(())
     }
 
     pub fn render(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        if self.is_too_small() {
+            return self.render_too_small(w);
+        }
+        if self.modal.is_some() {
+            self.full_render(w)?;
+            self.toasts.render(w, self.dimensions.screen_size)?;
+            return Ok(());
+        }
         match &mut self.state {
-            State::ChapterSelect { .. } => self.full_render(w)?,
+            State::ChapterSelect { .. }
+            | State::Search(..)
+            | State::Score(..)
+            | State::Leaderboard(..)
+            | State::Info(..)
+            | State::Vocabulary(..)
+            | State::Celebration(..)
+            | State::Warmup(..) => self.full_render(w)?,
             State::Chapter(display) => {
+                let toasts = self.toasts.drawn();
+                let scroll = display.pending_scroll();
                 if display.render_chapter(w)? {
                     self.full_render(w)?;
+                } else if !toasts.is_empty() {
+                    // Scrolling carries the toasts along with the text.
+                    let height = self.dimensions.screen_size.1 as isize;
+                    let shift = |row: u16| (row as isize - scroll).clamp(0, height) as u16;
+                    display.redraw_rows(w, shift(toasts.start)..shift(toasts.end))?;
                 }
             }
         }
+        self.toasts.render(w, self.dimensions.screen_size)?;
+        Ok(())
+    }
+
+    /// Whether the terminal is smaller than [`MIN_SCREEN_SIZE`].
+    fn is_too_small(&self) -> bool {
+        let (width, height) = self.dimensions.screen_size;
+        width < MIN_SCREEN_SIZE.0 || height < MIN_SCREEN_SIZE.1
+    }
+
+    fn render_too_small(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let (need_w, need_h) = MIN_SCREEN_SIZE;
+        queue!(
+            w,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        let quit = match self.config.bindings.quit.first() {
+            Some(key) => format!(", {key} to quit"),
+            None => String::new(),
+        };
+        let message = format!("terminal too small (need {need_w}x{need_h}){quit}");
+        let message: String = message.chars().take(self.screen_width() as usize).collect();
+        w.write_all(message.as_bytes())?;
+        w.flush()?;
         Ok(())
     }
 
     fn full_render(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        if self.is_too_small() {
+            return self.render_too_small(w);
+        }
         match &mut self.state {
             State::ChapterSelect => {
                 queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
 
-                let chapter = self.book.chapter_by_toc_index(self.chapter).unwrap();
-                let depth_offset = 2 * chapter.depth();
-                let wrap_at = self.content_width() as usize - depth_offset;
-                let wrapped = textwrap::wrap(chapter.name(), wrap_at);
-                let line = self.middle_row() - (wrapped.len() as u16 - 1) / 2;
+                let difficulties: Vec<Option<f64>> = self
+                    .toc
+                    .iter()
+                    .map(|&i| self.cache.difficulty(i).map(|d| d.score()))
+                    .collect();
+                let locked = self.locked_chapters();
+                let progress: Vec<Option<Progress>> = self
+                    .toc
+                    .clone()
+                    .into_iter()
+                    .map(|i| self.progress().get(&i).copied())
+                    .collect();
+
+                let entries = self.chapter_select_entries();
+                let heights: Vec<usize> = entries.iter().map(|(_, lines)| lines.len()).collect();
+                let tops = list_layout(&heights, self.chapter, self.middle_row() as isize);
+                let rows = 1..self.screen_height() as isize;
                 queue!(
                     w,
-                    cursor::MoveTo(self.content_starting_col() - 2, self.middle_row())
+                    cursor::MoveTo(
+                        self.content_starting_col().saturating_sub(2),
+                        self.middle_row()
+                    )
                 )?;
                 w.write_all(b">")?;
-                for (i, wrap) in wrapped.iter().enumerate() {
-                    queue!(
-                        w,
-                        cursor::MoveTo(
-                            self.content_starting_col() + depth_offset as u16,
-                            line + i as u16
-                        )
-                    )?;
-                    w.write_all(wrap.as_bytes())?;
-                }
-
-                let mut above = line - 2;
-                let mut below = line + wrapped.len() as u16 + 1;
-
-                let mut cur = self.chapter;
-                'outer: while cur > 0 {
-                    cur -= 1;
-                    let chapter = self.book.chapter_by_toc_index(cur).unwrap();
-
-                    let depth_offset = 2 * chapter.depth();
-                    let wrap_at = self.content_width() as usize - depth_offset;
-                    let wrapped = textwrap::wrap(chapter.name(), wrap_at);
-
-                    for (i, wrap) in wrapped.iter().rev().enumerate() {
-                        queue!(
-                            w,
-                            cursor::MoveTo(
-                                self.content_starting_col() + depth_offset as u16,
-                                above - i as u16
-                            )
-                        )?;
-                        w.write_all(wrap.as_bytes())?;
-                        if above <= 1 + i as u16 {
-                            break 'outer;
-                        }
+                for (i, ((indent, lines), top)) in entries.iter().zip(tops).enumerate() {
+                    if top >= rows.end || top + (lines.len() as isize) < rows.start {
+                        continue;
                     }
-
-                    above -= u16::try_from(wrapped.len()).unwrap() + 1;
-                }
-
-                cur = self.chapter;
-                'outer: while cur < self.book.chapter_count() {
-                    cur += 1;
-                    let chapter = self.book.chapter_by_toc_index(cur).unwrap();
-
-                    let depth_offset = 2 * chapter.depth();
-                    let wrap_at = self.content_width() as usize - depth_offset;
-                    let wrapped = textwrap::wrap(chapter.name(), wrap_at);
-
-                    for (i, wrap) in wrapped.iter().enumerate() {
-                        if below + i as u16 >= self.screen_height() {
-                            break 'outer;
+                    for (j, line) in lines.iter().enumerate() {
+                        let row = top + j as isize;
+                        if !rows.contains(&row) {
+                            continue;
+                        }
+                        let col = self.content_starting_col().saturating_add(*indent);
+                        queue!(w, cursor::MoveTo(col, row as u16))?;
+                        write_chapter_name(w, line, locked[i])?;
+                        if j == 0 {
+                            self.render_details(w, difficulties[i], progress[i], row as u16)?;
                         }
-                        queue!(
-                            w,
-                            cursor::MoveTo(
-                                self.content_starting_col() + depth_offset as u16,
-                                below + i as u16
-                            )
-                        )?;
-                        w.write_all(wrap.as_bytes())?;
                     }
-
-                    below += u16::try_from(wrapped.len()).unwrap() + 1;
                 }
                 w.flush()?;
                 Ok(())
             }
             State::Chapter(display) => display.full_render_chapter(w),
+            State::Search(search) => search.render(w),
+            State::Score(score) => score.render(w),
+            State::Leaderboard(leaderboard) => leaderboard.render(w),
+            State::Info(info) => info.render(w),
+            State::Vocabulary(vocabulary) => vocabulary.render(w),
+            State::Celebration(celebration) => celebration.render(w),
+            State::Warmup(drill) => drill.render(w),
+        }?;
+        if let Some((modal, _)) = &self.modal {
+            modal.render(w)?;
         }
+        Ok(())
     }
 
-    pub fn handle_input(&mut self, event: KeyEvent) -> anyhow::Result<bool> {
-        if let KeyEvent {
-            code: KeyCode::Esc, ..
-        } = &event
-        {
+    /// Each chapter's name in chapter select, wrapped to fit beside its
+    /// indentation, and the indentation.
+    fn chapter_select_entries(&self) -> Vec<(u16, Vec<String>)> {
+        self.toc
+            .iter()
+            .map(|&i| {
+                let chapter = self.book.chapter_by_toc_index(i).unwrap();
+                let indent = 2 * chapter.depth();
+                let wrap_at = (self.content_width() as usize)
+                    .saturating_sub(indent)
+                    .max(1);
+                let lines = textwrap::wrap(chapter.name(), wrap_at)
+                    .into_iter()
+                    .map(|line| line.into_owned())
+                    .collect();
+                (u16::try_from(indent).unwrap_or(u16::MAX), lines)
+            })
+            .collect()
+    }
+
+    /// How many entries page up and page down move through in chapter
+    /// select: as many as are wholly on screen below the selected one.
+    fn chapter_select_page(&self) -> usize {
+        let heights: Vec<usize> = self
+            .chapter_select_entries()
+            .iter()
+            .map(|(_, lines)| lines.len())
+            .collect();
+        let tops = list_layout(&heights, self.chapter, self.middle_row() as isize);
+        let bottom = self.screen_height() as isize;
+        let below = (self.chapter + 1..heights.len())
+            .take_while(|&i| tops[i] + heights[i] as isize <= bottom)
+            .count();
+        below.max(1)
+    }
+
+    /// Writes a chapter's difficulty score and how much of it has been
+    /// typed to the right of its name in chapter select, as far as there's
+    /// room.
+    fn render_details(
+        &self,
+        w: &mut impl Write,
+        difficulty: Option<f64>,
+        progress: Option<Progress>,
+        row: u16,
+    ) -> anyhow::Result<()> {
+        let col = self.content_starting_col() + self.content_width() + 2;
+        if col + 4 > self.screen_width() {
+            return Ok(());
+        }
+        if let Some(difficulty) = difficulty {
+            queue!(w, cursor::MoveTo(col, row), SetAttribute(Attribute::Dim))?;
+            write!(w, "{difficulty:.2}")?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+
+        let Some(progress) = progress else {
+            return Ok(());
+        };
+        let glyphs = caps::glyphs();
+        let (marker, color) = match progress.typed >= 1.0 {
+            true => (glyphs.check, Color::Green),
+            false => (glyphs.partial, Color::Yellow),
+        };
+        let mut details = format!(" {:>3.0}%", progress.typed * 100.0);
+        if let Some(last) = progress.last {
+            details += &format!("  {}", date::format_ago(last, store::now()));
+        }
+        let col = col + 6;
+        let room = self.screen_width().saturating_sub(col + 1) as usize;
+        if room == 0 {
+            return Ok(());
+        }
+        queue!(w, cursor::MoveTo(col, row), SetForegroundColor(color))?;
+        w.write_all(marker.as_bytes())?;
+        queue!(w, ResetColor, SetAttribute(Attribute::Dim))?;
+        let details: String = details.chars().take(room - 1).collect();
+        w.write_all(details.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        Ok(())
+    }
+
+    /// How much of each chapter of the book has been typed, by TOC index.
+    fn progress(&mut self) -> &BTreeMap<usize, Progress> {
+        self.progress.get_or_insert_with(|| {
+            let key = store::book_key(&self.book);
+            let mut progress = BTreeMap::new();
+            let coverage = self.store.coverage().unwrap_or_default();
+            if let Some(book) = coverage.books.get(&key) {
+                for (&chapter, bitmap) in &book.chapters {
+                    let typed = bitmap.count() as f64 / bitmap.len().max(1) as f64;
+                    progress.insert(chapter, Progress { typed, last: None });
+                }
+            }
+            let sessions = self.store.sessions().unwrap_or_default();
+            for session in sessions.iter().filter(|s| s.book == key) {
+                let entry: &mut Progress = progress.entry(session.chapter).or_default();
+                entry.last = entry.last.max(Some(session.timestamp));
+            }
+            progress
+        })
+    }
+
+    /// Acts on `event`, which arrived from the terminal at `received`.
+    /// Returns whether to quit.
+    pub fn handle_input(&mut self, event: KeyEvent, received: Instant) -> anyhow::Result<bool> {
+        if self.is_too_small() {
+            return Ok(keymap::matches(&self.config.bindings.quit, &event));
+        }
+        if let Some((modal, asking)) = &mut self.modal {
+            let Some(answer) = modal.handle_input(event) else {
+                return Ok(false);
+            };
+            let asking = *asking;
+            self.modal = None;
+            if let State::Chapter(display) = &mut self.state {
+                display.needs_full_render = true;
+            }
+            return Ok(self.answer(asking, answer));
+        }
+
+        if keymap::matches(&self.config.bindings.quit, &event) {
             match &mut self.state {
-                State::ChapterSelect => return Ok(true),
+                State::ChapterSelect => {
+                    self.ask(Asking::Quit, |d| modal::Modal::confirm(d, "Quit ept?"));
+                    return Ok(false);
+                }
                 State::Chapter(..) => {
+                    if let State::Chapter(display) =
+                        std::mem::replace(&mut self.state, State::ChapterSelect)
+                    {
+                        if display.backend.stats().keystrokes > 0 {
+                            self.finish_chapter(display);
+                        }
+                    }
+                    return Ok(false);
+                }
+                State::Score(..) => {
+                    self.leave_score();
+                    return Ok(false);
+                }
+                State::Warmup(drill) => {
+                    let start = drill.start;
+                    self.enter_chapter(start);
+                    return Ok(false);
+                }
+                State::Search(..)
+                | State::Leaderboard(..)
+                | State::Info(..)
+                | State::Vocabulary(..)
+                | State::Celebration(..) => {
                     self.state = State::ChapterSelect;
                     return Ok(false);
                 }
@@ -241,29 +707,1117 @@ impl Display {
 
         match &mut self.state {
             State::ChapterSelect => match event.code {
-                KeyCode::Up | KeyCode::Char('k') => self.chapter = self.chapter.saturating_sub(1),
-                KeyCode::Down | KeyCode::Char('j') => {
-                    self.chapter =
-                        (self.chapter + 1).min(self.book.chapter_count().saturating_sub(1))
-                }
-                KeyCode::Enter => {
-                    let idx = self
-                        .book
-                        .chapter_by_toc_index(self.chapter)
-                        .unwrap()
-                        .index_in_spine();
-                    self.state = State::Chapter(ChapterDisplay::enter(
+                _ if keymap::matches(&self.config.bindings.up, &event) => {
+                    self.chapter = self.chapter.saturating_sub(1)
+                }
+                _ if keymap::matches(&self.config.bindings.down, &event) => {
+                    self.chapter = (self.chapter + 1).min(self.toc.len().saturating_sub(1))
+                }
+                _ if keymap::matches(&self.config.bindings.page_up, &event) => {
+                    self.chapter = self.chapter.saturating_sub(self.chapter_select_page())
+                }
+                _ if keymap::matches(&self.config.bindings.page_down, &event) => {
+                    self.chapter = (self.chapter + self.chapter_select_page())
+                        .min(self.toc.len().saturating_sub(1))
+                }
+                _ if keymap::matches(&self.config.bindings.first, &event) => self.chapter = 0,
+                _ if keymap::matches(&self.config.bindings.last, &event) => {
+                    self.chapter = self.toc.len().saturating_sub(1)
+                }
+                _ if keymap::matches(&self.config.bindings.open, &event) => self.open_chapter(0),
+                _ if keymap::matches(&self.config.bindings.next_unfinished, &event) => {
+                    match self.first_unfinished() {
+                        Some(i) => self.chapter = i,
+                        None => self.toast(toast::Level::Info, "every chapter has been typed"),
+                    }
+                }
+                _ if keymap::matches(&self.config.bindings.search, &event) => {
+                    self.state = State::Search(search::Search::new(Arc::clone(&self.dimensions)))
+                }
+                _ if keymap::matches(&self.config.bindings.info, &event) => {
+                    let details = self.book_info();
+                    self.state =
+                        State::Info(info::BookInfo::new(Arc::clone(&self.dimensions), details));
+                }
+                _ if keymap::matches(&self.config.bindings.vocabulary, &event) => {
+                    self.show_vocabulary()
+                }
+                _ if keymap::matches(&self.config.bindings.pace, &event) => {
+                    let current = match &self.pace {
+                        Some(pace) if !pace.adaptive => format!("{}", pace.wpm),
+                        _ => String::new(),
+                    };
+                    self.ask(Asking::Pace, |d| {
+                        modal::Modal::prompt(
+                            d,
+                            "Pace caret speed in wpm (empty for none)",
+                            &current,
+                        )
+                    });
+                }
+                _ if keymap::matches(&self.config.bindings.leaderboard, &event) => {
+                    self.state = State::Leaderboard(leaderboard::Leaderboard::new(
                         Arc::clone(&self.dimensions),
-                        &mut self.book,
-                        idx,
-                    ));
+                        &store::book_key(&self.book),
+                        book_title(&self.book),
+                        &self.store,
+                    ))
                 }
                 _ => {}
             },
-            State::Chapter(display) => display.handle_input(event)?,
+            State::Chapter(display) => {
+                let stopping = display.backend.stop_on_error();
+                display.handle_input(event, received)?;
+                if display.backend.stop_on_error() != stopping {
+                    let message = match stopping {
+                        true => "moving on past mistakes",
+                        false => "stopping on mistakes",
+                    };
+                    self.toast(toast::Level::Info, message);
+                } else if display.backend.is_finished() {
+                    if let State::Chapter(display) =
+                        std::mem::replace(&mut self.state, State::ChapterSelect)
+                    {
+                        self.finish_chapter(display);
+                    }
+                }
+            }
+            State::Warmup(drill) => {
+                drill.handle_input(event);
+                if drill.is_finished(Instant::now()) {
+                    self.finish_warmup();
+                }
+            }
+            State::Score(..) if keymap::matches(&self.config.bindings.tag, &event) => {
+                let current = self
+                    .sessions
+                    .last()
+                    .map(|s| s.tags.join(", "))
+                    .unwrap_or_default();
+                self.ask(Asking::Tags, |d| {
+                    modal::Modal::prompt(d, "Tags for this session, separated by commas", &current)
+                });
+            }
+            State::Score(score) => {
+                if score.handle_input(event) {
+                    self.leave_score();
+                }
+            }
+            State::Celebration(..) => {
+                if let KeyCode::Enter | KeyCode::Char(' ') = event.code {
+                    self.state = State::ChapterSelect;
+                }
+            }
+            State::Leaderboard(..) => {
+                if matches!(event.code, KeyCode::Enter | KeyCode::Char(' '))
+                    || keymap::matches(&self.config.bindings.leaderboard, &event)
+                {
+                    self.state = State::ChapterSelect;
+                }
+            }
+            State::Vocabulary(vocabulary) => {
+                if vocabulary.handle_input(event) {
+                    self.open_chapter(0);
+                }
+            }
+            State::Info(..) if keymap::matches(&self.config.bindings.goal, &event) => {
+                let key = store::book_key(&self.book);
+                let current = self
+                    .store
+                    .shelf()
+                    .unwrap_or_default()
+                    .books
+                    .into_iter()
+                    .find(|b| b.key == key)
+                    .and_then(|b| b.finish_by)
+                    .map(date::format_date)
+                    .unwrap_or_default();
+                self.ask(Asking::Goal, |d| {
+                    modal::Modal::prompt(
+                        d,
+                        "Finish the book by (YYYY-MM-DD, empty for none)",
+                        &current,
+                    )
+                });
+            }
+            State::Info(info) => {
+                if let Some((chapter, offset)) = info.handle_input(event) {
+                    if let Some(i) = self.toc.iter().position(|&c| c == chapter) {
+                        self.chapter = i;
+                        self.open_chapter(offset);
+                    }
+                }
+            }
+            State::Search(search) => {
+                if let Some((chapter, offset)) =
+                    search.handle_input(event, &mut self.cache, &self.book)
+                {
+                    if let Some(i) = self.toc.iter().position(|&c| c == chapter) {
+                        self.chapter = i;
+                        self.open_chapter(offset);
+                    }
+                }
+            }
         }
         Ok(false)
     }
+
+    /// Opens a modal over the current screen.
+    fn ask(&mut self, asking: Asking, modal: impl FnOnce(Arc<Dimensions>) -> modal::Modal) {
+        self.modal = Some((modal(Arc::clone(&self.dimensions)), asking));
+    }
+
+    /// Acts on the answer to a modal, returning whether to quit.
+    fn answer(&mut self, asking: Asking, answer: modal::Answer) -> bool {
+        match (asking, answer) {
+            (Asking::Quit, modal::Answer::Confirmed) => true,
+            (Asking::Quit, _) => false,
+            (Asking::Pace, modal::Answer::Entered(wpm)) => {
+                match wpm.trim() {
+                    "" => self.pace = None,
+                    wpm => match wpm.parse::<f64>() {
+                        Ok(wpm) if wpm > 0.0 => {
+                            self.pace = Some(PaceConfig {
+                                wpm,
+                                adaptive: false,
+                                ..self.pace.clone().unwrap_or_default()
+                            });
+                        }
+                        _ => self.toast(toast::Level::Warning, format!("not a speed: {wpm}")),
+                    },
+                }
+                false
+            }
+            (Asking::Pace, _) => false,
+            (Asking::Tags, modal::Answer::Entered(tags)) => {
+                self.tag_session(stats::parse_tags(&tags));
+                false
+            }
+            (Asking::Tags, _) => false,
+            (Asking::Goal, modal::Answer::Entered(day)) => {
+                match day.trim() {
+                    "" => self.set_finish_by(None),
+                    day => match date::parse_timestamp(day) {
+                        Some(day) => self.set_finish_by(Some(day)),
+                        None => self.toast(toast::Level::Warning, format!("not a date: {day}")),
+                    },
+                }
+                false
+            }
+            (Asking::Goal, _) => false,
+        }
+    }
+
+    /// Saves the day to finish the book by on the shelf and shows the book
+    /// info again with it.
+    fn set_finish_by(&mut self, finish_by: Option<u64>) {
+        let key = store::book_key(&self.book);
+        let saved =
+            self.store
+                .shelf()
+                .and_then(|mut shelf| match shelf.set_finish_by(&key, finish_by) {
+                    true => self.store.write_shelf(&shelf).map(|()| true),
+                    false => Ok(false),
+                });
+        match saved {
+            Ok(true) => {
+                let details = self.book_info();
+                self.state =
+                    State::Info(info::BookInfo::new(Arc::clone(&self.dimensions), details));
+            }
+            Ok(false) => self.toast(
+                toast::Level::Warning,
+                "only books on the shelf can have a goal",
+            ),
+            Err(e) => {
+                log::error!("failed to save goal: {e}");
+                self.toast(toast::Level::Warning, format!("couldn't save goal: {e}"));
+            }
+        }
+    }
+
+    /// Replaces the tags on the session just recorded.
+    fn tag_session(&mut self, tags: Vec<String>) {
+        let Some(session) = self.sessions.last_mut() else {
+            return;
+        };
+        session.tags = tags;
+        match self.store.update_session(session) {
+            Ok(()) => self.toast(toast::Level::Info, "tags saved"),
+            Err(e) => {
+                log::error!("failed to tag session: {e}");
+                self.toast(toast::Level::Warning, format!("couldn't save tags: {e}"));
+            }
+        }
+    }
+
+    /// Appends a session for the selected chapter to the profile's log and
+    /// shows its result card.
+    fn finish_chapter(&mut self, display: ChapterDisplay) {
+        let backend = &display.backend;
+        let key = store::book_key(&self.book);
+        let sessions = self.store.sessions().unwrap_or_default();
+        let finished_before: Vec<&Session> = sessions
+            .iter()
+            .filter(|s| s.finished && s.book == key && s.chapter == self.toc[self.chapter])
+            .collect();
+        let rerun = finished_before
+            .iter()
+            .filter(|s| !s.paragraphs.is_empty())
+            .max_by_key(|s| s.timestamp)
+            .map(|s| stats::Rerun::compare(backend.text(), &s.paragraphs, &paragraphs(backend)))
+            .filter(|rerun| !rerun.changes.is_empty());
+        let card = Card {
+            title: book_title(&self.book),
+            chapter: self
+                .book
+                .chapter_by_toc_index(self.toc[self.chapter])
+                .map(|c| c.name().to_owned())
+                .unwrap_or_default(),
+            stats: backend.stats(),
+            metric: self.config.metric,
+            speeds: stats::speed_graph(backend.history(), card::SPEED_BUCKETS),
+            sentences: stats::sentence_speeds(
+                &backend.text()[backend.start().bytes..backend.stop().bytes],
+                backend.history(),
+            ),
+            difficulty: self
+                .cache
+                .difficulty(self.toc[self.chapter])
+                .map(|d| d.score()),
+            rerun,
+        };
+        let best = finished_before
+            .iter()
+            .map(|s| s.wpm())
+            .fold(None, |best: Option<f64>, wpm| {
+                Some(best.map_or(wpm, |b| b.max(wpm)))
+            });
+        // Leaving partway through doesn't set a best.
+        if backend.is_finished() && best.is_some_and(|best| card.stats.wpm() > best) {
+            self.toast(
+                toast::Level::Info,
+                format!("new personal best: {:.0} wpm", card.stats.wpm()),
+            );
+        }
+        // Stopping at a limit doesn't finish the chapter.
+        self.record_session(&display, backend.reached_end());
+        self.state = State::Score(score::Score::new(Arc::clone(&self.dimensions), card));
+    }
+
+    /// Moves on from the score screen, to the celebration if the book was
+    /// just finished.
+    fn leave_score(&mut self) {
+        self.state = match self.celebration.take() {
+            Some(celebration) => State::Celebration(celebration),
+            None => State::ChapterSelect,
+        };
+    }
+
+    /// Appends a session for the selected chapter to the profile's log and
+    /// adds what was typed to the book's coverage.
+    fn record_session(&mut self, display: &ChapterDisplay, finished: bool) {
+        self.progress = None;
+        let backend = &display.backend;
+        let chapter = self.toc[self.chapter];
+        let key = store::book_key(&self.book);
+        let session = Session {
+            classes: backend.classes().clone(),
+            shift: backend.shift().clone(),
+            bigrams: backend.bigrams().clone(),
+            intervals: backend.intervals().clone(),
+            keyboard: self.config.keyboard.clone(),
+            pastes: display.pastes,
+            paragraphs: paragraphs(backend),
+            errors: backend.mistake_positions().to_vec(),
+            ..Session::new(
+                key.clone(),
+                chapter,
+                store::now(),
+                &backend.stats(),
+                finished,
+                self.cache.difficulty(chapter).map(|d| d.score()),
+            )
+        };
+        match self.store.record_session(&session) {
+            Ok(()) => self.toast(toast::Level::Info, "session saved"),
+            Err(e) => {
+                log::error!("failed to record session: {e}");
+                self.toast(toast::Level::Warning, format!("couldn't save session: {e}"));
+            }
+        }
+        if let Some((replay, _)) = &display.replay {
+            if let Err(e) = self.store.record_replay(session.timestamp, replay) {
+                log::error!("failed to record replay: {e}");
+            }
+        }
+        if self
+            .config
+            .course
+            .is_some_and(|target| target.is_met_by(&session))
+        {
+            self.passed.insert(chapter);
+        }
+        if let Err(e) = self.record_reviews(&key, chapter, backend, &session.paragraphs) {
+            log::error!("failed to save paragraphs to review: {e}");
+        }
+        self.sessions.push(session);
+
+        if let Err(e) = self.record_coverage(&key, chapter, backend) {
+            log::error!("failed to record coverage: {e}");
+            self.toast(
+                toast::Level::Warning,
+                format!("couldn't save progress: {e}"),
+            );
+        }
+
+        self.left_off = (!finished).then(|| (chapter, backend.cursor().chars));
+        if let Err(e) = self.record_resume_point(&key) {
+            log::error!("failed to save where the book was left off: {e}");
+        }
+    }
+
+    /// Saves the paragraphs in `results` typed below the config's
+    /// `review_below` accuracy to type again on later days.
+    fn record_reviews(
+        &self,
+        key: &str,
+        chapter: usize,
+        backend: &Backend,
+        results: &[stats::ParagraphResult],
+    ) -> anyhow::Result<()> {
+        let Some(below) = self.config.review_below else {
+            return Ok(());
+        };
+        let mut reviews = self.store.reviews()?;
+        let saved = reviews.passages.len();
+        let now = store::now();
+        for result in results {
+            let from = backend.position(result.start).bytes;
+            let text = backend.text()[from..]
+                .split('\n')
+                .next()
+                .unwrap_or_default();
+            let accuracy = 1.0 - result.mistakes as f64 / text.chars().count().max(1) as f64;
+            if accuracy < below {
+                let review =
+                    Review::new(key.to_owned(), chapter, result.start, text.to_owned(), now);
+                reviews.add(review);
+            }
+        }
+        if reviews.passages.len() != saved {
+            self.store.write_reviews(&reviews)?;
+        }
+        Ok(())
+    }
+
+    /// Saves where to pick the book up again on the shelf, if it's there, so
+    /// reopening it carries on from here.
+    fn record_resume_point(&self, key: &str) -> anyhow::Result<()> {
+        let mut shelf = self.store.shelf()?;
+        if shelf.books.iter().any(|b| b.key == key) {
+            shelf.set_resume(key, self.resume_point());
+            self.store.write_shelf(&shelf)?;
+        }
+        Ok(())
+    }
+
+    fn record_coverage(
+        &mut self,
+        key: &str,
+        chapter: usize,
+        backend: &Backend,
+    ) -> anyhow::Result<()> {
+        let mut coverage = self.store.coverage()?;
+        let book = coverage.books.entry(key.to_owned()).or_default();
+        book.title = book_title(&self.book);
+        book.add(chapter, backend.end().chars, backend.typed_correctly());
+        book.add_mistakes(
+            chapter,
+            backend.end().chars,
+            backend.mistake_positions().iter().copied(),
+        );
+
+        if book.completed.is_none() && book.covers(self.body_chapters()) {
+            book.completed = Some(store::now());
+            let sessions = self.store.sessions().unwrap_or_default();
+            let totals = sessions.iter().filter(|s| s.book == key).collect();
+            self.celebration = Some(celebration::Celebration::new(
+                Arc::clone(&self.dimensions),
+                book_title(&self.book),
+                self.body_chapters().len(),
+                totals,
+            ));
+        }
+        self.store.write_coverage(&coverage)
+    }
+
+    /// Gathers what the book info page shows.
+    fn book_info(&mut self) -> info::Details {
+        let key = store::book_key(&self.book);
+        let sessions: Vec<Session> = self
+            .store
+            .sessions()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|s| s.book == key)
+            .collect();
+        let coverage = self
+            .store
+            .coverage()
+            .unwrap_or_default()
+            .books
+            .remove(&key)
+            .unwrap_or_default();
+        let name = |book: &Epub, i: usize| {
+            book.chapter_by_toc_index(i)
+                .map(|c| c.name().to_owned())
+                .unwrap_or_default()
+        };
+
+        let body = self.body_chapters();
+        let completed = body
+            .iter()
+            .filter(|&c| {
+                coverage
+                    .chapters
+                    .get(c)
+                    .is_some_and(coverage::Bitmap::is_full)
+            })
+            .count();
+        let speeds = self
+            .toc
+            .iter()
+            .filter_map(|&i| {
+                let totals: stats::Totals = sessions.iter().filter(|s| s.chapter == i).collect();
+                (totals.sessions > 0).then(|| info::ChapterSpeed {
+                    name: name(&self.book, i),
+                    wpm: totals.wpm(),
+                })
+            })
+            .collect();
+        let resume = self.resume_point();
+        let finish_by = self
+            .store
+            .shelf()
+            .unwrap_or_default()
+            .books
+            .into_iter()
+            .find(|b| b.key == key)
+            .and_then(|b| b.finish_by);
+        let goal = finish_by.map(|finish_by| {
+            // Chapters not parsed yet can't be counted, so the goal is as
+            // short of the whole book as the word count is.
+            let remaining = body
+                .iter()
+                .map(|&c| match coverage.chapters.get(&c) {
+                    Some(typed) => typed.len() - typed.count(),
+                    None => self.cache.get(c).map_or(0, |t| t.text.chars().count()),
+                })
+                .sum();
+            let schedule = goal::Schedule::new(remaining, finish_by, &sessions, store::now());
+            (finish_by, schedule)
+        });
+        let words = self
+            .cache
+            .iter()
+            .map(|(_, c)| c.text.split_whitespace().count())
+            .sum();
+
+        info::Details {
+            title: self.book.title().to_owned(),
+            author: self.book.author().map(str::to_owned),
+            chapters: self.toc.len(),
+            words,
+            parsed: (self.cache.parsed(), self.cache.len()),
+            completed: (completed, body.len()),
+            totals: sessions.iter().collect(),
+            speeds,
+            resume,
+            goal,
+        }
+    }
+
+    /// Where to pick the book up again: where the cursor was left this run,
+    /// or else in the chapter typed most recently, unless it's done.
+    pub fn resume_point(&self) -> Option<Resume> {
+        if let Some((chapter, offset)) = self.left_off {
+            let name = self.book.chapter_by_toc_index(chapter)?.name().to_owned();
+            return Some(Resume {
+                chapter,
+                offset,
+                name,
+            });
+        }
+        let key = store::book_key(&self.book);
+        let sessions = self.store.sessions().unwrap_or_default();
+        let last = sessions
+            .iter()
+            .filter(|s| s.book == key)
+            .max_by_key(|s| s.timestamp)?;
+        let coverage = self.store.coverage().unwrap_or_default();
+        let offset = match coverage
+            .books
+            .get(&key)
+            .and_then(|b| b.chapters.get(&last.chapter))
+        {
+            Some(bitmap) => bitmap.first_unset()?,
+            None => 0,
+        };
+        let name = self
+            .book
+            .chapter_by_toc_index(last.chapter)?
+            .name()
+            .to_owned();
+        let resume = Resume {
+            chapter: last.chapter,
+            offset,
+            name,
+        };
+        Some(resume).filter(|r| self.toc.contains(&r.chapter))
+    }
+
+    /// Opens the chapter at TOC index `chapter` from character `offset`, if
+    /// it's listed in chapter select. Returns whether it was.
+    pub fn open_at(&mut self, chapter: usize, offset: usize) -> bool {
+        let Some(i) = self.toc.iter().position(|&c| c == chapter) else {
+            return false;
+        };
+        self.chapter = i;
+        self.open_chapter(offset);
+        true
+    }
+
+    /// Whether nothing is being typed, neither a chapter nor a warm-up.
+    pub fn is_between_sessions(&self) -> bool {
+        !matches!(self.state, State::Chapter(..) | State::Warmup(..))
+    }
+
+    /// For each entry in chapter select, whether it's locked until the body
+    /// chapter before it has been typed to the course's targets.
+    fn locked_chapters(&mut self) -> Vec<bool> {
+        if self.config.course.is_none() {
+            return vec![false; self.toc.len()];
+        }
+        let body = self.body_chapters();
+        self.toc
+            .iter()
+            .map(|chapter| match body.iter().position(|c| c == chapter) {
+                Some(i) if i > 0 => !self.passed.contains(&body[i - 1]),
+                _ => false,
+            })
+            .collect()
+    }
+
+    fn unlock_hint(&self) -> String {
+        let target = self.config.course.unwrap_or_default();
+        let mut needs = Vec::new();
+        if let Some(wpm) = target.wpm {
+            needs.push(format!("{wpm:.0} wpm"));
+        }
+        if let Some(accuracy) = target.accuracy {
+            needs.push(format!("{:.0}% accuracy", accuracy * 100.0));
+        }
+        match needs.is_empty() {
+            true => "finish the chapter before to unlock".to_owned(),
+            false => format!(
+                "finish the chapter before at {} to unlock",
+                needs.join(" and ")
+            ),
+        }
+    }
+
+    /// Chapters that must be typed for the book to count as finished: those
+    /// listed in chapter select, minus front and back matter and chapters
+    /// with no text.
+    fn body_chapters(&mut self) -> Vec<usize> {
+        let mut body = Vec::new();
+        for &i in &self.toc {
+            let is_body = self
+                .book
+                .chapter_by_toc_index(i)
+                .is_some_and(|c| coverage::is_body_matter(c.name()));
+            // Chapters not parsed yet are assumed to have text.
+            let is_empty = self.cache.get(i).is_some_and(|t| t.text.trim().is_empty());
+            if is_body && !is_empty {
+                body.push(i);
+            }
+        }
+        body
+    }
+
+    pub fn sessions(&self) -> &[Session] {
+        &self.sessions
+    }
+
+    pub fn book(&self) -> &Epub {
+        &self.book
+    }
+
+    pub fn store(&self) -> &Store {
+        &self.store
+    }
+
+    /// Applies a book's sidecar to the chapters listed in chapter select.
+    pub fn arrange(&mut self, sidecar: &Sidecar) {
+        sidecar.arrange(&mut self.toc);
+        self.chapter = 0;
+        self.select_where_left_off();
+    }
+
+    /// Limits chapter select to the given TOC indices. Fails if none of them
+    /// are listed.
+    pub fn restrict_to(&mut self, chapters: RangeInclusive<usize>) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            self.toc.iter().any(|i| chapters.contains(i)),
+            "none of chapters {}-{} can be typed",
+            chapters.start() + 1,
+            chapters.end() + 1
+        );
+        self.toc.retain(|i| chapters.contains(i));
+        self.chapter = 0;
+        self.select_where_left_off();
+        Ok(())
+    }
+
+    /// Shows the uncommon words of the selected chapter, if it's been read.
+    fn show_vocabulary(&mut self) {
+        let toc_index = self.toc[self.chapter];
+        let Some(text) = self.cache.get(toc_index) else {
+            self.toast(
+                toast::Level::Info,
+                "still reading the chapter, try again shortly",
+            );
+            return;
+        };
+        let words = crate::vocabulary::uncommon(&text.text, VOCABULARY_WORDS);
+        let name = self
+            .book
+            .chapter_by_toc_index(toc_index)
+            .map(|c| c.name().to_owned())
+            .unwrap_or_default();
+        self.state = State::Vocabulary(vocabulary::Vocabulary::new(
+            Arc::clone(&self.dimensions),
+            name,
+            words,
+        ));
+    }
+
+    /// Opens the selected chapter at character `start`, after a warm-up if
+    /// one is due, unless the course hasn't reached it yet.
+    fn open_chapter(&mut self, start: usize) {
+        let Some(&locked) = self.locked_chapters().get(self.chapter) else {
+            return;
+        };
+        if locked {
+            self.state = State::ChapterSelect;
+            self.toast(toast::Level::Warning, self.unlock_hint());
+            return;
+        }
+        let toc_index = self.toc[self.chapter];
+        if self
+            .cache
+            .get(toc_index)
+            .is_some_and(|t| t.text.trim().is_empty())
+        {
+            self.state = State::ChapterSelect;
+            self.toast(
+                toast::Level::Warning,
+                "nothing to type here; the chapter is images or SVG only",
+            );
+            return;
+        }
+        if self.config.warmup && !self.warmed_up {
+            self.warmed_up = true;
+            let sessions = self.store.sessions().unwrap_or_default();
+            let mut bigrams = Bigrams::default();
+            for session in sessions.iter().rev().take(WARMUP_SESSIONS) {
+                bigrams.merge(&session.bigrams);
+            }
+            let text = warmup::drill(&bigrams.weakest(WEAK_BIGRAMS), store::now());
+            self.state = State::Warmup(drill::Drill::new(
+                Arc::clone(&self.dimensions),
+                &text,
+                start,
+            ));
+            return;
+        }
+        self.enter_chapter(start);
+    }
+
+    /// Records the warm-up just typed and carries on into the chapter.
+    fn finish_warmup(&mut self) {
+        let State::Warmup(drill) = &self.state else {
+            return;
+        };
+        let start = drill.start;
+        let result = drill.result(Instant::now(), store::now());
+        if result.keystrokes > 0 {
+            if let Err(e) = self.store.record_warmup(&result) {
+                log::error!("failed to record warm-up: {e}");
+            }
+            let wpm = stats::wpm(result.chars, Duration::from_millis(result.elapsed_ms));
+            let accuracy = 1.0 - result.mistakes as f64 / result.keystrokes as f64;
+            self.toast(
+                toast::Level::Info,
+                format!("warmed up at {wpm:.0} wpm, {:.0}%", accuracy * 100.0),
+            );
+        }
+        self.enter_chapter(start);
+    }
+
+    fn enter_chapter(&mut self, start: usize) {
+        let idx = self
+            .book
+            .chapter_by_toc_index(self.toc[self.chapter])
+            .unwrap()
+            .index_in_spine();
+        let pace = self.pace();
+        let mut display = ChapterDisplay::enter(
+            Arc::clone(&self.dimensions),
+            &self.config,
+            &mut self.book,
+            idx,
+            start,
+            pace,
+        );
+        let coverage = self.store.coverage().unwrap_or_default();
+        display.tint_paragraphs(
+            coverage.books.get(&store::book_key(&self.book)),
+            self.toc[self.chapter],
+        );
+        self.state = State::Chapter(display);
+    }
+
+    fn pace(&self) -> Option<Pace> {
+        let config = self.pace.as_ref()?;
+        if !config.adaptive {
+            return Some(Pace::new(Target::Fixed(config.wpm)));
+        }
+        let sessions = self.store.sessions().unwrap_or_default();
+        let imported = self.store.imported().unwrap_or_default();
+        Some(Pace::new(Target::Adaptive {
+            recent: stats::recent(&sessions, store::now(), pace::ROLLING_DAYS),
+            fallback: stats::baseline(&sessions, &imported).unwrap_or(config.wpm),
+            delta: config.delta,
+        }))
+    }
+
+    /// How long the event loop may wait for input before calling
+    /// [`tick`](Self::tick), or `None` if nothing is animating.
+    pub fn timeout(&self) -> Option<Duration> {
+        let now = Instant::now();
+        let frame = match &self.state {
+            State::Chapter(display) if self.modal.is_none() => {
+                let animation = display
+                    .is_animating(now)
+                    .then(|| self.clock.until_next(now));
+                match (animation, display.status_due(now)) {
+                    (Some(a), Some(b)) => Some(a.min(b)),
+                    (a, b) => a.or(b),
+                }
+            }
+            State::Warmup(drill) => drill.next_tick(now),
+            _ => None,
+        };
+        match (frame, self.toasts.next_change(now)) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// Draws a frame of whatever is animating, if one is due, and takes
+    /// down expired toasts.
+    pub fn tick(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        if self.is_too_small() {
+            return Ok(());
+        }
+        let now = Instant::now();
+        if self.toasts.update(now) {
+            match &mut self.state {
+                State::Chapter(display) => display.redraw_rows(w, self.toasts.drawn())?,
+                _ => self.full_render(w)?,
+            }
+            self.toasts.render(w, self.dimensions.screen_size)?;
+        }
+        if let State::Warmup(drill) = &self.state {
+            if drill.is_finished(now) {
+                self.finish_warmup();
+            }
+            self.full_render(w)?;
+            return self.toasts.render(w, self.dimensions.screen_size);
+        }
+        if self.modal.is_some() || !self.clock.start_frame(now) {
+            return Ok(());
+        }
+        if let State::Chapter(display) = &mut self.state {
+            display.tick(w)?;
+        }
+        Ok(())
+    }
+
+    /// Takes pasted text into whichever text field is open. Pastes while
+    /// typing are refused, and count against the session.
+    pub fn handle_paste(&mut self, text: &str) {
+        if let Some((modal, _)) = &mut self.modal {
+            modal.paste(text);
+            return;
+        }
+        match &mut self.state {
+            State::Search(search) => search.paste(text),
+            State::Chapter(display) => {
+                display.pastes += 1;
+                self.toast(toast::Level::Warning, "pasting isn't allowed while typing");
+            }
+            _ => {}
+        }
+    }
+
+    /// Lays everything out again for a terminal `width` by `height`.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        let dimensions = Dimensions::new(&self.config, width, height);
+        self.set_dimensions(Arc::new(dimensions));
+    }
+
+    /// Pauses typing while the terminal doesn't have focus.
+    pub fn set_focus(&mut self, focused: bool) {
+        if let State::Chapter(display) = &mut self.state {
+            match focused {
+                true => display.resume(),
+                false => display.pause(),
+            }
+        }
+    }
+
+    /// Shows a short message in the corner of the screen.
+    fn toast(&mut self, level: toast::Level, message: impl Into<String>) {
+        self.toasts.push(level, message);
+    }
+}
+
+/// Writes a chapter's name in chapter select, dimmed if it's locked.
+fn write_chapter_name(w: &mut impl Write, name: &str, locked: bool) -> anyhow::Result<()> {
+    if !locked {
+        w.write_all(name.as_bytes())?;
+        return Ok(());
+    }
+    queue!(w, SetAttribute(Attribute::Dim))?;
+    w.write_all(name.as_bytes())?;
+    queue!(w, SetAttribute(Attribute::Reset))?;
+    Ok(())
+}
+
+/// Plays a [`Replay`] back in the typing view, at the speed it was typed.
+pub struct Playback {
+    display: ChapterDisplay,
+    events: std::vec::IntoIter<replay::Event>,
+    next: Option<replay::Event>,
+    started: Instant,
+}
+
+impl Playback {
+    /// Fails if `book` doesn't have the text the replay was typed against,
+    /// whether it's a different book or the config filters it differently.
+    pub fn new(
+        book: &mut Epub,
+        config: &Config,
+        replay: Replay,
+        view_width: u16,
+        view_height: u16,
+    ) -> anyhow::Result<Self> {
+        let text = ChapterText::extract(book, replay.chapter, config)?;
+        let backend = replay.backend(text)?;
+        let dimensions = Arc::new(Dimensions::new(config, view_width, view_height));
+        let display = ChapterDisplay::from_backend(
+            dimensions,
+            backend,
+            None,
+            config.gutter,
+            InputMethod::Keyboard.translator(&config.bindings),
+        );
+        let mut events = replay.events.into_iter();
+        Ok(Self {
+            display,
+            next: events.next(),
+            events,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        Display::enter_screen(w)?;
+        self.started = Instant::now();
+        self.display.full_render_chapter(w)
+    }
+
+    /// How long until the next keystroke, or `None` once they've all been
+    /// played.
+    pub fn timeout(&self) -> Option<Duration> {
+        let next = self.next?;
+        let due = self.started + Duration::from_millis(next.ms);
+        Some(due.saturating_duration_since(Instant::now()))
+    }
+
+    /// Lays the text out again for a terminal `width` by `height`.
+    pub fn resize(&mut self, config: &Config, width: u16, height: u16) {
+        let dimensions = Dimensions::new(config, width, height);
+        self.display.set_dimensions(Arc::new(dimensions));
+    }
+
+    /// Plays every keystroke that's due.
+    pub fn tick(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        let elapsed = self.started.elapsed();
+        while let Some(event) = self.next.filter(|e| Duration::from_millis(e.ms) <= elapsed) {
+            self.display.apply(event.action);
+            self.next = self.events.next();
+        }
+        if self.display.render_chapter(w)? {
+            self.display.full_render_chapter(w)?;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> stats::Stats {
+        self.display.backend.stats()
+    }
+}
+
+/// A passage typed on its own, outside any book.
+pub struct Practice {
+    display: ChapterDisplay,
+    /// Keys that give up on the passage.
+    quit: Vec<keymap::Binding>,
+}
+
+impl Practice {
+    pub fn new(config: &Config, text: &str, view_width: u16, view_height: u16) -> Self {
+        let text = ChapterText {
+            text: text.to_owned(),
+            styling: Styling::builder().build(),
+            blocks: Vec::new(),
+        };
+        let backend = Backend::from_text(text, config.backspace);
+        let dimensions = Arc::new(Dimensions::new(config, view_width, view_height));
+        let mut display = ChapterDisplay::from_backend(
+            dimensions,
+            backend,
+            None,
+            config.gutter,
+            config.input.translator(&config.bindings),
+        );
+        display.alert = config.alert;
+        Self {
+            display,
+            quit: config.bindings.quit.clone(),
+        }
+    }
+
+    pub fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        Display::enter_screen(w)?;
+        self.display.full_render_chapter(w)
+    }
+
+    /// Returns whether the passage is over, typed to the end or given up
+    /// on with a quit key.
+    pub fn handle_input(&mut self, event: KeyEvent, received: Instant) -> anyhow::Result<bool> {
+        if keymap::matches(&self.quit, &event) {
+            return Ok(true);
+        }
+        self.display.handle_input(event, received)?;
+        Ok(self.display.backend.is_finished())
+    }
+
+    pub fn render(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        if self.display.render_chapter(w)? {
+            self.display.full_render_chapter(w)?;
+        }
+        Ok(())
+    }
+
+    /// Lays the passage out again for a terminal `width` by `height`.
+    pub fn resize(&mut self, config: &Config, width: u16, height: u16) {
+        let dimensions = Dimensions::new(config, width, height);
+        self.display.set_dimensions(Arc::new(dimensions));
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.display.backend.is_finished()
+    }
+
+    pub fn stats(&self) -> stats::Stats {
+        self.display.backend.stats()
+    }
+}
+
+/// Leftmost column of the text, its width and the width it's wrapped at, on
+/// a screen `view_width` columns wide.
+///
+/// A screen too narrow for the configured width still wraps at it, so that
+/// lines break in the same places whatever the terminal's size, and the text
+/// scrolls sideways to follow the cursor.
+fn text_column(config: &Config, view_width: u16) -> (u16, u16, u16) {
+    let left = config.margin_left + SIDE_MARGIN;
+    let right = config.margin_right + SIDE_MARGIN;
+    let available = view_width.saturating_sub(left + right);
+    let width = config
+        .width
+        .min((view_width as f64 * config.max_width.clamp(0.0, 1.0)) as u16)
+        .min(available)
+        .max(1);
+    let slack = available.saturating_sub(width);
+    let offset = match config.column {
+        Column::Left => 0,
+        Column::Center => slack / 2,
+        Column::Right => slack,
+    };
+    let wrap = match available < config.width {
+        true => config.width,
+        false => width,
+    };
+    (left + offset, width, wrap)
+}
+
+/// Top row of each entry in a list where entry `i` takes `heights[i]` rows,
+/// with a blank row between entries and the `selected` one centred on
+/// `middle`. Rows can be off either edge of the screen, and are left for
+/// the caller to clip.
+fn list_layout(heights: &[usize], selected: usize, middle: isize) -> Vec<isize> {
+    let mut tops = vec![0; heights.len()];
+    let Some(&height) = heights.get(selected) else {
+        return tops;
+    };
+    tops[selected] = middle - (height as isize - 1).max(0) / 2;
+    for i in (0..selected).rev() {
+        tops[i] = tops[i + 1] - heights[i] as isize - 1;
+    }
+    for i in selected + 1..heights.len() {
+        tops[i] = tops[i - 1] + heights[i - 1] as isize + 1;
+    }
+    tops
+}
+
+/// Row the typing line sits on, `anchor` of the way down a screen `height`
+/// rows tall.
+fn anchor_row(anchor: f64, height: u16) -> u16 {
+    let row = (anchor.clamp(0.0, 1.0) * height as f64) as u16;
+    row.min(height.saturating_sub(1))
+}
+
+/// Results for each paragraph typed in full in `backend`.
+fn paragraphs(backend: &Backend) -> Vec<stats::ParagraphResult> {
+    stats::paragraph_results(
+        &backend.text()[backend.start().bytes..backend.stop().bytes],
+        backend.start().chars,
+        backend.history(),
+        backend.mistake_positions(),
+    )
+}
+
+fn book_title(book: &Epub) -> String {
+    match book.author() {
+        Some(author) => format!("{author}'s {}", book.title()),
+        None => book.title().to_owned(),
+    }
 }
 
 struct ChapterDisplay {
@@ -272,11 +1826,48 @@ struct ChapterDisplay {
     lines: Vec<VirtualLine>,
     previous_line: usize,
     needs_full_render: bool,
+    /// Caret position while in reading mode, where the text can be browsed
+    /// without typing to pick a place to start from.
+    reading: Option<Len>,
+    pace: Option<Pace>,
+    /// Where the pace caret is currently drawn.
+    pace_drawn: Option<Len>,
+    gutter: Gutter,
+    /// Start of the paragraph the context pane was last drawn around.
+    context_drawn: Option<usize>,
+    translator: Box<dyn Translator>,
+    alert: Alert,
+    /// Set by a mistake until the next render rings the bell.
+    ring_bell: bool,
+    /// Screen edges flashed for a mistake.
+    flash: Option<Animation>,
+    /// Keystrokes so far, and when the first was.
+    replay: Option<(Replay, Option<Instant>)>,
+    /// When the terminal lost focus, while it doesn't have it.
+    paused: Option<Instant>,
+    /// Pastes refused since typing started.
+    pastes: usize,
+    /// Speed to show in the status corner, if it's shown.
+    status: Option<Metric>,
+    /// When the status corner was last drawn, and how wide.
+    status_drawn: Option<(Instant, usize)>,
+    /// Stretch of typing the status corner's live speed and accuracy cover,
+    /// if they're shown. Redrawn on every keystroke when set.
+    status_window: Option<Duration>,
+    /// Leftmost column of the wrapped text on screen, when it's wider than
+    /// the screen.
+    scroll: u16,
+    /// Where each paragraph starts, in characters, and the colour it's
+    /// tinted in reading mode.
+    tints: Vec<(usize, Option<Color>)>,
 }
 
 trait DisplayState {
     fn dimensions(&self) -> &Dimensions;
 
+    /// Takes on the layout of a resized terminal, to be drawn in full next.
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>);
+
     fn content_width(&self) -> u16 {
         self.dimensions().width
     }
@@ -302,70 +1893,176 @@ impl DisplayState for Display {
     fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        let screen: Option<&mut dyn DisplayState> = match &mut self.state {
+            State::ChapterSelect => None,
+            State::Chapter(display) => Some(display),
+            State::Search(search) => Some(search),
+            State::Score(score) => Some(score),
+            State::Leaderboard(leaderboard) => Some(leaderboard),
+            State::Info(info) => Some(info),
+            State::Vocabulary(vocabulary) => Some(vocabulary),
+            State::Celebration(celebration) => Some(celebration),
+            State::Warmup(drill) => Some(drill),
+        };
+        if let Some(screen) = screen {
+            screen.set_dimensions(Arc::clone(&dimensions));
+        }
+        if let Some((modal, _)) = &mut self.modal {
+            modal.set_dimensions(Arc::clone(&dimensions));
+        }
+        if let Some(celebration) = &mut self.celebration {
+            celebration.set_dimensions(Arc::clone(&dimensions));
+        }
+        self.dimensions = dimensions;
+    }
 }
 
 impl DisplayState for ChapterDisplay {
     fn dimensions(&self) -> &Dimensions {
         &self.dimensions
     }
+
+    /// Wraps the text again if the width it's wrapped at has changed.
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        if dimensions.wrap != self.dimensions.wrap {
+            self.lines = Self::wrap_text(self.backend.text(), dimensions.wrap);
+        }
+        self.dimensions = dimensions;
+        self.previous_line = self.char_index_to_virtual_line(self.backend.cursor().chars);
+        self.pace_drawn = None;
+        self.context_drawn = None;
+        self.status_drawn = None;
+        self.needs_full_render = true;
+    }
 }
 
 impl ChapterDisplay {
-    pub fn enter(dimensions: Arc<Dimensions>, book: &mut Epub, chapter: usize) -> Self {
-        let backend = Backend::new(book, chapter);
-        let lines = Self::wrap_text(backend.text(), dimensions.width);
+    /// Opens `chapter` with typing starting from the sentence containing the
+    /// `start`th character.
+    pub fn enter(
+        dimensions: Arc<Dimensions>,
+        config: &Config,
+        book: &mut Epub,
+        chapter: usize,
+        start: usize,
+        pace: Option<Pace>,
+    ) -> Self {
+        let mut backend = Backend::new(book, chapter, config);
+        if start > 0 {
+            backend.start_at(backend.sentence_start(backend.position(start)));
+        }
+        backend.set_limit(config.limit);
+        backend.set_grace(config.grace_ms);
+        backend.set_repeat_words(config.repeat_words);
+        backend.set_stop_on_error(config.stop_on_error);
+        let mut display = Self::from_backend(
+            dimensions,
+            backend,
+            pace,
+            config.gutter,
+            config.input.translator(&config.bindings),
+        );
+        display.alert = config.alert;
+        display.status = config.status.then_some(config.metric);
+        display.status_window =
+            (config.status_window > 0).then(|| Duration::from_secs(config.status_window));
+        let replay = Replay::new(
+            store::book_key(book),
+            chapter,
+            display.backend.cursor().chars,
+            display.backend.text(),
+            config,
+        );
+        display.replay = Some((replay, None));
+        display
+    }
 
-        Self {
+    fn from_backend(
+        dimensions: Arc<Dimensions>,
+        backend: Backend,
+        pace: Option<Pace>,
+        gutter: Gutter,
+        translator: Box<dyn Translator>,
+    ) -> Self {
+        let lines = Self::wrap_text(backend.text(), dimensions.wrap);
+
+        let mut display = Self {
             dimensions,
+            gutter,
+            context_drawn: None,
             backend,
             lines,
             previous_line: 0,
             needs_full_render: true,
-        }
+            reading: None,
+            pace,
+            pace_drawn: None,
+            translator,
+            alert: Alert::None,
+            ring_bell: false,
+            flash: None,
+            paused: None,
+            pastes: 0,
+            status: None,
+            status_drawn: None,
+            status_window: None,
+            replay: None,
+            scroll: 0,
+            tints: Vec::new(),
+        };
+        display.previous_line = display.char_index_to_virtual_line(display.backend.cursor().chars);
+        display
     }
 
     fn wrap_text(text: &str, width: u16) -> Vec<VirtualLine> {
         let mut lines = vec![];
         let mut byte_sum = 0;
         let mut char_sum = 0;
-        let mut line_number = 0;
+        let mut paragraph = Some(1);
+        let mut paragraphs = 1;
 
         let wrapped = textwrap::wrap(text, width as usize);
         let mut it = wrapped.into_iter();
         let mut prev = it.next();
         while let (Some(line), Some(next)) = (prev, it.next()) {
-            let this_line = line_number;
             let line_chars = line.chars().count();
-            let end = byte_sum + line.len();
-            let (separator_len, linebreak) = {
-                let next_start = next.as_ptr() as usize - text.as_ptr() as usize;
-                let len = next_start - end;
-                let separator = &text[end..next_start];
-                let kind = match separator.contains('\n') {
-                    true => {
-                        line_number += 2;
-                        Linebreak::Existing
-                    }
-                    false => {
-                        line_number += 1;
-                        Linebreak::Wrapped
-                    }
-                };
-                (Len::new(len, separator.chars().count()), kind)
+            let start = Len::new(byte_sum, char_sum);
+            let end = start + Len::new(line.len(), line_chars);
+            let next_start = next.as_ptr() as usize - text.as_ptr() as usize;
+            let separator = &text[end.bytes..next_start];
+            let separator_len = Len::new(separator.len(), separator.chars().count());
+            let linebreak = match separator.contains('\n') {
+                true => Linebreak::Existing,
+                false => Linebreak::Wrapped,
             };
             lines.push(VirtualLine {
-                line: this_line,
-                start: Len::new(byte_sum, char_sum),
-                end: Len::new(end, char_sum + line_chars),
+                paragraph: paragraph.take(),
+                start,
+                end,
                 separator_len,
                 linebreak,
             });
-            byte_sum += line.len() + separator_len.bytes;
-            char_sum += line_chars + separator_len.chars;
+            if linebreak == Linebreak::Existing {
+                paragraphs += 1;
+                paragraph = Some(paragraphs);
+                // Ends where the paragraph does, so the cursor never lands on
+                // it.
+                lines.push(VirtualLine {
+                    paragraph: None,
+                    start: end,
+                    end,
+                    separator_len: Len::new(0, 0),
+                    linebreak: Linebreak::Blank,
+                });
+            }
+            byte_sum = end.bytes + separator_len.bytes;
+            char_sum = end.chars + separator_len.chars;
             prev = Some(next);
         }
         lines.push(VirtualLine {
-            line: line_number,
+            paragraph,
             start: Len::new(byte_sum, char_sum),
             end: Len::new(text.len(), char_sum + text[byte_sum..].chars().count()),
             separator_len: Len::new(0, 0),
@@ -374,6 +2071,11 @@ impl ChapterDisplay {
         lines
     }
 
+    /// Position the view is centered on.
+    fn focus(&self) -> Len {
+        self.reading.unwrap_or_else(|| self.backend.cursor())
+    }
+
     fn char_index_to_virtual_line(&self, idx: usize) -> usize {
         self.lines.partition_point(|e| e.end.chars < idx)
     }
@@ -388,12 +2090,52 @@ impl ChapterDisplay {
         &self.backend.text()[vl.start.bytes..vl.end.bytes]
     }
 
+    /// Position `chars` characters into `vl`, counting a byte for each
+    /// character past its end.
+    fn offset_in_line(&self, vl: &VirtualLine, chars: usize) -> Len {
+        match self.virtual_line_str(vl).char_indices().nth(chars) {
+            Some((bytes, _)) => Len::new(bytes, chars),
+            None => {
+                let len = vl.end - vl.start;
+                len + Len::new(chars - len.chars, chars - len.chars)
+            }
+        }
+    }
+
+    /// Screen column of the `x`th column of the wrapped text.
+    fn col_of(&self, x: u16) -> u16 {
+        self.content_starting_col() + x.saturating_sub(self.scroll)
+    }
+
+    /// Scrolls sideways, when the text is wider than the screen, to keep the
+    /// focus a quarter of the width from either edge. Returns whether the
+    /// text moved.
+    fn update_scroll(&mut self) -> bool {
+        let width = self.content_width();
+        let scroll = match self.dimensions.wrap > width {
+            true => {
+                let (x, _) = self.to_virtual(self.focus().chars);
+                let margin = width / 4;
+                if x < self.scroll + margin {
+                    x.saturating_sub(margin)
+                } else if x + margin >= self.scroll + width {
+                    x + margin + 1 - width
+                } else {
+                    self.scroll
+                }
+            }
+            false => 0,
+        };
+        std::mem::replace(&mut self.scroll, scroll) != scroll
+    }
+
     fn screen_lines(&self, range: impl RangeBounds<u16>) -> impl Iterator<Item = ScreenLine<'_>> {
         let start_bound = match range.start_bound() {
             Bound::Included(&l) => l,
             Bound::Excluded(&l) => l + 1,
             Bound::Unbounded => 0,
         }
+        .max(self.dimensions.top)
         .min(self.screen_height() - 1);
         let end_bound = match range.end_bound() {
             Bound::Included(&l) => l + 1,
@@ -402,20 +2144,20 @@ impl ChapterDisplay {
         }
         .min(self.screen_height());
 
-        let line = self.char_index_to_virtual_line(self.backend.cursor().chars);
-        let cursor_vln = self.lines[line].line;
+        let cursor_vln = self.char_index_to_virtual_line(self.focus().chars);
         let top_of_screen_vln = cursor_vln as isize - self.middle_row() as isize;
         let start_vln = (top_of_screen_vln + start_bound as isize).max(0) as usize;
         let end_vln = (top_of_screen_vln + end_bound as isize).max(0) as usize;
         let offset = (start_vln as isize - top_of_screen_vln).max(0) as usize;
 
-        let start = self.lines.partition_point(|l| l.line < start_vln);
-        self.lines[start..]
+        let start = start_vln.min(self.lines.len());
+        let end = end_vln.clamp(start, self.lines.len());
+        self.lines[start..end]
             .iter()
-            .take_while(move |vl| vl.line < end_vln)
-            .map(move |vl| ScreenLine {
+            .enumerate()
+            .map(move |(i, vl)| ScreenLine {
                 line: vl,
-                row: ((vl.line - start_vln) + offset) as u16,
+                row: (i + offset) as u16,
             })
     }
 
@@ -442,9 +2184,220 @@ impl ChapterDisplay {
     }
 
     fn render_line(&self, w: &mut impl Write, line: &ScreenLine) -> anyhow::Result<()> {
+        if let (Gutter::Paragraphs, Some(paragraph)) = (self.gutter, line.line.paragraph) {
+            let number = paragraph.to_string();
+            let len = number.len() as u16;
+            if let Some(col) = self.content_starting_col().checked_sub(len + 2) {
+                queue!(
+                    w,
+                    cursor::MoveTo(col, line.row),
+                    SetAttribute(Attribute::Dim)
+                )?;
+                w.write_all(number.as_bytes())?;
+                queue!(w, SetAttribute(Attribute::Reset))?;
+            }
+        }
+        if let Some(tint) = self.tint(line.line) {
+            queue!(w, SetForegroundColor(tint))?;
+        }
         self.render_range_in_line(w, line, Len::new(0, 0), line.len_with_break())
     }
 
+    /// Works out the colour of each paragraph in reading mode, to show where
+    /// the trouble spots are: from how accurately it was typed before, if
+    /// most of it has been, or else from how hard it looks, in darker
+    /// colours.
+    fn tint_paragraphs(&mut self, book: Option<&BookCoverage>, chapter: usize) {
+        self.tints.clear();
+        if caps::get().color == caps::ColorDepth::None {
+            return;
+        }
+        let len = self.backend.end().chars;
+        let history = |bitmaps: &BTreeMap<usize, coverage::Bitmap>| {
+            bitmaps.get(&chapter).filter(|b| b.len() == len).cloned()
+        };
+        let typed = book.and_then(|b| history(&b.chapters));
+        let mistakes = book.and_then(|b| history(&b.mistakes));
+        let count = |bitmap: &Option<coverage::Bitmap>, range: Range<usize>| {
+            bitmap
+                .as_ref()
+                .map_or(0, |b| range.filter(|&i| b.contains(i)).count())
+        };
+
+        let mut start = 0;
+        for paragraph in self.backend.text().split('\n') {
+            let chars = paragraph.chars().count();
+            let range = start..start + chars;
+            let tint = if chars > 0 && count(&typed, range.clone()) * 2 >= chars {
+                let accuracy = 1.0 - count(&mistakes, range) as f64 / chars as f64;
+                Some(match accuracy {
+                    a if a >= 0.98 => Color::Green,
+                    a if a >= 0.94 => Color::Yellow,
+                    _ => Color::Red,
+                })
+            } else {
+                match Difficulty::of(paragraph).score() {
+                    d if d < 1.15 => None,
+                    d if d < 1.4 => Some(Color::DarkYellow),
+                    _ => Some(Color::DarkRed),
+                }
+            };
+            self.tints.push((start, tint));
+            start += chars + 1;
+        }
+    }
+
+    /// Colour `line` is tinted, while in reading mode.
+    fn tint(&self, line: &VirtualLine) -> Option<Color> {
+        self.reading?;
+        let i = self
+            .tints
+            .partition_point(|&(start, _)| start <= line.start.chars);
+        self.tints.get(i.checked_sub(1)?)?.1
+    }
+
+    /// Start of the paragraph the view is focused on.
+    fn paragraph_start(&self) -> usize {
+        let text = self.backend.text();
+        text[..self.focus().bytes].rfind('\n').map_or(0, |i| i + 1)
+    }
+
+    /// Draws the paragraphs before and after the current one, dimmed, in the
+    /// context pane if there is one.
+    fn render_context(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        let Some((col, width)) = self.dimensions.context else {
+            return Ok(());
+        };
+        let text = self.backend.text();
+        let start = self.paragraph_start();
+        let end = text[start..].find('\n').map_or(text.len(), |i| start + i);
+        let height = self.screen_height();
+        let middle = self.middle_row();
+
+        queue!(w, SetAttribute(Attribute::Dim))?;
+        for row in 0..height {
+            queue!(
+                w,
+                cursor::MoveTo(col, row),
+                terminal::Clear(terminal::ClearType::UntilNewLine)
+            )?;
+        }
+        if let Some(previous) = text[..start.saturating_sub(1)].rsplit('\n').next() {
+            let wrapped = textwrap::wrap(previous, width.max(1) as usize);
+            for (row, line) in (0..middle).rev().zip(wrapped.iter().rev()) {
+                queue!(w, cursor::MoveTo(col, row))?;
+                w.write_all(line.as_bytes())?;
+            }
+        }
+        let mut row = middle + 1;
+        for paragraph in text.get(end + 1..).unwrap_or_default().split('\n') {
+            for line in textwrap::wrap(paragraph, width.max(1) as usize) {
+                if row >= height {
+                    break;
+                }
+                queue!(w, cursor::MoveTo(col, row))?;
+                w.write_all(line.as_bytes())?;
+                row += 1;
+            }
+            row += 1;
+            if row >= height {
+                break;
+            }
+        }
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        self.context_drawn = Some(start);
+        Ok(())
+    }
+
+    /// Draws the word being typed in large letters above the text, if there
+    /// are rows kept for it. Between words, that's the next one.
+    fn render_large_word(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        if self.dimensions.top == 0 || self.reading.is_some() {
+            return Ok(());
+        }
+        let text = self.backend.text();
+        let cursor = self.backend.cursor();
+        let start = match text[cursor.bytes..].find(|c: char| !c.is_whitespace()) {
+            Some(0) | None => self.backend.word_start(cursor),
+            Some(skip) => cursor + Len::new(skip, text[cursor.bytes..][..skip].chars().count()),
+        };
+        let errors = self.backend.errors();
+        let mut pos = start;
+        let mut word = Vec::new();
+        for c in text[start.bytes..]
+            .chars()
+            .take_while(|c| !c.is_whitespace())
+        {
+            let look = match pos.chars.cmp(&cursor.chars) {
+                Ordering::Less if errors.binary_search(&pos).is_ok() => large::Look::Mistake,
+                Ordering::Less => large::Look::Typed,
+                Ordering::Equal => large::Look::Next,
+                Ordering::Greater => large::Look::Ahead,
+            };
+            word.push((c, look));
+            pos += Len::new(c.len_utf8(), 1);
+        }
+        large::render(
+            w,
+            self.content_starting_col(),
+            0,
+            self.content_width(),
+            &word,
+        )
+    }
+
+    /// Draws the progress bar or scrollbar down the right edge of the
+    /// screen, if enabled.
+    fn render_scrollbar(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        if !matches!(self.gutter, Gutter::Progress | Gutter::Errors) {
+            return Ok(());
+        }
+        let height = self.screen_height() as usize;
+        let len = self.backend.end().chars.max(1);
+        let row_of = |chars: usize| (chars * height / len).min(height.saturating_sub(1));
+        let mut mistakes = vec![0; height];
+        if self.gutter == Gutter::Errors {
+            for &i in self.backend.mistake_positions() {
+                mistakes[row_of(i)] += 1;
+            }
+        }
+        let chars_per_row = (len / height.max(1)).max(1) as f64;
+        let position = row_of(self.focus().chars);
+        let col = self.screen_width().saturating_sub(1);
+        let glyphs = caps::glyphs();
+        for (row, &mistakes) in mistakes.iter().enumerate() {
+            let (glyph, color) = match self.gutter {
+                Gutter::Progress if row <= position => (glyphs.thick_bar, None),
+                Gutter::Progress => (glyphs.bar, None),
+                _ => (
+                    if row == position {
+                        glyphs.block
+                    } else {
+                        glyphs.bar
+                    },
+                    match mistakes as f64 / chars_per_row {
+                        0.0 => None,
+                        d if d < 0.02 => Some(Color::Yellow),
+                        _ => Some(Color::Red),
+                    },
+                ),
+            };
+            queue!(w, cursor::MoveTo(col, row as u16))?;
+            match color {
+                Some(color) => queue!(w, SetForegroundColor(color))?,
+                None if glyph == glyphs.bar => queue!(w, SetAttribute(Attribute::Dim))?,
+                None => {}
+            }
+            w.write_all(glyph.as_bytes())?;
+            queue!(
+                w,
+                SetForegroundColor(Color::Reset),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        Ok(())
+    }
+
     fn render_range_in_line(
         &self,
         w: &mut impl Write,
@@ -452,10 +2405,25 @@ impl ChapterDisplay {
         start: Len,
         end: Len,
     ) -> anyhow::Result<()> {
-        queue!(
-            w,
-            cursor::MoveTo(self.content_starting_col() + start.chars as u16, line.row)
-        )?;
+        // Clip to the columns on screen.
+        let left = self.scroll as usize;
+        let right = left + self.content_width() as usize;
+        if start.chars >= right || end.chars <= left {
+            return Ok(());
+        }
+        let start = match start.chars < left {
+            true => self.offset_in_line(line.line, left),
+            false => start,
+        };
+        let end = match end.chars > right {
+            true => self.offset_in_line(line.line, right),
+            false => end,
+        };
+        if start > line.len() {
+            // Only the rest of a separator, which isn't drawn.
+            return Ok(());
+        }
+        let mut spans = SpanBuffer::at(self.col_of(start.chars as u16), line.row)?;
         let slice_end = end.min(line.len());
         let mut text = self.virtual_line_str(line.line)[start.bytes..slice_end.bytes].as_bytes();
         let mut cur_style = Style::empty();
@@ -463,44 +2431,28 @@ impl ChapterDisplay {
             .backend
             .style_iter(line.line.start + start, line.line.start + slice_end)
         {
-            for attr in (cur_style & !style)
-                .iter()
-                .filter_map(|s| match s {
-                    Style::BOLD => Some(Attribute::NormalIntensity),
-                    Style::ITALIC => Some(Attribute::NoItalic),
-                    _ => None,
-                })
-                .chain((style & !cur_style).iter().filter_map(|s| match s {
-                    Style::BOLD => Some(Attribute::Bold),
-                    Style::ITALIC => Some(Attribute::Italic),
-                    _ => None,
-                }))
-            {
-                crossterm::queue!(w, SetAttribute(attr))?;
-            }
-            w.write_all(&text[..len.bytes])?;
+            spans.push(style, &text[..len.bytes])?;
             text = &text[len.bytes..];
             cur_style = style;
         }
         if end > slice_end {
-            match line.line.linebreak {
-                Linebreak::Existing => write!(w, "{PARAGRAPH_TERMINATOR}")?,
-                Linebreak::Wrapped => w.write_all(b" ")?,
-                Linebreak::Eof => {}
-            }
+            let terminator = match line.line.linebreak {
+                Linebreak::Existing => caps::glyphs().paragraph_end,
+                Linebreak::Wrapped => " ",
+                Linebreak::Blank | Linebreak::Eof => "",
+            };
+            spans.push(cur_style, terminator.as_bytes())?;
         }
-        // TODO: this also disables error coloring
-        crossterm::queue!(w, SetAttribute(Attribute::Reset))?;
-        Ok(())
+        spans.finish(w)
     }
 
     fn line_difference(&self, current_line: usize) -> isize {
-        self.lines[current_line].line as isize - self.lines[self.previous_line].line as isize
+        current_line as isize - self.previous_line as isize
     }
 
     // true -> needs full render
     pub fn render_chapter(&mut self, w: &mut impl Write) -> anyhow::Result<bool> {
-        if self.needs_full_render {
+        if self.needs_full_render || self.reading.is_some() || self.update_scroll() {
             return Ok(true);
         }
         let (x, y) = self.to_virtual(self.backend.cursor().chars);
@@ -508,6 +2460,10 @@ impl ChapterDisplay {
         let Ok(lines_scrolled) = u16::try_from(line_diff.abs()) else {
             return Ok(true);
         };
+        if lines_scrolled > 0 && self.dimensions.top > 0 {
+            // Scrolling the terminal would take the large word with it.
+            return Ok(true);
+        }
 
         queue!(w, cursor::Hide)?;
 
@@ -524,6 +2480,15 @@ impl ChapterDisplay {
                 self.render_line(w, &line)?;
             }
         }
+        // Scrolling moves the context pane too.
+        if lines_scrolled > 0 || self.context_drawn != Some(self.paragraph_start()) {
+            self.render_context(w)?;
+        }
+        // Text scrolled into the top row may have covered the status, and the
+        // live figures change with every keystroke.
+        if lines_scrolled > 0 || self.status_window.is_some() {
+            self.render_status(w, Instant::now())?;
+        }
 
         // if self.cursor_prev.bytes >= self.line_starts[self.previous_line].end.bytes {
         //     let x = self.cursor_prev.chars - self.line_starts[self.previous_line].start.chars;
@@ -558,7 +2523,9 @@ impl ChapterDisplay {
             Some(self.backend.backspaced_errors())
         } {
             let range = match y.cmp(&self.previous_line) {
-                Ordering::Greater => self.middle_row() - lines_scrolled..=self.middle_row(),
+                Ordering::Greater => {
+                    self.middle_row().saturating_sub(lines_scrolled)..=self.middle_row()
+                }
                 _ => self.middle_row()..=self.middle_row() + lines_scrolled,
             };
             let mut cur = 0;
@@ -590,9 +2557,13 @@ impl ChapterDisplay {
             }
         }
 
+        self.render_pace(w)?;
+        self.render_scrollbar(w)?;
+        self.render_alert(w)?;
+        self.render_large_word(w)?;
         queue!(
             w,
-            cursor::MoveTo(self.content_starting_col() + x, self.middle_row()),
+            cursor::MoveTo(self.col_of(x), self.middle_row()),
             cursor::Show,
         )?;
 
@@ -603,15 +2574,29 @@ impl ChapterDisplay {
     }
 
     fn full_render_chapter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
-        let (x, _y) = self.to_virtual(self.backend.cursor().chars);
+        if self.paused.is_some() {
+            return self.render_paused(w);
+        }
+        self.update_scroll();
+        let (x, _y) = self.to_virtual(self.focus().chars);
 
         queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
         for line in self.screen_lines(..) {
             self.render_line(w, &line)?;
         }
+        self.pace_drawn = None;
+        if self.reading.is_none() {
+            self.render_pace(w)?;
+        }
+        self.render_context(w)?;
+        self.render_scrollbar(w)?;
+        self.render_alert(w)?;
+        self.status_drawn = None;
+        self.render_status(w, Instant::now())?;
+        self.render_large_word(w)?;
         queue!(
             w,
-            cursor::MoveTo(self.content_starting_col() + x, self.middle_row()),
+            cursor::MoveTo(self.col_of(x), self.middle_row()),
             cursor::Show,
         )?;
         w.flush()?;
@@ -619,27 +2604,637 @@ impl ChapterDisplay {
         Ok(())
     }
 
-    pub fn handle_input(&mut self, event: KeyEvent) -> anyhow::Result<()> {
-        match event {
+    /// How long until the status corner is due an update, or `None` if
+    /// there's nothing to show.
+    fn status_due(&self, now: Instant) -> Option<Duration> {
+        self.status?;
+        if self.paused.is_some() || self.reading.is_some() || self.backend.history().is_empty() {
+            return None;
+        }
+        Some(self.status_drawn.map_or(Duration::ZERO, |(at, _)| {
+            (at + STATUS_INTERVAL).saturating_duration_since(now)
+        }))
+    }
+
+    /// Draws the time since the first keystroke and the speed so far in the
+    /// top right corner, over whatever's there, followed by the speed and
+    /// accuracy over the last few seconds if they're shown. Leaves the terminal cursor
+    /// wherever it ends up.
+    fn render_status(&mut self, w: &mut impl Write, now: Instant) -> anyhow::Result<()> {
+        let Some(metric) = self.status else {
+            return Ok(());
+        };
+        let Some(&(first, _)) = self.backend.history().first() else {
+            return Ok(());
+        };
+        if self.paused.is_some() || self.reading.is_some() {
+            return Ok(());
+        }
+        let stats = stats::Stats {
+            elapsed: now.saturating_duration_since(first),
+            ..self.backend.stats()
+        };
+        let mut text = format!(
+            " {} · {} ",
+            stats::format_duration(stats.elapsed),
+            stats.format_speed(metric)
+        );
+        if let Some(window) = self.status_window {
+            let recent = self.backend.recent_stats(window, now);
+            text += &format!(
+                "· last {}s {} {:.0}% ",
+                window.as_secs(),
+                recent.format_speed(metric),
+                recent.accuracy() * 100.0
+            );
+        }
+        // Cover anything left over from a wider status.
+        let width = text
+            .chars()
+            .count()
+            .max(self.status_drawn.map_or(0, |(_, width)| width));
+        let col = self.screen_width().saturating_sub(width as u16);
+        queue!(w, cursor::MoveTo(col, 0), SetAttribute(Attribute::Dim))?;
+        write!(w, "{text:>width$}")?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        self.status_drawn = Some((now, width));
+        Ok(())
+    }
+
+    /// The text dimmed and without highlighting, while the terminal doesn't
+    /// have focus.
+    fn render_paused(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        queue!(
+            w,
+            cursor::Hide,
+            terminal::Clear(terminal::ClearType::All),
+            SetAttribute(Attribute::Dim)
+        )?;
+        self.update_scroll();
+        let left = self.scroll as usize;
+        let right = left + self.content_width() as usize;
+        for line in self.screen_lines(..) {
+            queue!(w, cursor::MoveTo(self.content_starting_col(), line.row))?;
+            let start = self.offset_in_line(line.line, left).min(line.len());
+            let end = self.offset_in_line(line.line, right).min(line.len());
+            let text = &self.virtual_line_str(line.line)[start.bytes..end.bytes];
+            w.write_all(text.as_bytes())?;
+        }
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        w.flush()?;
+        self.needs_full_render = false;
+        Ok(())
+    }
+
+    /// Stops the clock until [`resume`](Self::resume).
+    fn pause(&mut self) {
+        if self.paused.is_none() {
+            self.paused = Some(Instant::now());
+            self.needs_full_render = true;
+        }
+    }
+
+    /// Starts the clock again, leaving the time paused out of the session.
+    fn resume(&mut self) {
+        let Some(since) = self.paused.take() else {
+            return;
+        };
+        let away = since.elapsed();
+        self.backend.postpone(away);
+        if let Some((_, Some(first))) = &mut self.replay {
+            *first += away;
+        }
+        if let Some(pace) = &mut self.pace {
+            pace.postpone(away);
+        }
+        self.needs_full_render = true;
+    }
+
+    /// Rings the bell or flashes the screen edges for a fresh mistake.
+    /// Leaves the terminal cursor wherever it ends up.
+    fn render_alert(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        if std::mem::take(&mut self.ring_bell) {
+            w.write_all(b"\x07")?;
+        }
+        if self.flash.is_none() {
+            return Ok(());
+        }
+        let right = self.screen_width().saturating_sub(1);
+        queue!(w, SetBackgroundColor(Color::Red))?;
+        for row in 0..self.screen_height() {
+            queue!(w, cursor::MoveTo(0, row))?;
+            w.write_all(b" ")?;
+            queue!(w, cursor::MoveTo(right, row))?;
+            w.write_all(b" ")?;
+        }
+        queue!(w, SetBackgroundColor(Color::Reset))?;
+        Ok(())
+    }
+
+    /// Types `event`, timed by when it arrived at `received` rather than
+    /// when it's got to, so keys queued behind a slow frame keep their
+    /// spacing.
+    pub fn handle_input(&mut self, event: KeyEvent, received: Instant) -> anyhow::Result<()> {
+        // Some terminals don't report focus coming back.
+        self.resume();
+        if let KeyEvent {
+            code: KeyCode::Tab, ..
+        } = event
+        {
+            self.reading = match self.reading {
+                Some(_) => None,
+                None => Some(self.backend.cursor()),
+            };
+            self.needs_full_render = true;
+            return Ok(());
+        }
+
+        if let Some(caret) = self.reading {
+            return self.handle_reading_input(caret, event);
+        }
+
+        let expected = self.backend.text()[self.backend.cursor().bytes..]
+            .chars()
+            .next();
+        let actions = self.translator.feed(event, expected);
+        if actions.is_empty() {
+            return Ok(());
+        }
+        let mistakes = self.backend.stats().mistakes;
+        self.backend.set_clock(received);
+        for action in actions {
+            if let Some((replay, first)) = &mut self.replay {
+                replay.record(received - *first.get_or_insert(received), action);
+            }
+            self.apply(action);
+        }
+        if self.backend.stats().mistakes > mistakes {
+            match self.alert {
+                Alert::None => {}
+                Alert::Bell => self.ring_bell = true,
+                Alert::Flash => self.flash = Some(Animation::new(FLASH_DURATION)),
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, action: Action) {
+        self.backend.apply(action);
+        if let Action::RestartParagraph | Action::RetryError = action {
+            // Could be anywhere back in the chapter.
+            self.needs_full_render = true;
+        }
+        let cursor = self.backend.cursor();
+        if let Some(pace) = &mut self.pace {
+            if !pace.is_running_at(cursor) {
+                let end = self.backend.next_paragraph(cursor);
+                pace.start_paragraph(cursor, end, &self.backend.stats());
+            }
+        }
+    }
+
+    /// Lines the next partial render will scroll the text up by, or down
+    /// by if negative.
+    fn pending_scroll(&self) -> isize {
+        let (_, y) = self.to_virtual(self.backend.cursor().chars);
+        self.line_difference(y)
+    }
+
+    /// Redraws the text on `rows`, wiping anything drawn over it.
+    fn redraw_rows(&mut self, w: &mut impl Write, rows: Range<u16>) -> anyhow::Result<()> {
+        queue!(w, cursor::SavePosition)?;
+        for row in rows.clone() {
+            queue!(
+                w,
+                cursor::MoveTo(0, row),
+                terminal::Clear(terminal::ClearType::CurrentLine)
+            )?;
+        }
+        for line in self.screen_lines(rows.clone()) {
+            self.render_line(w, &line)?;
+        }
+        let pace_wiped = self.pace_drawn.is_some_and(|pos| {
+            self.screen_lines(rows.clone()).any(|l| {
+                l.line.start.chars <= pos.chars
+                    && pos.chars < (l.line.end + l.line.separator_len).chars
+            })
+        });
+        if pace_wiped {
+            self.pace_drawn = None;
+            if self.reading.is_none() {
+                self.render_pace(w)?;
+            }
+        }
+        self.render_context(w)?;
+        self.render_scrollbar(w)?;
+        if rows.start < self.dimensions.top {
+            self.render_large_word(w)?;
+        }
+        if rows.contains(&0) {
+            self.status_drawn = None;
+            self.render_status(w, Instant::now())?;
+        }
+        queue!(w, cursor::RestorePosition)?;
+        Ok(())
+    }
+
+    fn is_animating(&self, now: Instant) -> bool {
+        self.paused.is_none()
+            && (self.flash.is_some()
+                || self.reading.is_none() && self.pace.as_ref().is_some_and(|p| p.is_moving(now)))
+    }
+
+    fn tick(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        let now = Instant::now();
+        if self.flash.is_some_and(|f| f.is_finished(now)) {
+            self.flash = None;
+            self.redraw_rows(w, 0..self.screen_height())?;
+            w.flush()?;
+        }
+        if self.status_due(now).is_some_and(|due| due.is_zero()) {
+            self.render_status(w, now)?;
+            let (x, _) = self.to_virtual(self.backend.cursor().chars);
+            queue!(w, cursor::MoveTo(self.col_of(x), self.middle_row()),)?;
+            w.flush()?;
+        }
+        if self.reading.is_some() || self.pace.is_none() {
+            return Ok(());
+        }
+        let (x, _) = self.to_virtual(self.backend.cursor().chars);
+        self.render_pace(w)?;
+        queue!(
+            w,
+            cursor::MoveTo(self.col_of(x), self.middle_row()),
+            cursor::Show,
+        )?;
+        w.flush()?;
+        Ok(())
+    }
+
+    /// Moves the pace caret to where it should be now, if it has moved.
+    /// Leaves the terminal cursor wherever it ends up.
+    fn render_pace(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        let pos = self
+            .pace
+            .as_ref()
+            .and_then(|p| p.position(self.backend.text(), Instant::now()));
+        if pos == self.pace_drawn {
+            return Ok(());
+        }
+        if let Some(old) = self.pace_drawn.take() {
+            self.render_cell(w, old, false)?;
+        }
+        if let Some(pos) = pos {
+            self.render_cell(w, pos, true)?;
+            self.pace_drawn = Some(pos);
+        }
+        Ok(())
+    }
+
+    /// Redraws the character at `pos`, if it's on screen.
+    fn render_cell(&self, w: &mut impl Write, pos: Len, pace: bool) -> anyhow::Result<()> {
+        let Some(line) = self.screen_lines(..).find(|l| {
+            l.line.start.chars <= pos.chars && pos.chars < (l.line.end + l.line.separator_len).chars
+        }) else {
+            return Ok(());
+        };
+        let Some(c) = self.backend.text()[pos.bytes..].chars().next() else {
+            return Ok(());
+        };
+        let x = pos - line.line.start;
+        let len = Len::new(c.len_utf8(), 1);
+        if x.chars > line.len().chars {
+            // Past the first character of a separator, which isn't drawn.
+            return Ok(());
+        }
+        if pace {
+            queue!(
+                w,
+                SetAttribute(Attribute::Underlined),
+                SetForegroundColor(Color::Cyan)
+            )?;
+            self.render_range_in_line(w, &line, x, x + len)
+        } else if self.backend.errors().binary_search(&pos).is_ok() {
+            self.with_error(w, |w| self.render_range_in_line(w, &line, x, x + len))
+        } else {
+            self.render_range_in_line(w, &line, x, x + len)
+        }
+    }
+
+    fn start_typing_at(&mut self, pos: Len) {
+        self.backend.start_at(pos);
+        self.pastes = 0;
+        if let Some((replay, first)) = &mut self.replay {
+            replay.start = pos.chars;
+            replay.events.clear();
+            *first = None;
+        }
+        if let Some(pace) = &mut self.pace {
+            pace.stop();
+        }
+        self.previous_line = self.char_index_to_virtual_line(pos.chars);
+        self.reading = None;
+        self.needs_full_render = true;
+    }
+
+    fn handle_reading_input(&mut self, caret: Len, event: KeyEvent) -> anyhow::Result<()> {
+        let backend = &self.backend;
+        let caret = match event {
             KeyEvent {
-                code: KeyCode::Backspace | KeyCode::Char('w'),
+                code: KeyCode::Left,
                 modifiers: KeyModifiers::CONTROL,
                 ..
-            } => self.backend.delete_word_backwards(),
+            } => backend.previous_word(caret),
+            KeyEvent {
+                code: KeyCode::Right,
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => backend.next_word(caret),
+            KeyEvent {
+                code: KeyCode::Left,
+                ..
+            } => backend.previous_char(caret),
+            KeyEvent {
+                code: KeyCode::Right,
+                ..
+            } => backend.next_char(caret),
+            KeyEvent {
+                code: KeyCode::Char('{'),
+                ..
+            } => backend.previous_paragraph(caret),
             KeyEvent {
-                code: KeyCode::Backspace,
+                code: KeyCode::Char('}'),
                 ..
-            } => self.backend.pop(),
+            } => backend.next_paragraph(caret),
             KeyEvent {
                 code: KeyCode::Enter,
                 ..
-            } => self.backend.push('\n'),
+            } => {
+                self.start_typing_at(caret);
+                return Ok(());
+            }
             KeyEvent {
-                code: KeyCode::Char(c),
+                code: KeyCode::Char('s'),
                 ..
-            } => self.backend.push(c),
-            _ => {}
-        }
+            } => {
+                self.start_typing_at(backend.sentence_start(caret));
+                return Ok(());
+            }
+            _ => caret,
+        };
+        self.reading = Some(caret);
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        backend::{BackspaceMode, ChapterText},
+        keymap::Keymap,
+        latency,
+        translate::InputMethod,
+    };
+
+    #[test]
+    fn text_column_respects_margins() {
+        let config = Config {
+            width: 60,
+            margin_left: 4,
+            column: Column::Left,
+            ..Config::default()
+        };
+        assert_eq!(text_column(&config, 100), (6, 60, 60));
+        // Too narrow, so the text scrolls sideways.
+        assert_eq!(text_column(&config, 40), (6, 32, 60));
+
+        let config = Config {
+            max_width: 0.5,
+            column: Column::Right,
+            ..config
+        };
+        assert_eq!(text_column(&config, 100), (48, 50, 50));
+    }
+
+    #[test]
+    fn paragraphs_are_separated_by_blank_lines() {
+        let lines = ChapterDisplay::wrap_text("one two three\nfour", 8);
+        let kinds: Vec<Linebreak> = lines.iter().map(|l| l.linebreak).collect();
+        assert_eq!(
+            kinds,
+            [
+                Linebreak::Wrapped,
+                Linebreak::Existing,
+                Linebreak::Blank,
+                Linebreak::Eof
+            ]
+        );
+        // The line break itself belongs to the paragraph's last line.
+        let newline = 13;
+        assert_eq!(lines.partition_point(|l| l.end.chars < newline), 1);
+        assert_eq!(lines.partition_point(|l| l.end.chars < newline + 1), 3);
+    }
+
+    #[test]
+    fn narrow_screens_scroll_to_keep_the_cursor_in_view() {
+        let text = "It was on a dreary night of November that I beheld the \
+            accomplishment of my toils.";
+        let backend = Backend::from_text(
+            ChapterText {
+                text: text.to_owned(),
+                styling: crate::style::Styling::builder().build(),
+                blocks: Vec::new(),
+            },
+            BackspaceMode::Unlimited,
+        );
+        let dimensions = Arc::new(Dimensions {
+            screen_size: (20, 10),
+            anchor: (2, 5),
+            width: 16,
+            wrap: 40,
+            context: None,
+            top: 0,
+        });
+        let mut display = ChapterDisplay::from_backend(
+            dimensions,
+            backend,
+            None,
+            Gutter::None,
+            InputMethod::Keyboard.translator(&Keymap::default()),
+        );
+        // Lines break where they would on a wide enough screen.
+        assert_eq!(display.lines[0].end.chars, 36);
+        let mut w = Vec::new();
+        display.full_render_chapter(&mut w).unwrap();
+        for c in text.chars() {
+            display
+                .handle_input(
+                    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+                    Instant::now(),
+                )
+                .unwrap();
+            if display.render_chapter(&mut w).unwrap() {
+                display.full_render_chapter(&mut w).unwrap();
+            }
+            let (x, _) = display.to_virtual(display.backend.cursor().chars);
+            let col = display.col_of(x);
+            assert!(x < 4 || (6..14).contains(&col), "cursor at column {col}");
+        }
+    }
+
+    #[test]
+    fn list_entries_are_laid_out_around_the_selection() {
+        assert_eq!(list_layout(&[1, 3, 2, 1], 1, 5), [2, 4, 8, 11]);
+        // Long names above the top of a short screen go off it rather than
+        // wrapping around.
+        assert_eq!(list_layout(&[4, 4, 1], 2, 1), [-9, -4, 1]);
+        assert!(list_layout(&[], 0, 3).is_empty());
+    }
+
+    #[test]
+    fn resizing_wraps_the_text_again() {
+        let text = "It was on a dreary night of November that I beheld the \
+            accomplishment of my toils.";
+        let backend = Backend::from_text(
+            ChapterText {
+                text: text.to_owned(),
+                styling: crate::style::Styling::builder().build(),
+                blocks: Vec::new(),
+            },
+            BackspaceMode::Unlimited,
+        );
+        let config = Config {
+            width: 40,
+            ..Config::default()
+        };
+        let mut display = ChapterDisplay::from_backend(
+            Arc::new(Dimensions::new(&config, 80, 10)),
+            backend,
+            None,
+            Gutter::None,
+            InputMethod::Keyboard.translator(&Keymap::default()),
+        );
+        assert_eq!(display.lines[0].end.chars, 36);
+        let mut w = Vec::new();
+        display.full_render_chapter(&mut w).unwrap();
+        for c in text.chars().take(50) {
+            display
+                .handle_input(
+                    KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE),
+                    Instant::now(),
+                )
+                .unwrap();
+        }
+        display.render_chapter(&mut w).unwrap();
+
+        display.set_dimensions(Arc::new(Dimensions::new(&config, 30, 10)));
+        // Too narrow for the configured width, so wrapped as before.
+        assert_eq!(display.lines[0].end.chars, 36);
+        display.set_dimensions(Arc::new(Dimensions::new(
+            &Config {
+                max_width: 0.25,
+                ..config
+            },
+            80,
+            10,
+        )));
+        assert_eq!(display.lines[0].end.chars, 18);
+        assert_eq!(display.previous_line, 2);
+        assert!(display.render_chapter(&mut w).unwrap());
+    }
+
+    #[test]
+    fn keys_are_timed_by_when_they_arrived() {
+        let text = "It was on a dreary night of November that I beheld the \
+                    accomplishment of my toils.";
+        let backend = Backend::from_text(
+            ChapterText {
+                text: text.to_owned(),
+                styling: crate::style::Styling::builder().build(),
+                blocks: Vec::new(),
+            },
+            BackspaceMode::Unlimited,
+        );
+        let mut display = ChapterDisplay::from_backend(
+            Arc::new(Dimensions::new(&Config::default(), 80, 10)),
+            backend,
+            None,
+            Gutter::None,
+            InputMethod::Keyboard.translator(&Keymap::default()),
+        );
+        // Keys that arrived at a human pace, all handled at once as if they'd
+        // queued up behind a slow frame.
+        let base = Instant::now();
+        let mut at = Duration::ZERO;
+        for (i, c) in text.chars().enumerate() {
+            at += Duration::from_millis([140, 95, 210, 160, 120][i % 5]);
+            let event = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            display.handle_input(event, base + at).unwrap();
+        }
+        let intervals = display.backend.intervals();
+        assert!(intervals.count() >= 30);
+        assert_eq!(intervals.suspicion(), None);
+    }
+
+    // Timing depends on the machine, so this is run on demand with
+    // `cargo test --release -- --ignored` rather than with the rest.
+    #[test]
+    #[ignore]
+    fn typical_keystrokes_render_within_budget() {
+        let paragraph = "It was on a dreary night of November that I beheld the \
+            accomplishment of my toils. With an anxiety that almost amounted to \
+            agony, I collected the instruments of life around me.";
+        let text = vec![paragraph; 20].join("\n");
+        let backend = Backend::from_text(
+            ChapterText {
+                text: text.clone(),
+                styling: crate::style::Styling::builder().build(),
+                blocks: Vec::new(),
+            },
+            BackspaceMode::Unlimited,
+        );
+        let dimensions = Arc::new(Dimensions {
+            screen_size: (120, 40),
+            anchor: (30, 20),
+            width: 60,
+            wrap: 60,
+            context: None,
+            top: 0,
+        });
+        let mut display = ChapterDisplay::from_backend(
+            dimensions,
+            backend,
+            None,
+            Gutter::None,
+            InputMethod::Keyboard.translator(&Keymap::default()),
+        );
+        let mut w = Vec::new();
+        display.full_render_chapter(&mut w).unwrap();
+
+        let mut latency = Latency::default();
+        // Mostly correct typing with the odd mistake, like a real session.
+        for (i, c) in text.chars().take(2000).enumerate() {
+            let c = if i % 37 == 0 { '#' } else { c };
+            let started = Instant::now();
+            let code = match c {
+                '\n' => KeyCode::Enter,
+                c => KeyCode::Char(c),
+            };
+            display
+                .handle_input(KeyEvent::new(code, KeyModifiers::NONE), started)
+                .unwrap();
+            if display.render_chapter(&mut w).unwrap() {
+                display.full_render_chapter(&mut w).unwrap();
+            }
+            latency.record(started.elapsed());
+            w.clear();
+        }
+        let p95 = latency.percentile(95.0).unwrap_or(Duration::ZERO);
+        assert!(
+            p95 <= latency::BUDGET,
+            "p95 keystroke render took {p95:?}, over the {:?} budget",
+            latency::BUDGET
+        );
+    }
+}
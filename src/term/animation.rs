@@ -0,0 +1,60 @@
+//! Timing for whatever changes on screen without a key press: the pace
+//! caret, toasts, countdowns.
+
+use std::time::{Duration, Instant};
+
+/// Something that plays out over a fixed time from when it was created.
+#[derive(Debug, Clone, Copy)]
+pub struct Animation {
+    started: Instant,
+    duration: Duration,
+}
+
+impl Animation {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            duration,
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        (self.started + self.duration).saturating_duration_since(now)
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.remaining(now).is_zero()
+    }
+}
+
+/// Limits how often animations are redrawn.
+#[derive(Debug)]
+pub struct FrameClock {
+    interval: Duration,
+    last: Option<Instant>,
+}
+
+impl FrameClock {
+    pub fn new(max_fps: u32) -> Self {
+        Self {
+            interval: Duration::from_secs(1) / max_fps.max(1),
+            last: None,
+        }
+    }
+
+    /// How long until the next frame may be drawn.
+    pub fn until_next(&self, now: Instant) -> Duration {
+        self.last.map_or(Duration::ZERO, |last| {
+            (last + self.interval).saturating_duration_since(now)
+        })
+    }
+
+    /// Starts a frame if one is due, returning whether it did.
+    pub fn start_frame(&mut self, now: Instant) -> bool {
+        if !self.until_next(now).is_zero() {
+            return false;
+        }
+        self.last = Some(now);
+        true
+    }
+}
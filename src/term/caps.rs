@@ -0,0 +1,254 @@
+//! What the terminal can show, worked out once at startup from the
+//! environment, so drawing can fall back to plainer output where fancier
+//! output would come out garbled.
+
+use std::{io::Write, sync::OnceLock};
+
+use crossterm::{cursor, queue};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// `NO_COLOR` is set, or the terminal is dumb.
+    None,
+    /// The 16 standard colours.
+    Basic,
+    Ansi256,
+    TrueColor,
+}
+
+/// Protocols for drawing images in the terminal.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Graphics {
+    Kitty,
+    Sixel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    pub color: ColorDepth,
+    pub graphics: Option<Graphics>,
+    /// Whether box drawing, block elements and other symbols outside ASCII
+    /// come out one column wide. Not so on the Linux console, which lacks
+    /// the glyphs, or in non-UTF-8 locales.
+    pub unicode: bool,
+}
+
+impl Capabilities {
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok().filter(|v| !v.is_empty()))
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let term = var("TERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+
+        let color = if var("NO_COLOR").is_some() || term == "dumb" {
+            ColorDepth::None
+        } else if var("COLORTERM").is_some_and(|c| c == "truecolor" || c == "24bit") {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Basic
+        };
+
+        let graphics = if var("KITTY_WINDOW_ID").is_some()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+            || program == "WezTerm"
+        {
+            Some(Graphics::Kitty)
+        } else if term.contains("sixel")
+            || term == "foot"
+            || term.starts_with("mlterm")
+            || program == "iTerm.app"
+        {
+            Some(Graphics::Sixel)
+        } else {
+            None
+        };
+
+        let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+            .into_iter()
+            .find_map(&var)
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        let utf8 = locale.contains("utf-8") || locale.contains("utf8");
+        // Windows has no locale variables, and its terminals cope with
+        // Unicode, bar the old console host outside Windows Terminal.
+        let windows = cfg!(windows) && (var("WT_SESSION").is_some() || !program.is_empty());
+        let unicode = term != "linux" && term != "dumb" && (utf8 || windows);
+
+        Self {
+            color,
+            graphics,
+            unicode,
+        }
+    }
+}
+
+/// What the terminal was found to support.
+pub fn get() -> &'static Capabilities {
+    static CAPABILITIES: OnceLock<Capabilities> = OnceLock::new();
+    CAPABILITIES.get_or_init(Capabilities::detect)
+}
+
+/// Characters for the sides and corners of a box.
+pub struct Frame {
+    pub top_left: &'static str,
+    pub top_right: &'static str,
+    pub bottom_left: &'static str,
+    pub bottom_right: &'static str,
+    pub horizontal: &'static str,
+    pub vertical: &'static str,
+}
+
+impl Frame {
+    /// Draws a box around `width` by `height` cells, its top left corner at
+    /// `(left, top)`.
+    pub fn draw(
+        &self,
+        w: &mut impl Write,
+        left: u16,
+        top: u16,
+        width: usize,
+        height: u16,
+    ) -> anyhow::Result<()> {
+        let horizontal = self.horizontal.repeat(width);
+        queue!(w, cursor::MoveTo(left, top))?;
+        write!(w, "{}{horizontal}{}", self.top_left, self.top_right)?;
+        for row in 1..=height {
+            queue!(w, cursor::MoveTo(left, top + row))?;
+            write!(w, "{}{}{}", self.vertical, " ".repeat(width), self.vertical)?;
+        }
+        queue!(w, cursor::MoveTo(left, top + height + 1))?;
+        write!(w, "{}{horizontal}{}", self.bottom_left, self.bottom_right)?;
+        Ok(())
+    }
+}
+
+const ASCII_FRAME: Frame = Frame {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    horizontal: "-",
+    vertical: "|",
+};
+
+/// Symbols drawn around the text, each one column wide.
+pub struct Glyphs {
+    /// Shown at the end of each paragraph.
+    pub paragraph_end: &'static str,
+    pub frame: Frame,
+    pub rounded_frame: Frame,
+    pub double_frame: Frame,
+    pub bar: &'static str,
+    pub thick_bar: &'static str,
+    pub block: &'static str,
+    /// Levels of a sparkline, lowest first.
+    pub sparks: [char; 8],
+    pub star: &'static str,
+    /// Marks chapters typed all the way through.
+    pub check: &'static str,
+    /// Marks chapters typed part of the way through.
+    pub partial: &'static str,
+}
+
+const UNICODE: Glyphs = Glyphs {
+    paragraph_end: "¬",
+    frame: Frame {
+        top_left: "┌",
+        top_right: "┐",
+        bottom_left: "└",
+        bottom_right: "┘",
+        horizontal: "─",
+        vertical: "│",
+    },
+    rounded_frame: Frame {
+        top_left: "╭",
+        top_right: "╮",
+        bottom_left: "╰",
+        bottom_right: "╯",
+        horizontal: "─",
+        vertical: "│",
+    },
+    double_frame: Frame {
+        top_left: "╔",
+        top_right: "╗",
+        bottom_left: "╚",
+        bottom_right: "╝",
+        horizontal: "═",
+        vertical: "║",
+    },
+    bar: "│",
+    thick_bar: "┃",
+    block: "█",
+    sparks: ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'],
+    star: "✦",
+    check: "✓",
+    partial: "◐",
+};
+
+const ASCII: Glyphs = Glyphs {
+    paragraph_end: "~",
+    frame: ASCII_FRAME,
+    rounded_frame: ASCII_FRAME,
+    double_frame: Frame {
+        top_left: "#",
+        top_right: "#",
+        bottom_left: "#",
+        bottom_right: "#",
+        horizontal: "=",
+        vertical: "#",
+    },
+    bar: "|",
+    thick_bar: "#",
+    block: "#",
+    sparks: ['_', '.', ',', ':', '-', '=', '*', '#'],
+    star: "*",
+    check: "+",
+    partial: "~",
+};
+
+/// Glyphs the terminal can show.
+pub fn glyphs() -> &'static Glyphs {
+    match get().unicode {
+        true => &UNICODE,
+        false => &ASCII,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> Capabilities {
+        Capabilities::from_env(|name| {
+            vars.iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, v)| v.to_string())
+        })
+    }
+
+    #[test]
+    fn capabilities_come_from_the_environment() {
+        let kitty = detect(&[
+            ("TERM", "xterm-kitty"),
+            ("COLORTERM", "truecolor"),
+            ("LANG", "en_GB.UTF-8"),
+        ]);
+        assert_eq!(kitty.color, ColorDepth::TrueColor);
+        assert_eq!(kitty.graphics, Some(Graphics::Kitty));
+        assert!(kitty.unicode);
+
+        let console = detect(&[("TERM", "linux"), ("LANG", "en_US.UTF-8")]);
+        assert_eq!(console.color, ColorDepth::Basic);
+        assert_eq!(console.graphics, None);
+        assert!(!console.unicode);
+
+        let plain = detect(&[("TERM", "xterm-256color"), ("NO_COLOR", "1"), ("LANG", "C")]);
+        assert_eq!(plain.color, ColorDepth::None);
+        assert!(!plain.unicode);
+    }
+}
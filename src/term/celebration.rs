@@ -0,0 +1,116 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+    terminal,
+};
+
+use super::{caps, Dimensions, DisplayState};
+use crate::stats::{self, Totals};
+
+const INNER_WIDTH: usize = 40;
+/// Rows between the top and bottom borders.
+const INNER_HEIGHT: u16 = 8;
+
+/// Shown once, when the last untyped part of a book has been typed.
+pub struct Celebration {
+    dimensions: Arc<Dimensions>,
+    title: String,
+    chapters: usize,
+    totals: Totals,
+}
+
+impl DisplayState for Celebration {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Celebration {
+    pub fn new(
+        dimensions: Arc<Dimensions>,
+        title: String,
+        chapters: usize,
+        totals: Totals,
+    ) -> Self {
+        Self {
+            dimensions,
+            title,
+            chapters,
+            totals,
+        }
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let inner = INNER_WIDTH.min(self.screen_width().saturating_sub(4) as usize);
+        let left = self.screen_width().saturating_sub(inner as u16 + 4) / 2;
+        let top = self.middle_row().saturating_sub(INNER_HEIGHT / 2 + 1);
+
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+        let glyphs = caps::glyphs();
+        queue!(w, SetForegroundColor(Color::Yellow))?;
+        glyphs
+            .double_frame
+            .draw(w, left, top, inner + 2, INNER_HEIGHT)?;
+        queue!(w, SetForegroundColor(Color::Reset))?;
+
+        let lines = [
+            (
+                1,
+                Attribute::Bold,
+                Color::Yellow,
+                format!("{0} book finished {0}", glyphs.star),
+            ),
+            (3, Attribute::Bold, Color::Reset, self.title.clone()),
+            (
+                5,
+                Attribute::Reset,
+                Color::Reset,
+                format!(
+                    "{} chapters · {} typing",
+                    self.chapters,
+                    stats::format_duration(self.totals.elapsed)
+                ),
+            ),
+            (
+                6,
+                Attribute::Reset,
+                Color::Green,
+                format!(
+                    "{:.0} wpm · {:.1}% accuracy overall",
+                    self.totals.wpm(),
+                    self.totals.accuracy() * 100.0
+                ),
+            ),
+            (
+                8,
+                Attribute::Dim,
+                Color::Reset,
+                "enter to continue".to_owned(),
+            ),
+        ];
+        for (row, attr, color, text) in lines {
+            let text: String = text.chars().take(inner).collect();
+            let pad = (inner - text.chars().count()) as u16 / 2;
+            queue!(
+                w,
+                cursor::MoveTo(left + 2 + pad, top + row),
+                SetAttribute(attr),
+                SetForegroundColor(color),
+            )?;
+            w.write_all(text.as_bytes())?;
+            queue!(
+                w,
+                SetForegroundColor(Color::Reset),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
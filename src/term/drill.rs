@@ -0,0 +1,166 @@
+use std::{
+    io::Write,
+    ops::Range,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+    terminal,
+};
+
+use super::{Dimensions, DisplayState};
+use crate::{
+    stats,
+    warmup::{self, Warmup},
+};
+
+/// Lines of the drill shown at once, starting from the one being typed.
+const SHOWN_LINES: usize = 3;
+
+/// Timed warm-up typed before the first chapter.
+pub struct Drill {
+    dimensions: Arc<Dimensions>,
+    text: Vec<char>,
+    /// Whether each character typed so far was right.
+    typed: Vec<bool>,
+    /// Set by the first keystroke, which starts the clock.
+    started: Option<Instant>,
+    keystrokes: usize,
+    mistakes: usize,
+    /// Where to open the chapter once the drill is over.
+    pub start: usize,
+}
+
+impl DisplayState for Drill {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Drill {
+    pub fn new(dimensions: Arc<Dimensions>, text: &str, start: usize) -> Self {
+        Self {
+            dimensions,
+            text: text.chars().collect(),
+            typed: Vec::new(),
+            started: None,
+            keystrokes: 0,
+            mistakes: 0,
+            start,
+        }
+    }
+
+    pub fn handle_input(&mut self, event: KeyEvent) {
+        match event.code {
+            KeyCode::Backspace => drop(self.typed.pop()),
+            KeyCode::Char(c) if self.typed.len() < self.text.len() => {
+                self.started.get_or_insert_with(Instant::now);
+                let correct = self.text[self.typed.len()] == c;
+                self.keystrokes += 1;
+                self.mistakes += !correct as usize;
+                self.typed.push(correct);
+            }
+            _ => {}
+        }
+    }
+
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.started
+            .map_or(Duration::ZERO, |started| now - started)
+            .min(warmup::DURATION)
+    }
+
+    pub fn is_finished(&self, now: Instant) -> bool {
+        self.elapsed(now) >= warmup::DURATION || self.typed.len() == self.text.len()
+    }
+
+    /// Time until the countdown next changes, once it has started.
+    pub fn next_tick(&self, now: Instant) -> Option<Duration> {
+        self.started?;
+        let remaining = warmup::DURATION - self.elapsed(now);
+        Some(Duration::from_nanos(
+            (remaining.as_nanos() % Duration::from_secs(1).as_nanos()) as u64,
+        ))
+    }
+
+    pub fn result(&self, now: Instant, timestamp: u64) -> Warmup {
+        Warmup {
+            timestamp,
+            chars: self.typed.iter().filter(|&&correct| correct).count(),
+            elapsed_ms: self.elapsed(now).as_millis() as u64,
+            keystrokes: self.keystrokes,
+            mistakes: self.mistakes,
+        }
+    }
+
+    /// Character ranges of the text wrapped to the content width, each
+    /// including the space after it.
+    fn lines(&self) -> Vec<Range<usize>> {
+        let width = self.content_width() as usize;
+        let mut lines = Vec::new();
+        let mut start = 0;
+        let mut end = 0;
+        for word in self.text.split(|&c| c == ' ') {
+            let word_end = end + word.len();
+            if word_end - start > width && end > start {
+                lines.push(start..end);
+                start = end;
+            }
+            end = (word_end + 1).min(self.text.len());
+        }
+        lines.push(start..end);
+        lines
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let now = Instant::now();
+        let left = self.content_starting_col();
+        let top = self.middle_row().saturating_sub(2);
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        let remaining = (warmup::DURATION - self.elapsed(now)).as_secs_f64().ceil();
+        let heading = match self.started {
+            Some(started) => format!(
+                "warm-up · {remaining:.0}s · {:.0} wpm",
+                stats::wpm(self.typed.iter().filter(|&&c| c).count(), now - started)
+            ),
+            None => "warm-up · start typing · esc to skip".to_owned(),
+        };
+        queue!(w, cursor::MoveTo(left, top), SetAttribute(Attribute::Dim))?;
+        w.write_all(heading.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        let cursor = self.typed.len();
+        let lines = self.lines();
+        let current = lines
+            .iter()
+            .position(|line| line.contains(&cursor))
+            .unwrap_or(lines.len() - 1);
+        for (row, line) in lines[current..].iter().take(SHOWN_LINES).enumerate() {
+            queue!(w, cursor::MoveTo(left, top + 2 + row as u16))?;
+            for i in line.clone() {
+                let (color, attr) = match self.typed.get(i) {
+                    Some(true) => (Color::Reset, Attribute::Reset),
+                    Some(false) => (Color::Red, Attribute::Reset),
+                    None if i == cursor => (Color::Reset, Attribute::Underlined),
+                    None => (Color::Reset, Attribute::Dim),
+                };
+                queue!(w, SetForegroundColor(color), SetAttribute(attr))?;
+                write!(w, "{}", self.text[i])?;
+                queue!(w, SetAttribute(Attribute::Reset))?;
+            }
+        }
+        queue!(w, SetForegroundColor(Color::Reset))?;
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+    terminal,
+};
+
+use super::{caps, Dimensions, DisplayState};
+use crate::{date, goal::Schedule, shelf::Resume, stats::Totals};
+
+/// Columns given to chapter names in the speed chart.
+const NAME_WIDTH: usize = 20;
+/// Rows above the speed chart.
+const HEADER_ROWS: u16 = 10;
+
+pub struct ChapterSpeed {
+    pub name: String,
+    /// Average over every session in the chapter.
+    pub wpm: f64,
+}
+
+pub struct Details {
+    pub title: String,
+    pub author: Option<String>,
+    pub chapters: usize,
+    /// Words in the chapters parsed so far.
+    pub words: usize,
+    /// Chapters parsed so far, out of the total.
+    pub parsed: (usize, usize),
+    /// Body chapters typed all the way through, out of all of them.
+    pub completed: (usize, usize),
+    pub totals: Totals,
+    /// Chapters with sessions, in TOC order.
+    pub speeds: Vec<ChapterSpeed>,
+    pub resume: Option<Resume>,
+    /// Day to finish the book by, and how it's going.
+    pub goal: Option<(u64, Schedule)>,
+}
+
+/// Overview of the open book and the profile's history with it.
+pub struct BookInfo {
+    dimensions: Arc<Dimensions>,
+    details: Details,
+}
+
+impl DisplayState for BookInfo {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl BookInfo {
+    pub fn new(dimensions: Arc<Dimensions>, details: Details) -> Self {
+        Self {
+            dimensions,
+            details,
+        }
+    }
+
+    /// Returns the TOC index and character offset to resume from, once
+    /// chosen.
+    pub fn handle_input(&self, event: KeyEvent) -> Option<(usize, usize)> {
+        match event.code {
+            KeyCode::Enter => {
+                let resume = self.details.resume.as_ref()?;
+                Some((resume.chapter, resume.offset))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        let details = &self.details;
+        let clip = |s: &str| s.chars().take(width).collect::<String>();
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 1), SetAttribute(Attribute::Bold))?;
+        w.write_all(clip(&details.title).as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        if let Some(author) = &details.author {
+            queue!(w, cursor::MoveTo(col, 2), SetAttribute(Attribute::Dim))?;
+            w.write_all(clip(author).as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+
+        let (parsed, total) = details.parsed;
+        let mut line = format!("{} chapters · {} words", details.chapters, details.words);
+        if parsed < total {
+            line += &format!(" in {parsed} read so far");
+        }
+        let (completed, body) = details.completed;
+        let lines = [
+            line,
+            format!("{completed} of {body} chapters completed"),
+            match details.totals.sessions {
+                0 => "not typed yet".to_owned(),
+                n => format!(
+                    "{n} sessions · {:.0} wpm · {:.1}% accuracy",
+                    details.totals.wpm(),
+                    details.totals.accuracy() * 100.0
+                ),
+            },
+        ];
+        for (row, line) in (4..).zip(lines) {
+            queue!(w, cursor::MoveTo(col, row))?;
+            w.write_all(clip(&line).as_bytes())?;
+        }
+        if let Some((finish_by, schedule)) = &details.goal {
+            self.render_goal(w, *finish_by, schedule, 7)?;
+        }
+
+        queue!(w, cursor::MoveTo(col, 8), SetAttribute(Attribute::Dim))?;
+        let hint = match &details.resume {
+            Some(resume) => format!("enter to resume {}, g to set a goal", resume.name),
+            None => "g to set a goal, esc to go back".to_owned(),
+        };
+        w.write_all(clip(&hint).as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        let max = details.speeds.iter().map(|s| s.wpm).fold(0.0, f64::max);
+        let bar_width = width.saturating_sub(NAME_WIDTH + 6);
+        let rows = self.screen_height().saturating_sub(HEADER_ROWS + 1);
+        for (row, speed) in (HEADER_ROWS..).zip(details.speeds.iter().take(rows as usize)) {
+            let name: String = speed.name.chars().take(NAME_WIDTH - 1).collect();
+            let bar = match max {
+                max if max > 0.0 => (speed.wpm / max * bar_width as f64).round() as usize,
+                _ => 0,
+            };
+            queue!(w, cursor::MoveTo(col, row))?;
+            write!(w, "{name:<NAME_WIDTH$}")?;
+            queue!(w, SetForegroundColor(Color::Cyan))?;
+            write!(w, "{:<bar_width$}", caps::glyphs().block.repeat(bar))?;
+            queue!(w, SetForegroundColor(Color::Reset))?;
+            write!(w, " {:>4.0}", speed.wpm)?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+
+    /// The words a day needed to finish by the goal, and whether the recent
+    /// rate keeps up with it.
+    fn render_goal(
+        &self,
+        w: &mut impl Write,
+        finish_by: u64,
+        schedule: &Schedule,
+        row: u16,
+    ) -> anyhow::Result<()> {
+        let mut line = match schedule.days_left {
+            0 => format!("goal of {} has passed", date::format_date(finish_by)),
+            days => {
+                let mut line = format!(
+                    "by {}: {:.0} words a day for {days} days",
+                    date::format_date(finish_by),
+                    schedule.words_per_day.ceil()
+                );
+                if let Some(minutes) = schedule.minutes_per_day {
+                    line += &format!(" (~{:.0} min)", minutes.ceil());
+                }
+                line
+            }
+        };
+        let color = match schedule.projected {
+            Some(day) => {
+                let day = date::format_date(day as u64 * 24 * 60 * 60);
+                line += &format!(" · on pace for {day}");
+                match schedule.on_schedule() {
+                    true => Color::Green,
+                    false => Color::Yellow,
+                }
+            }
+            None => {
+                line += " · nothing typed this week";
+                Color::Yellow
+            }
+        };
+        let clipped: String = line.chars().take(self.content_width() as usize).collect();
+        queue!(
+            w,
+            cursor::MoveTo(self.content_starting_col(), row),
+            SetForegroundColor(color)
+        )?;
+        w.write_all(clipped.as_bytes())?;
+        queue!(w, SetForegroundColor(Color::Reset))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+//! The word being typed in letters several rows tall, drawn above the text
+//! for anyone who finds the text itself too small to read comfortably.
+
+use std::io::Write;
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+    terminal,
+};
+
+use super::caps;
+
+/// Rows of pixels in each letter: capitals and digits take the top five,
+/// lowercase letters sit on the fifth and the last is for descenders.
+const PIXEL_ROWS: usize = 6;
+
+/// How far through the word a letter is.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Look {
+    Typed,
+    Mistake,
+    /// The letter to type next.
+    Next,
+    Ahead,
+}
+
+/// Terminal rows the letters take. With block elements each row of cells
+/// holds two rows of pixels, which also keeps the pixels roughly square.
+pub fn height() -> u16 {
+    match caps::get().unicode {
+        true => (PIXEL_ROWS / 2) as u16,
+        false => PIXEL_ROWS as u16,
+    }
+}
+
+/// Draws `word` in large letters from `(col, row)`, cut off at `width`
+/// columns, over whatever was on those rows.
+pub fn render(
+    w: &mut impl Write,
+    col: u16,
+    row: u16,
+    width: u16,
+    word: &[(char, Look)],
+) -> anyhow::Result<()> {
+    for r in 0..height() {
+        queue!(
+            w,
+            cursor::MoveTo(col, row + r),
+            terminal::Clear(terminal::ClearType::UntilNewLine)
+        )?;
+    }
+    let mut x = 0;
+    for &(c, look) in word {
+        let glyph = glyph(c);
+        let glyph_width = glyph[0].len() as u16;
+        if x + glyph_width > width {
+            break;
+        }
+        match look {
+            Look::Typed => queue!(w, SetAttribute(Attribute::Dim))?,
+            Look::Mistake => queue!(w, SetForegroundColor(Color::Red))?,
+            Look::Next => queue!(w, SetAttribute(Attribute::Bold))?,
+            Look::Ahead => {}
+        }
+        for (r, line) in rows(&glyph).iter().enumerate() {
+            queue!(w, cursor::MoveTo(col + x, row + r as u16))?;
+            w.write_all(line.as_bytes())?;
+        }
+        queue!(
+            w,
+            SetForegroundColor(Color::Reset),
+            SetAttribute(Attribute::Reset)
+        )?;
+        // A column between letters.
+        x += glyph_width + 1;
+    }
+    Ok(())
+}
+
+/// Lines of cells drawing `glyph`.
+fn rows(glyph: &[&str; PIXEL_ROWS]) -> Vec<String> {
+    let on = |line: &str, i: usize| line.as_bytes()[i] == b'#';
+    let width = glyph[0].len();
+    if !caps::get().unicode {
+        return glyph.iter().map(|line| line.to_string()).collect();
+    }
+    glyph
+        .chunks(2)
+        .map(|pair| {
+            (0..width)
+                .map(|i| match (on(pair[0], i), on(pair[1], i)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Pixels of `c`, `#` for set. Characters without a letter of their own
+/// come out as a solid block.
+fn glyph(c: char) -> [&'static str; PIXEL_ROWS] {
+    match c {
+        'A' => [" # ", "# #", "###", "# #", "# #", "   "],
+        'B' => ["## ", "# #", "## ", "# #", "## ", "   "],
+        'C' => [" ##", "#  ", "#  ", "#  ", " ##", "   "],
+        'D' => ["## ", "# #", "# #", "# #", "## ", "   "],
+        'E' => ["###", "#  ", "## ", "#  ", "###", "   "],
+        'F' => ["###", "#  ", "## ", "#  ", "#  ", "   "],
+        'G' => [" ##", "#  ", "# #", "# #", " ##", "   "],
+        'H' => ["# #", "# #", "###", "# #", "# #", "   "],
+        'I' => ["###", " # ", " # ", " # ", "###", "   "],
+        'J' => ["  #", "  #", "  #", "# #", " # ", "   "],
+        'K' => ["# #", "# #", "## ", "# #", "# #", "   "],
+        'L' => ["#  ", "#  ", "#  ", "#  ", "###", "   "],
+        'M' => ["#   #", "## ##", "# # #", "#   #", "#   #", "     "],
+        'N' => ["#  #", "## #", "# ##", "#  #", "#  #", "    "],
+        'O' => [" # ", "# #", "# #", "# #", " # ", "   "],
+        'P' => ["## ", "# #", "## ", "#  ", "#  ", "   "],
+        'Q' => [" # ", "# #", "# #", "## ", " ##", "   "],
+        'R' => ["## ", "# #", "## ", "# #", "# #", "   "],
+        'S' => [" ##", "#  ", " # ", "  #", "## ", "   "],
+        'T' => ["###", " # ", " # ", " # ", " # ", "   "],
+        'U' => ["# #", "# #", "# #", "# #", "###", "   "],
+        'V' => ["# #", "# #", "# #", "# #", " # ", "   "],
+        'W' => ["#   #", "#   #", "# # #", "## ##", "#   #", "     "],
+        'X' => ["# #", "# #", " # ", "# #", "# #", "   "],
+        'Y' => ["# #", "# #", " # ", " # ", " # ", "   "],
+        'Z' => ["###", "  #", " # ", "#  ", "###", "   "],
+        'a' => ["   ", "   ", " ##", "# #", " ##", "   "],
+        'b' => ["#  ", "#  ", "## ", "# #", "## ", "   "],
+        'c' => ["   ", "   ", " ##", "#  ", " ##", "   "],
+        'd' => ["  #", "  #", " ##", "# #", " ##", "   "],
+        'e' => ["   ", "   ", " # ", "###", " ##", "   "],
+        'f' => [" ##", " # ", "###", " # ", " # ", "   "],
+        'g' => ["   ", "   ", " ##", "# #", " ##", "## "],
+        'h' => ["#  ", "#  ", "## ", "# #", "# #", "   "],
+        'i' => ["#", " ", "#", "#", "#", " "],
+        'j' => ["  #", "   ", "  #", "  #", "  #", "## "],
+        'k' => ["#  ", "#  ", "# #", "## ", "# #", "   "],
+        'l' => ["#", "#", "#", "#", "#", " "],
+        'm' => ["     ", "     ", "#### ", "# # #", "# # #", "     "],
+        'n' => ["   ", "   ", "## ", "# #", "# #", "   "],
+        'o' => ["   ", "   ", " # ", "# #", " # ", "   "],
+        'p' => ["   ", "   ", "## ", "# #", "## ", "#  "],
+        'q' => ["   ", "   ", " ##", "# #", " ##", "  #"],
+        'r' => ["   ", "   ", " ##", "#  ", "#  ", "   "],
+        's' => ["   ", "   ", " ##", " # ", "## ", "   "],
+        't' => [" # ", " # ", "###", " # ", " ##", "   "],
+        'u' => ["   ", "   ", "# #", "# #", " ##", "   "],
+        'v' => ["   ", "   ", "# #", "# #", " # ", "   "],
+        'w' => ["     ", "     ", "#   #", "# # #", " # # ", "     "],
+        'x' => ["   ", "   ", "# #", " # ", "# #", "   "],
+        'y' => ["   ", "   ", "# #", "# #", " ##", "## "],
+        'z' => ["   ", "   ", "###", " # ", "###", "   "],
+        '0' => ["###", "# #", "# #", "# #", "###", "   "],
+        '1' => [" # ", "## ", " # ", " # ", "###", "   "],
+        '2' => ["## ", "  #", " # ", "#  ", "###", "   "],
+        '3' => ["## ", "  #", " # ", "  #", "## ", "   "],
+        '4' => ["# #", "# #", "###", "  #", "  #", "   "],
+        '5' => ["###", "#  ", "## ", "  #", "## ", "   "],
+        '6' => [" ##", "#  ", "###", "# #", "###", "   "],
+        '7' => ["###", "  #", " # ", " # ", " # ", "   "],
+        '8' => ["###", "# #", "###", "# #", "###", "   "],
+        '9' => ["###", "# #", "###", "  #", "## ", "   "],
+        '.' => [" ", " ", " ", " ", "#", " "],
+        ',' => ["  ", "  ", "  ", "  ", " #", "# "],
+        ':' => [" ", " ", "#", " ", "#", " "],
+        ';' => ["  ", "  ", " #", "  ", " #", "# "],
+        '!' => ["#", "#", "#", " ", "#", " "],
+        '?' => ["## ", "  #", " # ", "   ", " # ", "   "],
+        '\'' | '‘' | '’' => ["#", "#", " ", " ", " ", " "],
+        '"' | '“' | '”' => ["# #", "# #", "   ", "   ", "   ", "   "],
+        '-' | '–' | '—' => ["  ", "  ", "##", "  ", "  ", "  "],
+        '(' => [" #", "# ", "# ", "# ", " #", "  "],
+        ')' => ["# ", " #", " #", " #", "# ", "  "],
+        '/' => ["  #", "  #", " # ", "#  ", "#  ", "   "],
+        _ => ["###", "###", "###", "###", "###", "   "],
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn glyphs_are_rectangular() {
+        let chars = ('!'..='~').chain("‘’“”–—".chars());
+        for c in chars {
+            let glyph = glyph(c);
+            assert!(
+                glyph.iter().all(|line| line.len() == glyph[0].len()),
+                "{c:?} has rows of different widths"
+            );
+        }
+    }
+}
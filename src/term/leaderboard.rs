@@ -0,0 +1,115 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, SetAttribute},
+    terminal,
+};
+
+use super::{Dimensions, DisplayState};
+use crate::{
+    stats::Totals,
+    store::{self, Store},
+};
+
+struct Entry {
+    profile: String,
+    totals: Totals,
+}
+
+/// Every profile's results on the open book, fastest first.
+pub struct Leaderboard {
+    dimensions: Arc<Dimensions>,
+    title: String,
+    current: String,
+    entries: Vec<Entry>,
+}
+
+impl DisplayState for Leaderboard {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Leaderboard {
+    /// Other profiles' sessions are read unencrypted, so encrypted profiles
+    /// other than `current` are left out. So are sessions that don't look
+    /// typed by hand.
+    pub fn new(dimensions: Arc<Dimensions>, book: &str, title: String, current: &Store) -> Self {
+        let mut entries: Vec<Entry> = store::profiles()
+            .into_iter()
+            .filter_map(|profile| {
+                let sessions = if profile == current.profile() {
+                    current.sessions()
+                } else {
+                    Store::open(&profile).sessions()
+                };
+                let sessions = sessions.ok()?;
+                let totals: Totals = sessions
+                    .iter()
+                    .filter(|s| s.book == book && s.is_plausible())
+                    .collect();
+                (totals.sessions > 0).then_some(Entry { profile, totals })
+            })
+            .collect();
+        entries.sort_by(|a, b| b.totals.best_wpm.total_cmp(&a.totals.best_wpm));
+
+        Self {
+            dimensions,
+            title,
+            current: current.profile().to_owned(),
+            entries,
+        }
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 1), SetAttribute(Attribute::Bold))?;
+        let title: String = self.title.chars().take(width).collect();
+        w.write_all(title.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        queue!(w, cursor::MoveTo(col, 3), SetAttribute(Attribute::Dim))?;
+        write!(
+            w,
+            "{:>3}  {:<16} {:>5} {:>5} {:>5} {:>6} {:>8}",
+            "#", "profile", "best", "avg", "adj", "acc", "sessions"
+        )?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        if self.entries.is_empty() {
+            queue!(w, cursor::MoveTo(col, 5))?;
+            w.write_all(b"no sessions recorded for this book yet")?;
+        }
+
+        let rows = self.screen_height().saturating_sub(5);
+        for (i, entry) in self.entries.iter().take(rows as usize).enumerate() {
+            queue!(w, cursor::MoveTo(col, 4 + i as u16))?;
+            if entry.profile == self.current {
+                queue!(w, SetAttribute(Attribute::Bold))?;
+            }
+            let name: String = entry.profile.chars().take(16).collect();
+            write!(
+                w,
+                "{:>3}  {:<16} {:>5.0} {:>5.0} {:>5.0} {:>5.1}% {:>8}",
+                i + 1,
+                name,
+                entry.totals.best_wpm,
+                entry.totals.wpm(),
+                entry.totals.adjusted_wpm(),
+                entry.totals.accuracy() * 100.0,
+                entry.totals.sessions
+            )?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,121 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, SetAttribute},
+    terminal,
+};
+
+use super::{Dimensions, Display, DisplayState, Pick, Picker};
+use crate::{config::Config, library::Book};
+
+/// Rows above the list of books.
+const HEADER_ROWS: u16 = 3;
+/// Rows each book takes: title, then author.
+const BOOK_ROWS: u16 = 3;
+
+/// The books in a directory, for choosing one to open.
+pub struct LibraryScreen {
+    dimensions: Arc<Dimensions>,
+    books: Vec<Book>,
+    selected: usize,
+}
+
+impl DisplayState for LibraryScreen {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl LibraryScreen {
+    pub fn new(config: &Config, books: Vec<Book>, view_width: u16, view_height: u16) -> Self {
+        Self {
+            dimensions: Arc::new(Dimensions::new(config, view_width, view_height)),
+            books,
+            selected: 0,
+        }
+    }
+}
+
+impl Picker for LibraryScreen {
+    type Item = Book;
+
+    fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        Display::enter_screen(w)?;
+        self.render(w)
+    }
+
+    fn resize(&mut self, config: &Config, width: u16, height: u16) {
+        self.set_dimensions(Arc::new(Dimensions::new(config, width, height)));
+    }
+
+    fn handle_input(&mut self, event: KeyEvent) -> Option<Pick<Book>> {
+        let last = self.books.len().saturating_sub(1);
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::Home | KeyCode::Char('g') => self.selected = 0,
+            KeyCode::End | KeyCode::Char('G') => self.selected = last,
+            KeyCode::Enter => return Some(Pick::Open(self.books.get(self.selected)?.clone())),
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Pick::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 1), SetAttribute(Attribute::Bold))?;
+        w.write_all(b"Library")?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        let fits =
+            (self.screen_height().saturating_sub(HEADER_ROWS + 1) / BOOK_ROWS).max(1) as usize;
+        let first = (self.selected + 1).saturating_sub(fits);
+        for (i, book) in self.books.iter().enumerate().skip(first).take(fits) {
+            let row = HEADER_ROWS + (i - first) as u16 * BOOK_ROWS;
+
+            queue!(w, cursor::MoveTo(col.saturating_sub(2), row))?;
+            if i == self.selected {
+                w.write_all(b">")?;
+                queue!(w, SetAttribute(Attribute::Bold))?;
+            }
+            queue!(w, cursor::MoveTo(col, row))?;
+            let title: String = book.title.chars().take(width).collect();
+            w.write_all(title.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+
+            if let Some(author) = &book.author {
+                let author: String = author.chars().take(width).collect();
+                queue!(
+                    w,
+                    cursor::MoveTo(col, row + 1),
+                    SetAttribute(Attribute::Dim)
+                )?;
+                w.write_all(author.as_bytes())?;
+                queue!(w, SetAttribute(Attribute::Reset))?;
+            }
+        }
+
+        let hint = "j/k to move, enter to open, q to quit";
+        let hint: String = hint.chars().take(width).collect();
+        queue!(
+            w,
+            cursor::MoveTo(col, self.screen_height().saturating_sub(1)),
+            SetAttribute(Attribute::Dim)
+        )?;
+        w.write_all(hint.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,221 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent, KeyModifiers},
+    queue,
+    style::{Attribute, SetAttribute},
+};
+
+use super::{caps, Dimensions, DisplayState};
+
+/// Widest a modal box gets, inside its border.
+const MAX_INNER_WIDTH: usize = 50;
+
+/// Single-line text field, for anything that asks for text.
+#[derive(Debug, Default, Clone)]
+pub struct TextInput {
+    text: String,
+    /// Character index of the cursor.
+    cursor: usize,
+}
+
+impl TextInput {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_owned(),
+            cursor: text.chars().count(),
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn byte_index(&self, chars: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(chars)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Applies an editing key, returning whether it was one.
+    pub fn handle_input(&mut self, event: KeyEvent) -> bool {
+        let ctrl = event.modifiers.contains(KeyModifiers::CONTROL);
+        match event.code {
+            KeyCode::Char('w') | KeyCode::Backspace if ctrl => {
+                let end = self.byte_index(self.cursor);
+                let kept = self.text[..end].trim_end();
+                let start = kept.rfind(' ').map_or(0, |i| i + 1);
+                self.cursor -= self.text[start..end].chars().count();
+                self.text.replace_range(start..end, "");
+            }
+            KeyCode::Char('u') if ctrl => {
+                let end = self.byte_index(self.cursor);
+                self.text.replace_range(..end, "");
+                self.cursor = 0;
+            }
+            KeyCode::Char('a') if ctrl => self.cursor = 0,
+            KeyCode::Char('e') if ctrl => self.cursor = self.text.chars().count(),
+            KeyCode::Char(_) if ctrl => return false,
+            KeyCode::Char(c) => {
+                let i = self.byte_index(self.cursor);
+                self.text.insert(i, c);
+                self.cursor += 1;
+            }
+            KeyCode::Backspace if self.cursor > 0 => {
+                self.cursor -= 1;
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Delete if self.cursor < self.text.chars().count() => {
+                self.text.remove(self.byte_index(self.cursor));
+            }
+            KeyCode::Left => self.cursor = self.cursor.saturating_sub(1),
+            KeyCode::Right => self.cursor = (self.cursor + 1).min(self.text.chars().count()),
+            KeyCode::Home => self.cursor = 0,
+            KeyCode::End => self.cursor = self.text.chars().count(),
+            KeyCode::Backspace | KeyCode::Delete => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Inserts `text` at the cursor, on one line.
+    pub fn paste(&mut self, text: &str) {
+        let text: String = text
+            .chars()
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+        let i = self.byte_index(self.cursor);
+        self.text.insert_str(i, &text);
+        self.cursor += text.chars().count();
+    }
+
+    /// Draws the text from `(col, row)`, scrolled to keep the cursor within
+    /// `width`, and returns the column the cursor should be shown at.
+    pub fn render(
+        &self,
+        w: &mut impl Write,
+        col: u16,
+        row: u16,
+        width: u16,
+    ) -> anyhow::Result<u16> {
+        let width = width.max(1) as usize;
+        let skip = (self.cursor + 1).saturating_sub(width);
+        let visible: String = self.text.chars().skip(skip).take(width).collect();
+        queue!(w, cursor::MoveTo(col, row))?;
+        w.write_all(visible.as_bytes())?;
+        Ok(col + (self.cursor - skip) as u16)
+    }
+}
+
+/// How a modal was closed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Confirmed,
+    Entered(String),
+    Cancelled,
+}
+
+enum Kind {
+    Confirm,
+    Prompt(TextInput),
+}
+
+/// A boxed question drawn over the current screen, taking all input until
+/// it's answered.
+pub struct Modal {
+    dimensions: Arc<Dimensions>,
+    question: String,
+    kind: Kind,
+}
+
+impl DisplayState for Modal {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Modal {
+    /// Asks a yes/no question.
+    pub fn confirm(dimensions: Arc<Dimensions>, question: impl Into<String>) -> Self {
+        Self {
+            dimensions,
+            question: question.into(),
+            kind: Kind::Confirm,
+        }
+    }
+
+    /// Asks for a line of text, starting from `initial`.
+    pub fn prompt(dimensions: Arc<Dimensions>, question: impl Into<String>, initial: &str) -> Self {
+        Self {
+            dimensions,
+            question: question.into(),
+            kind: Kind::Prompt(TextInput::new(initial)),
+        }
+    }
+
+    /// Returns the answer once the modal is closed.
+    pub fn handle_input(&mut self, event: KeyEvent) -> Option<Answer> {
+        match (&mut self.kind, event.code) {
+            (_, KeyCode::Esc) => Some(Answer::Cancelled),
+            (Kind::Confirm, KeyCode::Enter | KeyCode::Char('y' | 'Y')) => Some(Answer::Confirmed),
+            (Kind::Confirm, KeyCode::Char('n' | 'N')) => Some(Answer::Cancelled),
+            (Kind::Prompt(input), KeyCode::Enter) => Some(Answer::Entered(input.text().to_owned())),
+            (Kind::Prompt(input), _) => {
+                input.handle_input(event);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        if let Kind::Prompt(input) = &mut self.kind {
+            input.paste(text);
+        }
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let inner = MAX_INNER_WIDTH
+            .min(self.question.chars().count().max(20))
+            .min(self.screen_width().saturating_sub(4) as usize);
+        let left = self.screen_width().saturating_sub(inner as u16 + 4) / 2;
+        let top = self.middle_row().saturating_sub(2);
+
+        queue!(w, cursor::Hide)?;
+        caps::glyphs().frame.draw(w, left, top, inner + 2, 3)?;
+
+        let question: String = self.question.chars().take(inner).collect();
+        queue!(
+            w,
+            cursor::MoveTo(left + 2, top + 1),
+            SetAttribute(Attribute::Bold)
+        )?;
+        w.write_all(question.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        match &self.kind {
+            Kind::Confirm => {
+                queue!(
+                    w,
+                    cursor::MoveTo(left + 2, top + 3),
+                    SetAttribute(Attribute::Dim)
+                )?;
+                w.write_all(b"y / n")?;
+                queue!(w, SetAttribute(Attribute::Reset))?;
+            }
+            Kind::Prompt(input) => {
+                let col = input.render(w, left + 2, top + 3, inner as u16)?;
+                queue!(w, cursor::MoveTo(col, top + 3), cursor::Show)?;
+            }
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
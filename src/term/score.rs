@@ -0,0 +1,195 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, Color, SetAttribute, SetForegroundColor},
+    terminal,
+};
+
+use super::{caps, Dimensions, DisplayState};
+use crate::{card::Card, stats::SentenceSpeed};
+
+const MIN_INNER_WIDTH: usize = 36;
+/// Rows between the top and bottom borders, without the sentences.
+const INNER_HEIGHT: u16 = 10;
+/// Slowest and fastest sentences listed.
+const SENTENCES: usize = 5;
+/// Paragraphs that changed most since the last run listed.
+const PARAGRAPHS: usize = 3;
+
+/// Result card shown once a session ends, whether the chapter was typed to
+/// the end or left with escape.
+pub struct Score {
+    dimensions: Arc<Dimensions>,
+    card: Card,
+    message: Option<String>,
+}
+
+impl DisplayState for Score {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Score {
+    pub fn new(dimensions: Arc<Dimensions>, card: Card) -> Self {
+        Self {
+            dimensions,
+            card,
+            message: None,
+        }
+    }
+
+    /// Returns whether the card should be dismissed.
+    pub fn handle_input(&mut self, event: KeyEvent) -> bool {
+        match event.code {
+            KeyCode::Enter | KeyCode::Char(' ') => return true,
+            KeyCode::Char('p') => self.message = Some(self.save_png()),
+            _ => {}
+        }
+        false
+    }
+
+    #[cfg(feature = "png")]
+    fn save_png(&self) -> String {
+        let secs = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = std::path::PathBuf::from(format!("ept-{secs}.png"));
+        match self.card.save_png(&path) {
+            Ok(()) => format!("saved to {}", path.display()),
+            Err(e) => format!("couldn't save: {e}"),
+        }
+    }
+
+    #[cfg(not(feature = "png"))]
+    fn save_png(&self) -> String {
+        "built without png support".to_owned()
+    }
+
+    /// The slowest sentences, slowest first, then the fastest, fastest
+    /// first. Fewer than [`SENTENCES`] of each if there aren't enough to go
+    /// round without repeating.
+    fn extremes(&self) -> (Vec<&SentenceSpeed>, Vec<&SentenceSpeed>) {
+        let mut sorted: Vec<&SentenceSpeed> = self.card.sentences.iter().collect();
+        sorted.sort_by(|a, b| a.wpm.total_cmp(&b.wpm));
+        let n = SENTENCES.min(sorted.len() / 2);
+        let fastest = sorted.iter().rev().take(n).copied().collect();
+        sorted.truncate(n);
+        (sorted, fastest)
+    }
+
+    fn sparkline(&self) -> String {
+        let max = self.card.speeds.iter().copied().fold(0.0, f64::max);
+        if max <= 0.0 {
+            return String::new();
+        }
+        let sparks = caps::glyphs().sparks;
+        self.card
+            .speeds
+            .iter()
+            .map(|s| sparks[(s / max * (sparks.len() - 1) as f64).round() as usize])
+            .collect()
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let inner = MIN_INNER_WIDTH
+            .max(self.card.speeds.len())
+            .min(self.screen_width().saturating_sub(4) as usize);
+        let left = self.screen_width().saturating_sub(inner as u16 + 4) / 2;
+        let (slowest, fastest) = self.extremes();
+        let listed = match slowest.len() {
+            0 => 0,
+            n => 2 * n as u16 + 4,
+        };
+        let changes = self
+            .card
+            .rerun
+            .as_ref()
+            .map_or(&[][..], |r| &r.changes[..r.changes.len().min(PARAGRAPHS)]);
+        let compared = match self.card.rerun {
+            Some(_) => changes.len() as u16 + 2,
+            None => 0,
+        };
+        let height = INNER_HEIGHT + listed + compared;
+        let top = self.middle_row().saturating_sub(height / 2 + 1);
+
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+        caps::glyphs()
+            .rounded_frame
+            .draw(w, left, top, inner + 2, height)?;
+
+        let hint = match &self.message {
+            Some(message) => message.clone(),
+            None => "enter to continue · p png · t tag".to_owned(),
+        };
+        let sparkline = self.sparkline();
+        let sparkline_pad = " ".repeat(inner.saturating_sub(sparkline.chars().count()) / 2);
+        let mut lines = vec![
+            (1, Attribute::Bold, Color::Reset, self.card.title.clone()),
+            (2, Attribute::Dim, Color::Reset, self.card.chapter.clone()),
+            (4, Attribute::Bold, Color::Green, self.card.headline()),
+            (5, Attribute::Reset, Color::Reset, self.card.speeds()),
+            (6, Attribute::Reset, Color::Reset, self.card.details()),
+            (
+                7,
+                Attribute::Dim,
+                Color::Reset,
+                self.card.difficulty().unwrap_or_default(),
+            ),
+            (8, Attribute::Reset, Color::Cyan, sparkline_pad + &sparkline),
+            (height, Attribute::Dim, Color::Reset, hint),
+        ];
+        let mut row = 10;
+        for (heading, sentences) in [("slowest", &slowest), ("fastest", &fastest)] {
+            if sentences.is_empty() {
+                continue;
+            }
+            lines.push((row, Attribute::Dim, Color::Reset, heading.to_owned()));
+            for sentence in sentences.iter() {
+                row += 1;
+                let text = format!("{:>4.0} wpm  {}", sentence.wpm, sentence.text);
+                lines.push((row, Attribute::Reset, Color::Reset, text));
+            }
+            row += 2;
+        }
+        if let Some(rerun) = self.card.rerun() {
+            lines.push((row, Attribute::Dim, Color::Reset, rerun));
+            for change in changes {
+                row += 1;
+                let color = match change.wpm >= 0.0 {
+                    true => Color::Green,
+                    false => Color::Red,
+                };
+                let text = format!(
+                    "{:+5.1} wpm  {:+} mistakes  paragraph {}",
+                    change.wpm, change.mistakes, change.paragraph
+                );
+                lines.push((row, Attribute::Reset, color, text));
+            }
+        }
+        for (row, attr, color, text) in lines {
+            let text: String = text.chars().take(inner).collect();
+            queue!(
+                w,
+                cursor::MoveTo(left + 2, top + row),
+                SetAttribute(attr),
+                SetForegroundColor(color),
+            )?;
+            w.write_all(text.as_bytes())?;
+            queue!(
+                w,
+                SetForegroundColor(Color::Reset),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+        w.flush()?;
+        Ok(())
+    }
+}
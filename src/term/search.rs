@@ -0,0 +1,221 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, SetAttribute},
+    terminal,
+};
+use lepu::Epub;
+
+use super::{modal::TextInput, Dimensions, DisplayState};
+use crate::{backend, cache::ChapterCache};
+
+/// Characters of context shown either side of a match.
+const CONTEXT: usize = 30;
+const MAX_RESULTS: usize = 500;
+/// Rows taken up by a single result: chapter name, snippet, gap.
+const RESULT_ROWS: u16 = 3;
+/// Rows above the first result.
+const HEADER_ROWS: u16 = 4;
+
+struct Match {
+    /// TOC index of the chapter the match is in.
+    chapter: usize,
+    /// Character offset of the match within the chapter.
+    offset: usize,
+    chapter_name: String,
+    before: String,
+    matched: String,
+    after: String,
+}
+
+pub struct Search {
+    dimensions: Arc<Dimensions>,
+    query: TextInput,
+    /// Query the current results are for.
+    searched: Option<String>,
+    results: Vec<Match>,
+    selected: usize,
+    /// Chapters that had been parsed when the search ran, out of the total.
+    coverage: (usize, usize),
+}
+
+impl DisplayState for Search {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Search {
+    pub fn new(dimensions: Arc<Dimensions>) -> Self {
+        Self {
+            dimensions,
+            query: TextInput::default(),
+            searched: None,
+            results: Vec::new(),
+            selected: 0,
+            coverage: (0, 0),
+        }
+    }
+
+    /// Returns the TOC index and character offset to start typing from once
+    /// a result is picked.
+    pub fn handle_input(
+        &mut self,
+        event: KeyEvent,
+        cache: &mut ChapterCache,
+        book: &Epub,
+    ) -> Option<(usize, usize)> {
+        match event.code {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected = (self.selected + 1).min(self.results.len().saturating_sub(1))
+            }
+            KeyCode::Enter if self.searched.as_deref() != Some(self.query.text()) => {
+                self.run(cache, book)
+            }
+            KeyCode::Enter => {
+                let m = self.results.get(self.selected)?;
+                return Some((m.chapter, m.offset));
+            }
+            _ => {
+                self.query.handle_input(event);
+            }
+        }
+        None
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.query.paste(text);
+    }
+
+    fn run(&mut self, cache: &mut ChapterCache, book: &Epub) {
+        self.results.clear();
+        self.selected = 0;
+        self.searched = Some(self.query.text().to_owned());
+        if self.query.text().is_empty() {
+            return;
+        }
+
+        let total = cache.len();
+        let mut parsed = 0;
+        'outer: for (chapter, content) in cache.iter() {
+            parsed += 1;
+            let chapter_name = book
+                .chapter_by_toc_index(chapter)
+                .map(|c| c.name().to_owned())
+                .unwrap_or_default();
+            let text = content.text.as_str();
+            for (offset, (i, _)) in text.char_indices().enumerate() {
+                let Some(len) = match_len(&text[i..], self.query.text()) else {
+                    continue;
+                };
+                let paragraph = text[..i].rfind('\n').map_or(0, |p| p + 1);
+                let before = &text[paragraph..i];
+                let skip = before.chars().count().saturating_sub(CONTEXT);
+                self.results.push(Match {
+                    chapter,
+                    offset,
+                    chapter_name: chapter_name.clone(),
+                    before: before.chars().skip(skip).collect(),
+                    matched: text[i..i + len].to_owned(),
+                    after: text[i + len..]
+                        .chars()
+                        .take_while(|&c| c != '\n')
+                        .take(CONTEXT)
+                        .collect(),
+                });
+                if self.results.len() >= MAX_RESULTS {
+                    break 'outer;
+                }
+            }
+        }
+        self.coverage = (parsed, total);
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 2), SetAttribute(Attribute::Dim))?;
+        match &self.searched {
+            Some(q) if q == self.query.text() => {
+                let (parsed, total) = self.coverage;
+                write!(w, "{} results", self.results.len())?;
+                if parsed < total {
+                    write!(w, " ({parsed}/{total} chapters searched)")?;
+                }
+            }
+            _ => write!(w, "enter to search")?,
+        }
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        let visible = (self.screen_height().saturating_sub(HEADER_ROWS) / RESULT_ROWS).max(1);
+        let first = (self.selected + 1).saturating_sub(visible as usize);
+        for (i, m) in self.results[first..]
+            .iter()
+            .take(visible as usize)
+            .enumerate()
+        {
+            let row = HEADER_ROWS + i as u16 * RESULT_ROWS;
+            if first + i == self.selected {
+                queue!(w, cursor::MoveTo(col.saturating_sub(2), row + 1))?;
+                w.write_all(b">")?;
+            }
+
+            queue!(w, cursor::MoveTo(col, row), SetAttribute(Attribute::Dim))?;
+            let name: String = m.chapter_name.chars().take(width).collect();
+            w.write_all(name.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+
+            let matched: String = m.matched.chars().take(width).collect();
+            let avail = width - matched.chars().count();
+            let before_len = m.before.chars().count();
+            let after_len = m.after.chars().count();
+            let left = before_len.min(avail - after_len.min(avail / 2));
+            let right = after_len.min(avail - left);
+            let before: String = m.before.chars().skip(before_len - left).collect();
+            let after: String = m.after.chars().take(right).collect();
+
+            queue!(w, cursor::MoveTo(col, row + 1))?;
+            w.write_all(before.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reverse))?;
+            w.write_all(matched.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::NoReverse))?;
+            w.write_all(after.as_bytes())?;
+        }
+
+        queue!(w, cursor::MoveTo(col, 1))?;
+        w.write_all(b"/")?;
+        let cursor_col =
+            self.query
+                .render(w, col + 1, 1, self.content_width().saturating_sub(1))?;
+        queue!(w, cursor::MoveTo(cursor_col, 1), cursor::Show)?;
+        w.flush()?;
+        Ok(())
+    }
+}
+
+/// Length in bytes of the text `text` starts with if it matches `query`,
+/// ignoring case and typographic quote differences.
+fn match_len(text: &str, query: &str) -> Option<usize> {
+    let mut len = 0;
+    let mut chars = text.chars();
+    for q in query.chars() {
+        let c = chars.next()?;
+        if !(c.to_lowercase().eq(q.to_lowercase())
+            || backend::chars_are_equal_including_unicode_alternatives(c, q))
+        {
+            return None;
+        }
+        len += c.len_utf8();
+    }
+    Some(len)
+}
@@ -0,0 +1,199 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, SetAttribute},
+    terminal,
+};
+
+use super::{Dimensions, Display, DisplayState};
+use crate::{
+    config::Config,
+    date,
+    shelf::{Shelf, Shelved},
+    store,
+};
+
+/// Rows above the list of books.
+const HEADER_ROWS: u16 = 3;
+/// Rows each book takes: title, then author and where it was left off.
+const BOOK_ROWS: u16 = 3;
+
+/// What was chosen from a list of books.
+pub enum Pick<T> {
+    Open(T),
+    /// Type the paragraphs due for review.
+    Review,
+    Quit,
+}
+
+/// A full-screen list to pick something from, as the shelf and the library
+/// are.
+pub trait Picker {
+    /// What's picked from the list.
+    type Item;
+
+    fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()>;
+
+    /// Lays the list out again for a terminal `width` by `height`.
+    fn resize(&mut self, config: &Config, width: u16, height: u16);
+
+    /// Returns what was picked, once something has been.
+    fn handle_input(&mut self, event: KeyEvent) -> Option<Pick<Self::Item>>;
+
+    fn render(&self, w: &mut impl Write) -> anyhow::Result<()>;
+}
+
+/// The books being read, for choosing one to carry on with.
+pub struct ShelfScreen {
+    dimensions: Arc<Dimensions>,
+    shelf: Shelf,
+    selected: usize,
+    /// Paragraphs due for review.
+    due: usize,
+}
+
+impl DisplayState for ShelfScreen {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl ShelfScreen {
+    pub fn new(
+        config: &Config,
+        shelf: Shelf,
+        due: usize,
+        view_width: u16,
+        view_height: u16,
+    ) -> Self {
+        Self {
+            dimensions: Arc::new(Dimensions::new(config, view_width, view_height)),
+            shelf,
+            selected: 0,
+            due,
+        }
+    }
+
+    /// The shelf, less any books taken off it.
+    pub fn shelf(&self) -> &Shelf {
+        &self.shelf
+    }
+}
+
+impl Picker for ShelfScreen {
+    type Item = Shelved;
+
+    fn enter(&mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        Display::enter_screen(w)?;
+        self.render(w)
+    }
+
+    fn resize(&mut self, config: &Config, width: u16, height: u16) {
+        self.set_dimensions(Arc::new(Dimensions::new(config, width, height)));
+    }
+
+    fn handle_input(&mut self, event: KeyEvent) -> Option<Pick<Shelved>> {
+        let last = self.shelf.books.len().saturating_sub(1);
+        match event.code {
+            KeyCode::Up | KeyCode::Char('k') => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.selected = (self.selected + 1).min(last),
+            KeyCode::Enter => {
+                let book = self.shelf.books.get(self.selected)?;
+                if book.path.is_file() {
+                    return Some(Pick::Open(book.clone()));
+                }
+            }
+            KeyCode::Char('r') if self.due > 0 => return Some(Pick::Review),
+            KeyCode::Char('d') => {
+                if let Some(book) = self.shelf.books.get(self.selected) {
+                    let key = book.key.clone();
+                    self.shelf.remove(&key);
+                    self.selected = self.selected.min(self.shelf.books.len().saturating_sub(1));
+                }
+                if self.shelf.books.is_empty() {
+                    return Some(Pick::Quit);
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => return Some(Pick::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 1), SetAttribute(Attribute::Bold))?;
+        w.write_all(b"Currently reading")?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        let fits =
+            (self.screen_height().saturating_sub(HEADER_ROWS + 1) / BOOK_ROWS).max(1) as usize;
+        let first = (self.selected + 1).saturating_sub(fits);
+        let now = store::now();
+        for (i, book) in self.shelf.books.iter().enumerate().skip(first).take(fits) {
+            let row = HEADER_ROWS + (i - first) as u16 * BOOK_ROWS;
+            let missing = !book.path.is_file();
+
+            queue!(w, cursor::MoveTo(col.saturating_sub(2), row))?;
+            if i == self.selected {
+                w.write_all(b">")?;
+                queue!(w, SetAttribute(Attribute::Bold))?;
+            }
+            queue!(w, cursor::MoveTo(col, row))?;
+            if missing {
+                queue!(w, SetAttribute(Attribute::Dim))?;
+            }
+            let title: String = book.title.chars().take(width).collect();
+            w.write_all(title.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+
+            let mut details = Vec::new();
+            if let Some(author) = &book.author {
+                details.push(author.clone());
+            }
+            match (&book.resume, missing) {
+                (_, true) => details.push(format!("missing from {}", book.path.display())),
+                (Some(resume), false) => details.push(format!("at {}", resume.name)),
+                (None, false) => {}
+            }
+            details.push(format!("opened {}", date::format_ago(book.opened, now)));
+            let details: String = details.join("  ").chars().take(width).collect();
+            queue!(
+                w,
+                cursor::MoveTo(col, row + 1),
+                SetAttribute(Attribute::Dim)
+            )?;
+            w.write_all(details.as_bytes())?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+
+        let hint = match self.due {
+            0 => "enter to open, d to take off the shelf, q to quit".to_owned(),
+            1 => "enter to open, r to review a paragraph, d to take off the shelf, q to quit"
+                .to_owned(),
+            n => format!(
+                "enter to open, r to review {n} paragraphs, d to take off the shelf, q to quit"
+            ),
+        };
+        let hint: String = hint.chars().take(width).collect();
+        queue!(
+            w,
+            cursor::MoveTo(col, self.screen_height().saturating_sub(1)),
+            SetAttribute(Attribute::Dim)
+        )?;
+        w.write_all(hint.as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        w.flush()?;
+        Ok(())
+    }
+}
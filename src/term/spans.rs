@@ -0,0 +1,57 @@
+use std::io::Write;
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, SetAttribute},
+};
+
+use crate::style::Style;
+
+/// A run of styled text built up in memory, so it reaches the terminal in a
+/// single write rather than one per style change. Attributes are only
+/// emitted where the style actually changes between spans.
+pub struct SpanBuffer {
+    buf: Vec<u8>,
+    style: Style,
+}
+
+impl SpanBuffer {
+    /// Starts a run at the given screen position.
+    pub fn at(col: u16, row: u16) -> anyhow::Result<Self> {
+        let mut buf = Vec::with_capacity(256);
+        queue!(buf, cursor::MoveTo(col, row))?;
+        Ok(Self {
+            buf,
+            style: Style::empty(),
+        })
+    }
+
+    pub fn push(&mut self, style: Style, text: &[u8]) -> anyhow::Result<()> {
+        if style != self.style {
+            let removed = (self.style & !style).iter().filter_map(|s| match s {
+                Style::BOLD => Some(Attribute::NormalIntensity),
+                Style::ITALIC => Some(Attribute::NoItalic),
+                _ => None,
+            });
+            let added = (style & !self.style).iter().filter_map(|s| match s {
+                Style::BOLD => Some(Attribute::Bold),
+                Style::ITALIC => Some(Attribute::Italic),
+                _ => None,
+            });
+            for attr in removed.chain(added) {
+                queue!(self.buf, SetAttribute(attr))?;
+            }
+            self.style = style;
+        }
+        self.buf.extend_from_slice(text);
+        Ok(())
+    }
+
+    /// Writes the run, resetting attributes after it.
+    pub fn finish(mut self, w: &mut impl Write) -> anyhow::Result<()> {
+        // TODO: this also disables error coloring
+        queue!(self.buf, SetAttribute(Attribute::Reset))?;
+        w.write_all(&self.buf)?;
+        Ok(())
+    }
+}
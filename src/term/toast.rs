@@ -0,0 +1,113 @@
+use std::{collections::VecDeque, io::Write, ops::Range, time::Duration, time::Instant};
+
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, SetAttribute, SetBackgroundColor, SetForegroundColor},
+};
+
+use super::animation::Animation;
+
+/// How long each toast stays up.
+const DURATION: Duration = Duration::from_secs(3);
+/// Most toasts on screen at once; the rest wait their turn.
+const MAX_SHOWN: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+}
+
+struct Toast {
+    level: Level,
+    message: String,
+    /// Started once the toast is on screen.
+    animation: Animation,
+}
+
+/// Short messages stacked in the bottom right corner for a few seconds.
+#[derive(Default)]
+pub struct Toasts {
+    shown: Vec<Toast>,
+    queued: VecDeque<(Level, String)>,
+    /// Rows covered by the last render.
+    drawn: Range<u16>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        self.queued.push_back((level, message.into()));
+        self.update(Instant::now());
+    }
+
+    /// Drops expired toasts and shows queued ones in their place. Returns
+    /// whether anything changed.
+    pub fn update(&mut self, now: Instant) -> bool {
+        let before = self.shown.len();
+        self.shown.retain(|t| !t.animation.is_finished(now));
+        let mut changed = self.shown.len() != before;
+        while self.shown.len() < MAX_SHOWN {
+            let Some((level, message)) = self.queued.pop_front() else {
+                break;
+            };
+            self.shown.push(Toast {
+                level,
+                message,
+                animation: Animation::new(DURATION),
+            });
+            changed = true;
+        }
+        changed
+    }
+
+    /// Time until the next toast expires.
+    pub fn next_change(&self, now: Instant) -> Option<Duration> {
+        self.shown.iter().map(|t| t.animation.remaining(now)).min()
+    }
+
+    /// Rows covered by toasts since the last render, which need clearing
+    /// before drawing anything else there.
+    pub fn drawn(&self) -> Range<u16> {
+        self.drawn.clone()
+    }
+
+    /// Draws the toasts over whatever is on screen, restoring the cursor
+    /// after.
+    pub fn render(
+        &mut self,
+        w: &mut impl Write,
+        (width, height): (u16, u16),
+    ) -> anyhow::Result<()> {
+        let rows = self.shown.len().min(height as usize) as u16;
+        self.drawn = height - rows..height;
+        if self.shown.is_empty() {
+            return Ok(());
+        }
+        let max_len = (width / 2).saturating_sub(2) as usize;
+        queue!(w, cursor::SavePosition)?;
+        for (toast, row) in self.shown.iter().rev().zip(self.drawn.clone().rev()) {
+            let message: String = toast.message.chars().take(max_len).collect();
+            let len = message.chars().count() as u16 + 2;
+            let color = match toast.level {
+                Level::Info => Color::DarkCyan,
+                Level::Warning => Color::DarkYellow,
+            };
+            queue!(
+                w,
+                cursor::MoveTo(width.saturating_sub(len), row),
+                SetBackgroundColor(color),
+                SetForegroundColor(Color::Black),
+            )?;
+            write!(w, " {message} ")?;
+            queue!(
+                w,
+                SetBackgroundColor(Color::Reset),
+                SetForegroundColor(Color::Reset),
+                SetAttribute(Attribute::Reset),
+            )?;
+        }
+        queue!(w, cursor::RestorePosition)?;
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+use std::{io::Write, sync::Arc};
+
+use crossterm::{
+    cursor,
+    event::{KeyCode, KeyEvent},
+    queue,
+    style::{Attribute, SetAttribute},
+    terminal,
+};
+
+use super::{Dimensions, DisplayState};
+
+/// Rows above the list of words.
+const HEADER_ROWS: u16 = 3;
+/// Columns between one column of words and the next.
+const GAP: usize = 3;
+
+/// The uncommon words of a chapter, shown before typing it.
+pub struct Vocabulary {
+    dimensions: Arc<Dimensions>,
+    chapter_name: String,
+    /// Words and how often they appear, most often first.
+    words: Vec<(String, usize)>,
+}
+
+impl DisplayState for Vocabulary {
+    fn dimensions(&self) -> &Dimensions {
+        &self.dimensions
+    }
+
+    fn set_dimensions(&mut self, dimensions: Arc<Dimensions>) {
+        self.dimensions = dimensions;
+    }
+}
+
+impl Vocabulary {
+    pub fn new(
+        dimensions: Arc<Dimensions>,
+        chapter_name: String,
+        words: Vec<(String, usize)>,
+    ) -> Self {
+        Self {
+            dimensions,
+            chapter_name,
+            words,
+        }
+    }
+
+    /// Returns whether to start typing the chapter.
+    pub fn handle_input(&self, event: KeyEvent) -> bool {
+        event.code == KeyCode::Enter
+    }
+
+    pub fn render(&self, w: &mut impl Write) -> anyhow::Result<()> {
+        let col = self.content_starting_col();
+        let width = self.content_width() as usize;
+        let clip = |s: &str| s.chars().take(width).collect::<String>();
+        queue!(w, cursor::Hide, terminal::Clear(terminal::ClearType::All))?;
+
+        queue!(w, cursor::MoveTo(col, 1), SetAttribute(Attribute::Bold))?;
+        w.write_all(clip(&format!("Vocabulary · {}", self.chapter_name)).as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+
+        if self.words.is_empty() {
+            queue!(w, cursor::MoveTo(col, HEADER_ROWS))?;
+            w.write_all(clip("no uncommon words in this chapter").as_bytes())?;
+        }
+
+        // Words run down each column, then on to the next.
+        let count_width = self.words.first().map_or(1, |(_, n)| n.to_string().len());
+        let word_width = self
+            .words
+            .iter()
+            .map(|(word, _)| word.chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(width.saturating_sub(count_width + 1));
+        let column_width = word_width + count_width + 1 + GAP;
+        let columns = ((width + GAP) / column_width).max(1);
+        let rows = self.screen_height().saturating_sub(HEADER_ROWS + 2).max(1) as usize;
+        for (i, (word, count)) in self.words.iter().enumerate().take(rows * columns) {
+            let x = col + ((i / rows) * column_width) as u16;
+            let y = HEADER_ROWS + (i % rows) as u16;
+            let word: String = word.chars().take(word_width).collect();
+            queue!(w, cursor::MoveTo(x, y))?;
+            write!(w, "{word:<word_width$} ")?;
+            queue!(w, SetAttribute(Attribute::Dim))?;
+            write!(w, "{count:>count_width$}")?;
+            queue!(w, SetAttribute(Attribute::Reset))?;
+        }
+
+        queue!(
+            w,
+            cursor::MoveTo(col, self.screen_height().saturating_sub(1)),
+            SetAttribute(Attribute::Dim)
+        )?;
+        w.write_all(clip("enter to start typing, esc to go back").as_bytes())?;
+        queue!(w, SetAttribute(Attribute::Reset))?;
+        w.flush()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,321 @@
+//! The steps a chapter's text goes through between being read out of the
+//! book and being typed. Each one is set up from its own part of the
+//! [`Config`], and left out of the [`pipeline`] when it's turned off.
+
+use std::{cmp::Reverse, collections::BTreeMap, ops::Range};
+
+use crate::{
+    backend::{block::Block, Len},
+    config::Config,
+    filter::{self, Filters, KeySet, Replacements},
+    gutenberg, quotes, rewrap,
+    style::Style,
+};
+
+/// Characters awkward to type on most keyboards, and what to type instead,
+/// unless the config's `normalize` says otherwise.
+#[rustfmt::skip]
+const NORMALIZED: &[(&str, &str)] = &[
+    ("—", "--"),
+    ("…", "..."),
+];
+
+/// Chapter text partway through the pipeline.
+#[derive(Debug, Default)]
+pub struct Draft {
+    pub text: String,
+    /// Styles and the ranges of `text` they cover, which may overlap.
+    pub styles: Vec<(Style, Range<Len>)>,
+    /// Line breaks inside paragraphs, as opposed to between them, until
+    /// [`JoinLines`] has had a look at them.
+    pub breaks: Vec<Len>,
+    /// Blocks and the ranges of `text` they cover.
+    pub blocks: Vec<Block>,
+}
+
+impl Draft {
+    /// Removes `spans`, sorted and non-overlapping byte ranges of the text.
+    /// Style boundaries inside a removed span are moved to where it was.
+    pub fn remove(&mut self, spans: &[Range<usize>]) {
+        let spans: Vec<_> = spans.iter().map(|span| (span.clone(), "")).collect();
+        self.splice(&spans);
+    }
+
+    /// Replaces `spans`, sorted and non-overlapping byte ranges of the text,
+    /// with the text paired with each. Style and block boundaries and breaks
+    /// inside a replaced span are moved to its start.
+    pub fn splice(&mut self, spans: &[(Range<usize>, &str)]) {
+        if spans.is_empty() {
+            return;
+        }
+        let text = &self.text;
+        let mut out = String::with_capacity(text.len());
+        // Where each span started, how long it was and how long its
+        // replacement is.
+        let mut spliced: Vec<(Len, Len, Len)> = Vec::with_capacity(spans.len());
+        let mut last = Len::default();
+        for (span, with) in spans {
+            let kept = &text[last.bytes..span.start];
+            out.push_str(kept);
+            out.push_str(with);
+            let start = last + Len::new(kept.len(), kept.chars().count());
+            let len = Len::new(span.len(), text[span.clone()].chars().count());
+            spliced.push((start, len, Len::new(with.len(), with.chars().count())));
+            last = start + len;
+        }
+        out.push_str(&text[last.bytes..]);
+
+        let map = |pos: Len| {
+            let mut removed = Len::default();
+            let mut added = Len::default();
+            for &(start, len, with) in &spliced {
+                if pos >= start + len {
+                    removed += len;
+                    added += with;
+                } else if pos > start {
+                    return start + added - removed;
+                } else {
+                    break;
+                }
+            }
+            pos + added - removed
+        };
+        for (_, range) in &mut self.styles {
+            *range = map(range.start)..map(range.end);
+        }
+        for block in &mut self.blocks {
+            block.range = map(block.range.start)..map(block.range.end);
+        }
+        for pos in &mut self.breaks {
+            *pos = map(*pos);
+        }
+        self.text = out;
+    }
+}
+
+/// A step in the [`pipeline`].
+pub trait Transform {
+    fn apply(&self, draft: &mut Draft);
+}
+
+/// The steps `config` asks for, in the order they run. Filters go after
+/// replacements so rules can match the text as it'll be typed, and quotes
+/// are swapped only once boilerplate that might sway the guess at which
+/// ones the book uses is gone.
+pub fn pipeline(config: &Config) -> Vec<Box<dyn Transform + '_>> {
+    let mut steps: Vec<Box<dyn Transform + '_>> = vec![Box::new(Normalize(&config.normalize))];
+    if config.join_lines {
+        steps.push(Box::new(JoinLines(&config.hyphenated)));
+    }
+    steps.push(Box::new(Replace(&config.replace)));
+    steps.push(Box::new(Exclude {
+        filters: &config.exclude,
+        gutenberg: config.strip_gutenberg,
+    }));
+    if config.double_quotes {
+        steps.push(Box::new(DoubleQuotes));
+    }
+    if let Some(keys) = &config.keys {
+        steps.push(Box::new(Keys(keys)));
+    }
+    steps
+}
+
+/// Swaps text in [`NORMALIZED`] and the config's own table for plainer
+/// spellings. The config's entries replace built-in ones for the same text,
+/// and where two entries could match at once the longer one wins.
+pub struct Normalize<'a>(pub &'a BTreeMap<String, String>);
+
+impl Transform for Normalize<'_> {
+    fn apply(&self, draft: &mut Draft) {
+        let mut table: Vec<(&str, &str)> = NORMALIZED
+            .iter()
+            .filter(|(from, _)| !self.0.contains_key(*from))
+            .copied()
+            .chain(
+                self.0
+                    .iter()
+                    .map(|(from, with)| (from.as_str(), with.as_str())),
+            )
+            .filter(|(from, with)| !from.is_empty() && from != with)
+            .collect();
+        table.sort_by_key(|(from, _)| Reverse(from.len()));
+
+        let text = &draft.text;
+        let mut spans = Vec::new();
+        let mut i = 0;
+        while let Some(c) = text[i..].chars().next() {
+            match table.iter().find(|(from, _)| text[i..].starts_with(from)) {
+                Some(&(from, with)) => {
+                    spans.push((i..i + from.len(), with));
+                    i += from.len();
+                }
+                None => i += c.len_utf8(),
+            }
+        }
+        draft.splice(&spans);
+    }
+}
+
+/// Joins lines broken mid-paragraph, see [`rewrap`]. Words in the list keep
+/// their hyphen when split at it.
+pub struct JoinLines<'a>(pub &'a [String]);
+
+impl Transform for JoinLines<'_> {
+    fn apply(&self, draft: &mut Draft) {
+        let breaks: Vec<usize> = draft.breaks.iter().map(|pos| pos.bytes).collect();
+        let wraps = rewrap::spans(&draft.text, &breaks, self.0);
+        draft.splice(&wraps);
+        draft.breaks.clear();
+    }
+}
+
+/// Text changed by the config's `replace` rules.
+pub struct Replace<'a>(pub &'a Replacements);
+
+impl Transform for Replace<'_> {
+    fn apply(&self, draft: &mut Draft) {
+        let replacements = self.0.spans(&draft.text);
+        let spans: Vec<_> = replacements
+            .iter()
+            .map(|(span, with)| (span.clone(), with.as_str()))
+            .collect();
+        draft.splice(&spans);
+    }
+}
+
+/// Text removed by the config's `exclude` rules, along with Project
+/// Gutenberg boilerplate if `gutenberg` is set.
+pub struct Exclude<'a> {
+    pub filters: &'a Filters,
+    pub gutenberg: bool,
+}
+
+impl Transform for Exclude<'_> {
+    fn apply(&self, draft: &mut Draft) {
+        let mut spans = self.filters.spans(&draft.text);
+        for span in &spans {
+            log::info!("excluded {:?}", &draft.text[span.clone()]);
+        }
+        if self.gutenberg {
+            spans.extend(gutenberg::spans(&draft.text));
+        }
+        filter::merge_spans(&mut spans);
+        draft.remove(&spans);
+    }
+}
+
+/// Dialogue put in double quotes, in books that use single ones for it.
+pub struct DoubleQuotes;
+
+impl Transform for DoubleQuotes {
+    fn apply(&self, draft: &mut Draft) {
+        // Every character keeps its length, so styles stay where they are.
+        if quotes::uses_single_quotes(&draft.text) {
+            draft.text = quotes::swap(&draft.text);
+        }
+    }
+}
+
+/// Paragraphs needing keys outside the set left out.
+pub struct Keys<'a>(pub &'a KeySet);
+
+impl Transform for Keys<'_> {
+    fn apply(&self, draft: &mut Draft) {
+        let spans = self.0.spans(&draft.text);
+        draft.remove(&spans);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::backend::block;
+
+    fn draft(text: &str, styles: &[(Style, Range<Len>)]) -> Draft {
+        Draft {
+            text: text.to_owned(),
+            styles: styles.to_vec(),
+            breaks: Vec::new(),
+            blocks: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_removes_spans_and_shifts_styles() {
+        let mut draft = draft(
+            "one [1] two [2]",
+            &[
+                (Style::ITALIC, Len::new(0, 0)..Len::new(3, 3)),
+                (Style::BOLD, Len::new(8, 8)..Len::new(11, 11)),
+            ],
+        );
+        draft.blocks = vec![Block::new(
+            Len::new(8, 8)..Len::new(15, 15),
+            block::Kind::Paragraph,
+            None,
+        )];
+        draft.remove(&[3..7, 11..15]);
+        assert_eq!(draft.text, "one two");
+        assert_eq!(draft.styles[0].1, Len::new(0, 0)..Len::new(3, 3));
+        assert_eq!(draft.styles[1].1, Len::new(4, 4)..Len::new(7, 7));
+        assert_eq!(draft.blocks[0].range, Len::new(4, 4)..Len::new(7, 7));
+    }
+
+    #[test]
+    fn it_splices_spans_and_shifts_styles() {
+        let mut draft = draft(
+            "tbe cat, tbe hat",
+            &[
+                (Style::ITALIC, Len::new(0, 0)..Len::new(3, 3)),
+                (Style::BOLD, Len::new(8, 8)..Len::new(11, 11)),
+            ],
+        );
+        draft.splice(&[(0..3, "the"), (3..8, "")]);
+        assert_eq!(draft.text, "the tbe hat");
+        assert_eq!(draft.styles[0].1, Len::new(0, 0)..Len::new(3, 3));
+        assert_eq!(draft.styles[1].1, Len::new(3, 3)..Len::new(6, 6));
+    }
+
+    #[test]
+    fn it_moves_style_boundaries_out_of_removed_spans() {
+        // Bold from the "b", which is removed along with the dash before it.
+        let mut draft = draft("a—b—xyz", &[(Style::BOLD, Len::new(4, 2)..Len::new(11, 7))]);
+        draft.remove(&[0..1, 1..5]);
+        assert_eq!(draft.text, "—xyz");
+        assert_eq!(draft.styles[0].1, Len::new(0, 0)..Len::new(6, 4));
+    }
+
+    #[test]
+    fn lines_are_joined_after_dashes_are_normalized() {
+        let text = "It was a dark and stormy night; the rain fell in torrents—\nexcept \
+                    at occasional intervals…";
+        let mut draft = Draft {
+            breaks: vec![Len::new(60, 58)],
+            ..draft(text, &[(Style::ITALIC, Len::new(61, 59)..Len::new(67, 65))])
+        };
+        assert_eq!(&text[60..61], "\n");
+        Normalize(&BTreeMap::new()).apply(&mut draft);
+        assert_eq!(draft.breaks, [Len::new(59, 59)]);
+        JoinLines(&[]).apply(&mut draft);
+        assert_eq!(
+            draft.text,
+            "It was a dark and stormy night; the rain fell in torrents-- except \
+             at occasional intervals..."
+        );
+        assert_eq!(&draft.text[60..66], "except");
+        assert_eq!(draft.styles[0].1, Len::new(60, 60)..Len::new(66, 66));
+    }
+
+    #[test]
+    fn the_config_adds_to_and_overrides_normalization() {
+        let table = BTreeMap::from([
+            ("—".to_owned(), "—".to_owned()),
+            ("‘".to_owned(), "'".to_owned()),
+            ("——".to_owned(), "---".to_owned()),
+        ]);
+        let mut draft = draft("‘Wait—no——stop…’", &[]);
+        Normalize(&table).apply(&mut draft);
+        assert_eq!(draft.text, "'Wait—no---stop...’");
+    }
+}
@@ -0,0 +1,159 @@
+//! Turning key events into typing, so input methods other than a plain
+//! keyboard can feed the typing area.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::keymap::{self, Keymap};
+
+/// Something to do to the text being typed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Action {
+    /// Type `c`, with `shifted` set if Shift was held for it.
+    Type {
+        c: char,
+        shifted: bool,
+    },
+    Backspace,
+    DeleteWord,
+    /// Go back to the start of the paragraph being typed.
+    RestartParagraph,
+    /// Go back to the last error still standing, to type on from there.
+    RetryError,
+    /// Turn holding the cursor on mistakes on or off.
+    ToggleStopOnError,
+}
+
+/// Decodes key events into [`Action`]s. Implementations may hold on to
+/// events until they can be decoded, and produce any number of actions
+/// from one.
+pub trait Translator: Send {
+    /// `expected` is the next character of the text, if any is left.
+    fn feed(&mut self, event: KeyEvent, expected: Option<char>) -> Vec<Action>;
+}
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum InputMethod {
+    /// Every key typed is taken as it is.
+    #[default]
+    Keyboard,
+    /// Output from a steno engine such as Plover, which puts spaces before
+    /// words rather than after them.
+    Steno,
+}
+
+impl InputMethod {
+    pub fn translator(self, keymap: &Keymap) -> Box<dyn Translator> {
+        let keyboard = Keyboard(keymap.clone());
+        match self {
+            Self::Keyboard => Box::new(keyboard),
+            Self::Steno => Box::new(Steno {
+                keyboard,
+                in_paragraph: false,
+            }),
+        }
+    }
+}
+
+/// Maps keys straight to actions, with the commands bound in the keymap
+/// taking precedence over typing.
+#[derive(Default)]
+pub struct Keyboard(pub Keymap);
+
+impl Translator for Keyboard {
+    fn feed(&mut self, event: KeyEvent, _: Option<char>) -> Vec<Action> {
+        let action = match event {
+            _ if keymap::matches(&self.0.delete_word, &event) => Action::DeleteWord,
+            _ if keymap::matches(&self.0.restart, &event) => Action::RestartParagraph,
+            _ if keymap::matches(&self.0.retry_error, &event) => Action::RetryError,
+            _ if keymap::matches(&self.0.stop_on_error, &event) => Action::ToggleStopOnError,
+            KeyEvent {
+                code: KeyCode::Backspace,
+                ..
+            } => Action::Backspace,
+            KeyEvent {
+                code: KeyCode::Enter,
+                ..
+            } => Action::Type {
+                c: '\n',
+                shifted: false,
+            },
+            KeyEvent {
+                code: KeyCode::Char(c),
+                modifiers,
+                ..
+            } => Action::Type {
+                c,
+                shifted: modifiers.contains(KeyModifiers::SHIFT),
+            },
+            _ => return Vec::new(),
+        };
+        vec![action]
+    }
+}
+
+/// Drops the space a steno engine sends before the first word of each
+/// paragraph, where the text has none. Spaces between words then land
+/// where the text expects them, before the next word instead of after the
+/// last.
+#[derive(Default)]
+pub struct Steno {
+    keyboard: Keyboard,
+    /// Whether anything has been typed since the last line break.
+    in_paragraph: bool,
+}
+
+impl Translator for Steno {
+    fn feed(&mut self, event: KeyEvent, expected: Option<char>) -> Vec<Action> {
+        let actions = self.keyboard.feed(event, expected);
+        actions
+            .into_iter()
+            .filter(|action| match *action {
+                Action::Type { c: ' ', .. } => {
+                    self.in_paragraph || expected.is_some_and(char::is_whitespace)
+                }
+                Action::Type { c, .. } => {
+                    self.in_paragraph = c != '\n';
+                    true
+                }
+                Action::RestartParagraph => {
+                    self.in_paragraph = false;
+                    true
+                }
+                Action::Backspace
+                | Action::DeleteWord
+                | Action::RetryError
+                | Action::ToggleStopOnError => true,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::keymap::Binding;
+
+    #[test]
+    fn steno_drops_leading_spaces() {
+        let mut steno = Steno::default();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        assert!(steno.feed(key(' '), Some('T')).is_empty());
+        assert_eq!(steno.feed(key('T'), Some('T')).len(), 1);
+        assert_eq!(steno.feed(key(' '), Some(' ')).len(), 1);
+    }
+
+    #[test]
+    fn rebound_commands_are_typed_no_more() {
+        let mut keyboard = Keyboard(Keymap {
+            restart: vec![Binding::try_from("ctrl+t".to_owned()).unwrap()],
+            ..Keymap::default()
+        });
+        let ctrl = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL);
+        assert_eq!(keyboard.feed(ctrl('t'), None), [Action::RestartParagraph]);
+        assert_ne!(keyboard.feed(ctrl('r'), None), [Action::RestartParagraph]);
+        assert_eq!(keyboard.feed(ctrl('w'), None), [Action::DeleteWord]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Words in a chapter that don't turn up much in everyday English, to look
+//! over before typing it.
+
+use std::collections::{BTreeMap, HashSet};
+
+/// Words shorter than this are left out; they're rarely hard to spell.
+const MIN_LEN: usize = 4;
+
+/// Roughly the most frequent thousand words of written English, along with
+/// their common inflections through [`stem`].
+const COMMON: &str = "
+the of and to a in is it you that he was for on are with as his they be at
+one have this from or had by hot word but what some we can out other were
+all there when up use your how said an each she which do their time if will
+way about many then them write would like so these her long make thing see
+him two has look more day could go come did number sound no most people my
+over know water than call first who may down side been now find any new work
+part take get place made live where after back little only round man year
+came show every good me give our under name very through just form sentence
+great think say help low line differ turn cause much mean before move right
+boy old too same tell does set three want air well also play small end put
+home read hand port large spell add even land here must big high such follow
+act why ask men change went light kind off need house picture try us again
+animal point mother world near build self earth father head stand own page
+should country found answer school grow study still learn plant cover food
+sun four between state keep eye never last let thought city tree cross farm
+hard start might story saw far sea draw left late run don't while press close
+night real life few north open seem together next white children begin got
+walk example ease paper group always music those both mark often letter until
+mile river car feet care second book carry took science eat room friend began
+idea fish mountain stop once base hear horse cut sure watch color colour face
+wood main enough plain girl usual young ready above ever red list though feel
+talk bird soon body dog family direct pose leave song measure door product
+black short numeral class wind question happen complete ship area half rock
+order fire south problem piece told knew pass since top whole king space
+heard best hour better true during hundred five remember step early hold west
+ground interest reach fast verb sing listen six table travel less morning ten
+simple several vowel toward war lay against pattern slow center centre love
+person money serve appear road map rain rule govern pull cold notice voice
+unit power town fine certain fly fall lead cry dark machine note wait plan
+figure star box noun field rest correct able pound done beauty drive stood
+contain front teach week final gave green quick develop ocean warm free
+minute strong special mind behind clear tail produce fact street inch
+multiply nothing course stay wheel full force blue object decide surface deep
+moon island foot system busy test record boat common gold possible plane
+stead dry wonder laugh thousand ago ran check game shape equate miss brought
+heat snow tire bring distant fill east paint language among grand ball yet
+wave drop heart present heavy dance engine position arm wide sail material
+size vary settle speak weight general ice matter circle pair include divide
+syllable felt perhaps pick sudden count square reason length represent art
+subject region energy hunt probable bed brother egg ride cell believe fraction
+forest sit race window store summer train sleep prove lone exercise wall
+catch mount wish sky board joy winter sat written wild instrument kept glass
+grass cow job edge sign visit past soft fun bright gas weather month million
+bear finish happy hope flower clothe strange gone jump baby eight village
+meet root buy raise solve metal whether push seven paragraph third shall held
+hair describe cook floor either result burn hill safe cat century consider
+type law bit coast copy phrase silent tall sand soil roll temperature finger
+industry value fight lie beat excite natural view sense ear else quite broke
+case middle kill son lake moment scale loud spring observe child straight
+consonant nation dictionary milk speed method organ pay age section dress
+cloud surprise quiet stone tiny climb cool design poor lot experiment bottom
+key iron single stick flat twenty skin smile crease hole trade melody trip
+office receive row mouth exact symbol die least trouble shout except wrote
+seed tone join suggest clean break lady yard rise bad blow oil blood touch
+grew cent mix team wire cost lost brown wear garden equal sent choose fell
+fit flow fair bank collect save control decimal gentle woman captain practice
+separate difficult doctor please protect noon whose locate ring character
+insect caught period indicate radio spoke atom human history effect electric
+expect crop modern element hit student corner party supply bone rail imagine
+provide agree thus capital won't chair danger fruit rich thick soldier
+process operate guess necessary sharp wing create neighbor neighbour wash bat
+rather crowd corn compare poem string bell depend meat rub tube famous dollar
+stream fear sight thin triangle planet hurry chief colony clock mine tie
+enter major fresh search send yellow gun allow print dead spot desert suit
+current lift rose continue block chart hat sell success company subtract
+event particular deal swim term opposite wife shoe shoulder spread arrange
+camp invent cotton born determine quart nine truck noise level chance gather
+shop stretch throw shine property column molecule select wrong gray grey
+repeat require broad prepare salt nose plural anger claim continent oxygen
+sugar death pretty skill women season solution magnet silver thank branch
+match suffix especially fig afraid huge sister steel discuss forward similar
+guide experience score apple bought led pitch coat mass card band rope slip
+win dream evening condition feed tool total basic smell valley nor double
+seat arrive master track parent shore division sheet substance favor favour
+connect post spend chord fat glad original share station dad bread charge
+proper bar offer segment slave duck instant market degree populate chick dear
+enemy reply drink occur support speech nature range steam motion path liquid
+log meant quotient teeth shell neck sir madam lord lady mr mrs miss upon
+shall thee thou thy hath went toward towards nothing something anything
+everything someone anyone everyone nobody myself yourself himself herself
+itself ourselves themselves without within whom whose whatever however
+perhaps indeed yes oh yet around across along behind beyond beside
+besides beneath among against about above below into onto upon till
+unless although because though whether since already almost quite rather
+really very much more most less least again once twice ever never always
+often sometimes soon later today tonight tomorrow yesterday away home else
+instead together alone enough little every another such same own other
+";
+
+/// How often each uncommon word of `text` appears, most often first, up to
+/// `limit` of them. Words are counted regardless of case, and shown as they
+/// were written unless they were written in more than one way.
+pub fn uncommon(text: &str, limit: usize) -> Vec<(String, usize)> {
+    let common: HashSet<&str> = COMMON.split_whitespace().collect();
+    let mut counts: BTreeMap<String, (String, usize)> = BTreeMap::new();
+    for word in words(text) {
+        let word = word
+            .strip_suffix("'s")
+            .or_else(|| word.strip_suffix("’s"))
+            .unwrap_or(word);
+        let key = word.to_lowercase();
+        if key.chars().count() < MIN_LEN || stem(&key).any(|s| common.contains(s)) {
+            continue;
+        }
+        let (shown, count) = counts
+            .entry(key.clone())
+            .or_insert_with(|| (word.to_owned(), 0));
+        if shown != word {
+            *shown = key;
+        }
+        *count += 1;
+    }
+    let mut words: Vec<(String, usize)> = counts.into_values().collect();
+    words.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    words.truncate(limit);
+    words
+}
+
+/// Runs of letters in `text`, along with apostrophes between them.
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !(c.is_alphabetic() || c == '\'' || c == '’'))
+        .map(|word| word.trim_matches(|c| c == '\'' || c == '’'))
+        .filter(|word| !word.is_empty() && !word.contains(|c: char| c.is_numeric()))
+}
+
+/// `word` and what it might be an inflection of, for looking up in
+/// [`COMMON`].
+fn stem(word: &str) -> impl Iterator<Item = &str> {
+    let stems = ["s", "es", "ed", "d", "ing", "ly", "er", "est"]
+        .into_iter()
+        .filter_map(move |suffix| word.strip_suffix(suffix));
+    std::iter::once(word).chain(stems)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn common_words_and_their_inflections_are_left_out() {
+        let text = "The Jabberwock, with eyes of flame, came whiffling through the \
+                    tulgey wood, and burbled as it came! The Jabberwock's eyes, \
+                    the Jabberwock's jaws.";
+        let words = uncommon(text, 10);
+        assert_eq!(words[0], ("Jabberwock".to_owned(), 3));
+        assert!(words
+            .iter()
+            .all(|(word, _)| word != "came" && word != "eyes"));
+        assert!(words.iter().any(|(word, _)| word == "whiffling"));
+    }
+}
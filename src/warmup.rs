@@ -0,0 +1,378 @@
+//! Short drills to loosen up on before typing a chapter.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// How long a warm-up lasts.
+pub const DURATION: Duration = Duration::from_secs(30);
+/// Words in a drill; more than anyone types in [`DURATION`].
+const DRILL_WORDS: usize = 80;
+/// How often a word is picked for a weak pair of letters rather than at
+/// random, one in this many.
+const WEAK_EVERY: usize = 3;
+
+const COMMON_WORDS: &[&str] = &[
+    "the",
+    "of",
+    "and",
+    "to",
+    "in",
+    "that",
+    "it",
+    "was",
+    "he",
+    "for",
+    "on",
+    "are",
+    "as",
+    "with",
+    "his",
+    "they",
+    "at",
+    "be",
+    "this",
+    "have",
+    "from",
+    "or",
+    "one",
+    "had",
+    "by",
+    "word",
+    "but",
+    "not",
+    "what",
+    "all",
+    "were",
+    "we",
+    "when",
+    "your",
+    "can",
+    "said",
+    "there",
+    "use",
+    "each",
+    "which",
+    "she",
+    "do",
+    "how",
+    "their",
+    "if",
+    "will",
+    "up",
+    "other",
+    "about",
+    "out",
+    "many",
+    "then",
+    "them",
+    "these",
+    "so",
+    "some",
+    "her",
+    "would",
+    "make",
+    "like",
+    "him",
+    "into",
+    "time",
+    "has",
+    "look",
+    "two",
+    "more",
+    "write",
+    "go",
+    "see",
+    "number",
+    "no",
+    "way",
+    "could",
+    "people",
+    "my",
+    "than",
+    "first",
+    "water",
+    "been",
+    "call",
+    "who",
+    "oil",
+    "its",
+    "now",
+    "find",
+    "long",
+    "down",
+    "day",
+    "did",
+    "get",
+    "come",
+    "made",
+    "may",
+    "part",
+    "over",
+    "new",
+    "sound",
+    "take",
+    "only",
+    "little",
+    "work",
+    "know",
+    "place",
+    "year",
+    "live",
+    "back",
+    "give",
+    "most",
+    "very",
+    "after",
+    "thing",
+    "our",
+    "just",
+    "name",
+    "good",
+    "sentence",
+    "man",
+    "think",
+    "say",
+    "great",
+    "where",
+    "help",
+    "through",
+    "much",
+    "before",
+    "line",
+    "right",
+    "too",
+    "mean",
+    "old",
+    "any",
+    "same",
+    "tell",
+    "boy",
+    "follow",
+    "came",
+    "want",
+    "show",
+    "also",
+    "around",
+    "form",
+    "three",
+    "small",
+    "set",
+    "put",
+    "end",
+    "does",
+    "another",
+    "well",
+    "large",
+    "must",
+    "big",
+    "even",
+    "such",
+    "because",
+    "turn",
+    "here",
+    "why",
+    "ask",
+    "went",
+    "men",
+    "read",
+    "need",
+    "land",
+    "different",
+    "home",
+    "us",
+    "move",
+    "try",
+    "kind",
+    "hand",
+    "picture",
+    "again",
+    "change",
+    "off",
+    "play",
+    "spell",
+    "air",
+    "away",
+    "animal",
+    "house",
+    "point",
+    "page",
+    "letter",
+    "mother",
+    "answer",
+    "found",
+    "study",
+    "still",
+    "learn",
+    "should",
+    "world",
+    "high",
+    "every",
+    "near",
+    "add",
+    "food",
+    "between",
+    "own",
+    "below",
+    "country",
+    "plant",
+    "last",
+    "school",
+    "father",
+    "keep",
+    "tree",
+    "never",
+    "start",
+    "city",
+    "earth",
+    "eye",
+    "light",
+    "thought",
+    "head",
+    "under",
+    "story",
+    "saw",
+    "left",
+    "few",
+    "while",
+    "along",
+    "might",
+    "close",
+    "something",
+    "seem",
+    "next",
+    "hard",
+    "open",
+    "example",
+    "begin",
+    "life",
+    "always",
+    "those",
+    "both",
+    "paper",
+    "together",
+    "got",
+    "group",
+    "often",
+    "run",
+    "important",
+    "until",
+    "children",
+    "side",
+    "feet",
+    "car",
+    "mile",
+    "night",
+    "walk",
+    "white",
+    "sea",
+    "began",
+    "grow",
+    "took",
+    "river",
+    "four",
+    "carry",
+    "state",
+    "once",
+    "book",
+    "hear",
+    "stop",
+    "without",
+    "second",
+    "later",
+    "miss",
+    "idea",
+    "enough",
+    "eat",
+    "face",
+    "watch",
+    "far",
+    "quick",
+    "really",
+    "almost",
+    "let",
+    "above",
+    "girl",
+    "sometimes",
+    "mountain",
+    "cut",
+    "young",
+    "talk",
+    "soon",
+    "list",
+    "song",
+    "being",
+    "leave",
+    "family",
+    "body",
+    "jump",
+    "zero",
+    "quiet",
+    "exact",
+    "puzzle",
+    "wolf",
+    "knife",
+    "whose",
+    "lazy",
+    "fox",
+    "vowel",
+    "query",
+    "oxygen",
+];
+
+/// Small xorshift generator; drills don't need anything better.
+struct Rng(u64);
+
+impl Rng {
+    fn below(&mut self, n: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % n as u64) as usize
+    }
+}
+
+/// A line of common words with every few being one that contains one of
+/// the `weak` pairs of letters, seeded by `seed`.
+pub fn drill(weak: &[&str], seed: u64) -> String {
+    let mut rng = Rng(seed | 1);
+    let practice: Vec<&str> = COMMON_WORDS
+        .iter()
+        .copied()
+        .filter(|word| weak.iter().any(|pair| word.contains(pair)))
+        .collect();
+    let mut words = Vec::with_capacity(DRILL_WORDS);
+    for i in 0..DRILL_WORDS {
+        let from = match practice.is_empty() || i % WEAK_EVERY != 0 {
+            true => COMMON_WORDS,
+            false => &practice[..],
+        };
+        words.push(from[rng.below(from.len())]);
+    }
+    words.join(" ")
+}
+
+/// Outcome of a warm-up, kept apart from the sessions typed in books.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Warmup {
+    /// Seconds since the Unix epoch when the warm-up ended.
+    pub timestamp: u64,
+    pub chars: usize,
+    pub elapsed_ms: u64,
+    pub keystrokes: usize,
+    pub mistakes: usize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn drills_practice_weak_pairs() {
+        let drill = drill(&["qu"], 7);
+        let words: Vec<_> = drill.split(' ').collect();
+        assert_eq!(words.len(), DRILL_WORDS);
+        let practiced = words.iter().filter(|w| w.contains("qu")).count();
+        assert!(practiced >= DRILL_WORDS / WEAK_EVERY);
+    }
+}